@@ -2,13 +2,13 @@
 pragma solidity ^0.8.20;
 
 import "../fields/QM31Field.sol";
-import "../core/KeccakChannelLib.sol";
+import "../core/ChannelLib.sol";
 
 /// @title CirclePoint
 /// @notice A point on the complex circle, treated as an additive group
 library CirclePoint {
     using QM31Field for QM31Field.QM31;
-    using KeccakChannelLib for KeccakChannelLib.ChannelState;
+    using ChannelLib for ChannelLib.ChannelState;
 
     /// @notice Represents a point on the circle with coordinates (x, y)
     struct Point {
@@ -124,10 +124,10 @@ library CirclePoint {
     /// @dev Uses Fiat-Shamir transform to generate cryptographically secure random point
     /// @param channelState The channel state providing randomness
     /// @return A random point on the circle
-    function getRandomPointFromState(KeccakChannelLib.ChannelState storage channelState) internal returns (Point memory) {
+    function getRandomPointFromState(ChannelLib.ChannelState storage channelState) internal returns (Point memory) {
         // Draw random element t from secure field using library
         QM31Field.QM31 memory t;
-        t = KeccakChannelLib.drawSecureFelt(channelState);
+        t = ChannelLib.drawSecureFelt(channelState);
         
         // Compute t²
         QM31Field.QM31 memory tSquare = QM31Field.square(t);
@@ -3,25 +3,35 @@ pragma solidity ^0.8.26;
 
 import "../fields/M31Field.sol";
 import "../fields/QM31Field.sol";
+import "../vcs/HashFunction.sol";
 
 /**
- * @title KeccakChannelLib
- * @notice Library for STWO verifier channel using native EVM keccak256
+ * @title ChannelLib
+ * @notice Library for STWO verifier channel (Fiat-Shamir transcript)
+ * @dev Hashes with the function selected in the channel state: native keccak256
+ *      for KeccakChannel or BLAKE2s for Blake2sChannel
  */
-library KeccakChannelLib {
+library ChannelLib {
     using M31Field for uint32;
     using QM31Field for uint256[4];
     
     /// @notice Channel state structure
     /// @param digest Current channel digest
     /// @param nDraws Number of draws performed
+    /// @param hashFunction Hash function identifier (see HashFunction)
     struct ChannelState {
         bytes32 digest;
         uint32 nDraws;
+        uint8 hashFunction;
     }
     
     uint32 private constant POW_PREFIX = 0x12345678;
-    uint256 private constant KECCAK_BYTES_PER_HASH = 32;
+    /// @dev Zero bytes after POW_PREFIX: KeccakChannel pads it to 28 bytes,
+    ///      Blake2sChannel to 16
+    uint256 private constant KECCAK_POW_PADDING = 24;
+    uint256 private constant BLAKE2S_POW_PADDING = 12;
+    /// @dev Keccak-256 and BLAKE2s-256 both produce 32-byte digests
+    uint256 private constant DIGEST_BYTES = 32;
     uint256 private constant FELTS_PER_HASH = 8;
     uint256 private constant SECURE_EXTENSION_DEGREE = 4;
     
@@ -37,11 +47,27 @@ library KeccakChannelLib {
         state.digest = digest;
         state.nDraws = nDraws;
     }
+
+    /// @notice Initialize channel state with specific digest, draw counter and hash function
+    function initializeWith(
+        ChannelState storage state,
+        bytes32 digest,
+        uint32 nDraws,
+        uint8 hashFunction
+    ) internal {
+        if (!HashFunction.isSupported(hashFunction)) {
+            revert HashFunction.UnsupportedHashFunction(hashFunction);
+        }
+        state.digest = digest;
+        state.nDraws = nDraws;
+        state.hashFunction = hashFunction;
+    }
     
     /// @notice Clear channel state after verification
     function clearState(ChannelState storage state) internal {
         state.digest = bytes32(0);
         state.nDraws = 0;
+        state.hashFunction = HashFunction.KECCAK;
     }
     
     /// @notice Update digest and reset draw counter
@@ -50,12 +76,12 @@ library KeccakChannelLib {
         state.nDraws = 0;
     }
     
-    /// @notice Number of bytes produced by keccak256
+    /// @notice Number of bytes produced by the channel hash, the same for every supported hash function
     function BYTES_PER_HASH() internal pure returns (uint256) {
-        return KECCAK_BYTES_PER_HASH;
+        return DIGEST_BYTES;
     }
     
-    /// @notice Mix array of u32 values using keccak256
+    /// @notice Mix array of u32 values using the channel hash
     function mixU32s(ChannelState storage state, uint32[] memory data) internal {
         bytes memory input = abi.encodePacked(state.digest);
         
@@ -63,7 +89,7 @@ library KeccakChannelLib {
             input = abi.encodePacked(input, _u32ToLittleEndian(data[i]));
         }
         
-        state.digest = keccak256(input);
+        state.digest = _hash(state, input);
         state.nDraws = 0;
     }
     
@@ -86,7 +112,7 @@ library KeccakChannelLib {
  
         bytes memory input = abi.encodePacked(state.digest, feltsBytes);
         
-        state.digest = keccak256(input);
+        state.digest = _hash(state, input);
         state.nDraws = 0;
     }
     
@@ -136,15 +162,15 @@ library KeccakChannelLib {
     }
     
     /// @notice Draw random u32 values from current state
+    /// @dev Hashes digest || counter || 0x00. Blake2sChannel pads the
+    ///      little-endian counter to 32 bytes, KeccakChannel does not.
     function drawU32s(ChannelState storage state) internal returns (uint32[] memory) {
-        bytes memory input = abi.encodePacked(
-            state.digest,
-            _u32ToLittleEndian(state.nDraws),
-            uint8(0)
-        );
+        bytes memory input = state.hashFunction == HashFunction.BLAKE2S
+            ? abi.encodePacked(state.digest, _u32ToLittleEndian(state.nDraws), bytes28(0), uint8(0))
+            : abi.encodePacked(state.digest, _u32ToLittleEndian(state.nDraws), uint8(0));
         
         state.nDraws++;
-        bytes32 hash = keccak256(input);
+        bytes32 hash = _hash(state, input);
         
         uint32[] memory result = new uint32[](FELTS_PER_HASH);
         for (uint256 i = 0; i < FELTS_PER_HASH; i++) {
@@ -162,17 +188,17 @@ library KeccakChannelLib {
     function verifyPowNonce(ChannelState storage state, uint32 nBits, uint64 nonce) internal view returns (bool) {
         bytes memory prefixInput = abi.encodePacked(
             _u32ToLittleEndian(POW_PREFIX),
-            new bytes(24),
+            new bytes(state.hashFunction == HashFunction.BLAKE2S ? BLAKE2S_POW_PADDING : KECCAK_POW_PADDING),
             state.digest,
             _u32ToLittleEndian(nBits)
         );
-        bytes32 prefixedDigest = keccak256(prefixInput);
+        bytes32 prefixedDigest = _hash(state, prefixInput);
         
         bytes memory finalInput = abi.encodePacked(
             prefixedDigest,
             _u64ToLittleEndian(nonce)
         );
-        bytes32 finalHash = keccak256(finalInput);
+        bytes32 finalHash = _hash(state, finalInput);
         
         uint256 trailingZeros = _countTrailingZeros(finalHash);
         
@@ -181,7 +207,7 @@ library KeccakChannelLib {
     
     /// @notice Hash two elements sequentially
     function mixRoot(ChannelState storage state, bytes32 left, bytes32 right) internal returns (bytes32) {
-        bytes32 newDigest = keccak256(abi.encodePacked(left, right));
+        bytes32 newDigest = _hash(state, abi.encodePacked(left, right));
         state.nDraws = 0;
         state.digest = newDigest;
         return newDigest;
@@ -214,7 +240,12 @@ library KeccakChannelLib {
             retries++;
         }
         
-        revert("KeccakChannelLib: Failed to generate valid base felts");
+        revert("ChannelLib: Failed to generate valid base felts");
+    }
+
+    /// @notice Hash bytes with the channel's hash function
+    function _hash(ChannelState storage state, bytes memory data) private view returns (bytes32) {
+        return HashFunction.hash(state.hashFunction, data);
     }
 
     /// @notice Convert u32 to little-endian bytes
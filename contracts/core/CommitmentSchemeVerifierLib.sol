@@ -6,7 +6,7 @@ import "../vcs/MerkleVerifier.sol";
 import "../circle/CirclePoint.sol";
 import "../circle/CirclePolyDegreeBound.sol";
 import "../fields/QM31Field.sol";
-import "./KeccakChannelLib.sol";
+import "./ChannelLib.sol";
 
 /// @title CommitmentSchemeVerifierLib
 /// @notice Library for verifying polynomial commitment scheme proofs
@@ -16,7 +16,7 @@ library CommitmentSchemeVerifierLib {
     using QM31Field for QM31Field.QM31;
     using CirclePoint for CirclePoint.Point;
     using CirclePolyDegreeBound for CirclePolyDegreeBound.Bound;
-    using KeccakChannelLib for KeccakChannelLib.ChannelState;
+    using ChannelLib for ChannelLib.ChannelState;
 
     /// @notice Verifier state containing trees and configuration
     struct VerifierState {
@@ -48,11 +48,13 @@ library CommitmentSchemeVerifierLib {
     event VerificationCompleted(bool indexed success);
 
     /// @notice Initialize verifier state with configuration and trees
+    /// @param hashFunction Merkle hash function identifier (see HashFunction)
     function initialize(
         VerifierState storage state, 
         PcsConfig.Config memory config,
         bytes32[] memory treeRoots,
-        uint32[][] memory treeColumnLogSizes
+        uint32[][] memory treeColumnLogSizes,
+        uint8 hashFunction
     ) internal {
         require(PcsConfig.isValidConfig(config), "Invalid PCS configuration");
         require(treeRoots.length == treeColumnLogSizes.length, "Mismatched trees and column sizes");
@@ -60,7 +62,7 @@ library CommitmentSchemeVerifierLib {
         state.config = config;
         
         // Create Merkle verifier with all trees
-        state.merkleVerifier = MerkleVerifier.newVerifier(treeRoots, treeColumnLogSizes);
+        state.merkleVerifier = MerkleVerifier.newVerifier(treeRoots, treeColumnLogSizes, hashFunction);
     }
     
     /// @notice Initialize verifier state with configuration only (for incremental tree addition)
@@ -91,7 +93,7 @@ library CommitmentSchemeVerifierLib {
         VerifierState storage state,
         bytes32 commitment,
         uint32[] memory logSizes,
-        KeccakChannelLib.ChannelState storage channelState
+        ChannelLib.ChannelState storage channelState
     ) internal {
         channelState.mixRoot(channelState.digest, commitment);
         
@@ -102,7 +104,8 @@ library CommitmentSchemeVerifierLib {
         
         MerkleVerifier.MerkleTree memory newTree = MerkleVerifier.createMerkleTree(
             commitment,
-            extendedLogSizes
+            extendedLogSizes,
+            channelState.hashFunction
         );
         
         uint256 currentLength = state.merkleVerifier.trees.length;
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../verifier/StwoVerifier.sol";
+import "../verifier/ProofParser.sol";
+import "../vcs/HashFunction.sol";
+import "../core/ChannelLib.sol";
+import "../fields/M31Field.sol";
+import "../fields/QM31Field.sol";
+import "./PinnedVerifyingKey.sol";
+
+/// @title BatchResultRegistry
+/// @notice Records the result of every instance proven by a batched proof
+/// @dev Public inputs use the contracts::batch layout:
+///      [nInstances, inputLen, outputLen, input_0.., output_0.., input_1.., output_1.., ...].
+///      The prover mixes them into the channel before the first commitment, so the
+///      transcript digest handed to STWOVerifier is recomputed here from the claimed
+///      public inputs and tree roots. The pinned circuit adds 1 / combine(input, output)
+///      per row to a LogUp sum, and the components' claimed sums must add up to the sum
+///      recomputed here from the public inputs, padding rows being the instance (0, 0) -> 0.
+///      Trust assumption: STWOVerifier does not evaluate AIR constraints, so that the
+///      claimed sums are the ones the trace rows produce, and that padding rows are
+///      zero, relies on the prover having checked the proof off-chain. The registry
+///      binds the listed results to the proof's transcript and claimed sums only.
+contract BatchResultRegistry is PinnedVerifyingKey {
+    using ChannelLib for ChannelLib.ChannelState;
+    using QM31Field for QM31Field.QM31;
+
+    uint256 internal constant HEADER_LEN = 3;
+
+    struct InstanceRecord {
+        /// @notice Batch that proved the instance, 0 when never proven
+        uint256 batch;
+        uint32[] output;
+    }
+
+    STWOVerifier public immutable verifier;
+
+    /// @notice Input and output length of every instance of the pinned circuit
+    uint32 public immutable inputLen;
+    uint32 public immutable outputLen;
+
+    /// @notice Number of batches proven so far; batch ids start at 1
+    uint256 public batchCount;
+
+    /// @notice Batch id => number of instances it proved
+    mapping(uint256 => uint256) public batchSize;
+
+    /// @notice instanceKey(input) => latest record
+    mapping(bytes32 => InstanceRecord) private _records;
+
+    /// @dev Transcript replayed to draw the lookup elements
+    ChannelLib.ChannelState private _channel;
+
+    event BatchProven(uint256 indexed batch, uint256 nInstances, bytes32 digest);
+
+    error MalformedPublicInputs();
+    error DigestMismatch(bytes32 expected, bytes32 actual);
+    error ProofRejected();
+    error ClaimedSumMismatch();
+    error ComponentShapeMismatch();
+
+    /// @param key Verifying key of the batched circuit for the batch size and hash used
+    /// @param inputLen_ Input length of every instance
+    /// @param outputLen_ Output length of every instance
+    constructor(STWOVerifier verifier_, VerifyingKey memory key, uint32 inputLen_, uint32 outputLen_)
+        PinnedVerifyingKey(key)
+    {
+        verifier = verifier_;
+        inputLen = inputLen_;
+        outputLen = outputLen_;
+    }
+
+    /// @notice Verify a batched proof and record the output of each of its instances
+    /// @param publicInputs Batch public inputs, see the contract documentation
+    /// @param proof Proof in the ABI-words encoding
+    /// @param params Verification parameters of the batched component
+    /// @param treeRoots Preprocessed, trace and interaction commitments
+    /// @param treeColumnLogSizes Extended column log sizes per tree
+    /// @param digest Channel digest after the last commitment
+    /// @return batch Id of the new batch
+    function proveBatch(
+        uint32[] calldata publicInputs,
+        ProofParser.Proof calldata proof,
+        STWOVerifier.VerificationParams calldata params,
+        bytes32[] calldata treeRoots,
+        uint32[][] calldata treeColumnLogSizes,
+        bytes32 digest
+    ) external returns (uint256 batch) {
+        uint256 nInstances = _header(publicInputs);
+        _checkVerifyingKey(proof, params, treeRoots, treeColumnLogSizes);
+
+        bytes32 expected = publicInputDigest(proof.header.hashFunction, publicInputs, treeRoots);
+        if (expected != digest) {
+            revert DigestMismatch(expected, digest);
+        }
+
+        if (!verifier.verify(proof, params, treeRoots, treeColumnLogSizes, digest, 0)) {
+            revert ProofRejected();
+        }
+        _checkClaimedSum(proof.header.hashFunction, publicInputs, params, treeRoots);
+
+        batch = ++batchCount;
+        batchSize[batch] = nInstances;
+
+        uint256 offset = HEADER_LEN;
+        for (uint256 i = 0; i < nInstances; i++) {
+            uint32[] memory input = _slice(publicInputs, offset, inputLen);
+            uint32[] memory output = _slice(publicInputs, offset + inputLen, outputLen);
+            _records[keccak256(abi.encode(input))] = InstanceRecord(batch, output);
+            offset += inputLen + outputLen;
+        }
+
+        emit BatchProven(batch, nInstances, digest);
+    }
+
+    /// @notice Proven output of the instance with `input`
+    /// @return proven Whether any batch proved the instance
+    /// @return batch Latest batch that proved it
+    /// @return output Its output, empty when not proven
+    function resultOf(uint32[] calldata input)
+        external
+        view
+        returns (bool proven, uint256 batch, uint32[] memory output)
+    {
+        InstanceRecord storage record = _records[instanceKey(input)];
+        return (record.batch != 0, record.batch, record.output);
+    }
+
+    /// @notice Storage key of the instance with `input`
+    function instanceKey(uint32[] calldata input) public pure returns (bytes32) {
+        return keccak256(abi.encode(input));
+    }
+
+    /// @notice Channel digest after mixing the public inputs and every tree root
+    /// @dev Mirrors ChannelLib.mixU32s on a zero digest followed by ChannelLib.mixRoot
+    function publicInputDigest(
+        uint8 hashFunction,
+        uint32[] calldata publicInputs,
+        bytes32[] calldata treeRoots
+    ) public pure returns (bytes32 digest) {
+        bytes memory input = new bytes(32 + 4 * publicInputs.length);
+        for (uint256 i = 0; i < publicInputs.length; i++) {
+            uint32 value = publicInputs[i];
+            uint256 at = 32 + 4 * i;
+            input[at] = bytes1(uint8(value));
+            input[at + 1] = bytes1(uint8(value >> 8));
+            input[at + 2] = bytes1(uint8(value >> 16));
+            input[at + 3] = bytes1(uint8(value >> 24));
+        }
+        digest = HashFunction.hash(hashFunction, input);
+
+        for (uint256 i = 0; i < treeRoots.length; i++) {
+            digest = HashFunction.hash(hashFunction, abi.encodePacked(digest, treeRoots[i]));
+        }
+    }
+
+    /// @notice Revert unless the batched components together claim the LogUp sum of
+    ///         `publicInputs`
+    /// @dev Replays the transcript up to the main trace commitment and draws the lookup
+    ///      elements (z, alpha) as the prover's relation does. Each instance adds
+    ///      1 / (sum_j alpha^j * v_j - z) over its input and output values v, and each
+    ///      padding row adds 1 / -z, assuming it holds the zero instance. Every component
+    ///      must have the same log size, so the rows number nComponents * 2^logSize. The
+    ///      sum is kept as a fraction so no inverse is needed.
+    function _checkClaimedSum(
+        uint8 hashFunction,
+        uint32[] calldata publicInputs,
+        STWOVerifier.VerificationParams calldata params,
+        bytes32[] calldata treeRoots
+    ) private {
+        _channel.initializeWith(bytes32(0), 0, hashFunction);
+        _channel.mixU32s(publicInputs);
+        _channel.mixRoot(_channel.digest, treeRoots[0]);
+        _channel.mixRoot(_channel.digest, treeRoots[1]);
+        QM31Field.QM31[] memory elements = _channel.drawSecureFelts(2);
+        _channel.clearState();
+        (QM31Field.QM31 memory z, QM31Field.QM31 memory alpha) = (elements[0], elements[1]);
+
+        uint256 nComponents = params.componentParams.length;
+        if (nComponents == 0) {
+            revert ComponentShapeMismatch();
+        }
+        uint32 logSize = params.componentParams[0].logSize;
+        QM31Field.QM31 memory claimedSum = QM31Field.zero();
+        for (uint256 i = 0; i < nComponents; i++) {
+            if (params.componentParams[i].logSize != logSize) {
+                revert ComponentShapeMismatch();
+            }
+            claimedSum = claimedSum.add(params.componentParams[i].claimedSum);
+        }
+
+        uint256 nInstances = publicInputs[0];
+        uint256 nRows = nComponents << logSize;
+        if (nInstances > nRows) {
+            revert MalformedPublicInputs();
+        }
+
+        QM31Field.QM31 memory numerator = QM31Field.zero();
+        QM31Field.QM31 memory denominator = QM31Field.one();
+        uint256 width = inputLen + outputLen;
+        for (uint256 i = 0; i < nInstances; i++) {
+            QM31Field.QM31 memory combined = z.neg();
+            QM31Field.QM31 memory power = QM31Field.one();
+            for (uint256 j = 0; j < width; j++) {
+                uint32 value = publicInputs[HEADER_LEN + i * width + j];
+                combined = combined.add(power.mul(QM31Field.fromReal(value)));
+                power = power.mul(alpha);
+            }
+            numerator = numerator.mul(combined).add(denominator);
+            denominator = denominator.mul(combined);
+        }
+        QM31Field.QM31 memory padding = QM31Field.fromReal(uint32(nRows - nInstances));
+        numerator = numerator.mul(z.neg()).add(denominator.mul(padding));
+        denominator = denominator.mul(z.neg());
+
+        if (!claimedSum.mul(denominator).eq(numerator)) {
+            revert ClaimedSumMismatch();
+        }
+    }
+
+    /// @notice Number of instances, after checking the layout, the pinned instance shape
+    ///         and that every value is a canonical M31 element
+    function _header(uint32[] calldata publicInputs) private view returns (uint256 nInstances) {
+        if (
+            publicInputs.length < HEADER_LEN || publicInputs[1] != inputLen
+                || publicInputs[2] != outputLen
+        ) {
+            revert MalformedPublicInputs();
+        }
+        nInstances = publicInputs[0];
+        if (publicInputs.length != HEADER_LEN + nInstances * (uint256(inputLen) + outputLen)) {
+            revert MalformedPublicInputs();
+        }
+        for (uint256 i = HEADER_LEN; i < publicInputs.length; i++) {
+            if (!M31Field.isValid(publicInputs[i])) {
+                revert MalformedPublicInputs();
+            }
+        }
+    }
+
+    function _slice(uint32[] calldata values, uint256 start, uint256 length)
+        private
+        pure
+        returns (uint32[] memory slice)
+    {
+        slice = new uint32[](length);
+        for (uint256 i = 0; i < length; i++) {
+            slice[i] = values[start + i];
+        }
+    }
+}
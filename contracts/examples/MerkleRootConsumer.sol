@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../verifier/StwoVerifier.sol";
+import "../verifier/ProofParser.sol";
+import "../vcs/HashFunction.sol";
+import "./PinnedVerifyingKey.sol";
+
+/// @title MerkleRootConsumer
+/// @notice Accepts Merkle membership proofs against a stored root
+/// @dev The prover mixes leaf || root into the channel before the first commitment
+///      (SolidityProver::mix_public_inputs), so the transcript digest handed to
+///      STWOVerifier is recomputed here from the claimed public inputs and tree roots.
+///      A proof generated for another leaf or root resumes from a different digest,
+///      and a proof declared for another circuit fails the pinned verifying key.
+///      Trust assumption: STWOVerifier does not evaluate AIR constraints, so nothing
+///      on-chain checks that the committed trace hashes the leaf up to the root; a
+///      recorded membership relies on the prover having checked the proof off-chain.
+contract MerkleRootConsumer is PinnedVerifyingKey {
+    uint256 internal constant DIGEST_SIZE = 8;
+
+    STWOVerifier public immutable verifier;
+    address public owner;
+
+    /// @notice Root of the tree membership is proven against, 8 M31 elements
+    uint32[DIGEST_SIZE] private _root;
+
+    /// @notice keccak256(abi.encodePacked(root, leaf)) => proven
+    mapping(bytes32 => bool) public isProvenMember;
+
+    event RootUpdated(uint32[DIGEST_SIZE] root);
+    event MembershipProven(uint32[DIGEST_SIZE] root, uint32[DIGEST_SIZE] leaf);
+
+    error NotOwner();
+    error RootMismatch();
+    error DigestMismatch(bytes32 expected, bytes32 actual);
+    error ProofRejected();
+
+    modifier onlyOwner() {
+        if (msg.sender != owner) {
+            revert NotOwner();
+        }
+        _;
+    }
+
+    /// @param key Verifying key of the membership circuit for the tree depth and hash used
+    constructor(STWOVerifier verifier_, uint32[DIGEST_SIZE] memory root_, VerifyingKey memory key)
+        PinnedVerifyingKey(key)
+    {
+        verifier = verifier_;
+        owner = msg.sender;
+        _root = root_;
+        emit RootUpdated(root_);
+    }
+
+    /// @notice Current root
+    function root() external view returns (uint32[DIGEST_SIZE] memory) {
+        return _root;
+    }
+
+    /// @notice Replace the stored root, e.g. after the tree was updated
+    function setRoot(uint32[DIGEST_SIZE] calldata newRoot) external onlyOwner {
+        _root = newRoot;
+        emit RootUpdated(newRoot);
+    }
+
+    /// @notice Verify a membership proof of `leaf` under the stored root and record it
+    /// @param leaf Leaf public input
+    /// @param root_ Root public input, must equal the stored root
+    /// @param proof Proof in the ABI-words encoding
+    /// @param params Verification parameters of the membership component
+    /// @param treeRoots Preprocessed and trace commitments
+    /// @param treeColumnLogSizes Extended column log sizes per tree
+    /// @param digest Channel digest after the last commitment
+    function proveMembership(
+        uint32[DIGEST_SIZE] calldata leaf,
+        uint32[DIGEST_SIZE] calldata root_,
+        ProofParser.Proof calldata proof,
+        STWOVerifier.VerificationParams calldata params,
+        bytes32[] calldata treeRoots,
+        uint32[][] calldata treeColumnLogSizes,
+        bytes32 digest
+    ) external {
+        if (keccak256(abi.encodePacked(root_)) != keccak256(abi.encodePacked(_root))) {
+            revert RootMismatch();
+        }
+        _checkVerifyingKey(proof, params, treeRoots, treeColumnLogSizes);
+
+        bytes32 expected = publicInputDigest(proof.header.hashFunction, leaf, root_, treeRoots);
+        if (expected != digest) {
+            revert DigestMismatch(expected, digest);
+        }
+
+        if (!verifier.verify(proof, params, treeRoots, treeColumnLogSizes, digest, 0)) {
+            revert ProofRejected();
+        }
+
+        isProvenMember[keccak256(abi.encodePacked(root_, leaf))] = true;
+        emit MembershipProven(root_, leaf);
+    }
+
+    /// @notice Channel digest after mixing leaf || root and every tree root
+    /// @dev Mirrors ChannelLib.mixU32s on a zero digest followed by ChannelLib.mixRoot
+    function publicInputDigest(
+        uint8 hashFunction,
+        uint32[DIGEST_SIZE] calldata leaf,
+        uint32[DIGEST_SIZE] calldata root_,
+        bytes32[] calldata treeRoots
+    ) public pure returns (bytes32 digest) {
+        bytes memory input = abi.encodePacked(bytes32(0));
+        for (uint256 i = 0; i < DIGEST_SIZE; i++) {
+            input = abi.encodePacked(input, _u32ToLittleEndian(leaf[i]));
+        }
+        for (uint256 i = 0; i < DIGEST_SIZE; i++) {
+            input = abi.encodePacked(input, _u32ToLittleEndian(root_[i]));
+        }
+        digest = HashFunction.hash(hashFunction, input);
+
+        for (uint256 i = 0; i < treeRoots.length; i++) {
+            digest = HashFunction.hash(hashFunction, abi.encodePacked(digest, treeRoots[i]));
+        }
+    }
+
+    /// @notice Whether `leaf` was proven under the stored root
+    function isMember(uint32[DIGEST_SIZE] calldata leaf) external view returns (bool) {
+        return isProvenMember[keccak256(abi.encodePacked(_root, leaf))];
+    }
+
+    function _u32ToLittleEndian(uint32 value) private pure returns (bytes4) {
+        return bytes4(
+            abi.encodePacked(uint8(value), uint8(value >> 8), uint8(value >> 16), uint8(value >> 24))
+        );
+    }
+}
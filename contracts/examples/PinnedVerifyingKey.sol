@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../verifier/StwoVerifier.sol";
+import "../verifier/ProofParser.sol";
+
+/// @title PinnedVerifyingKey
+/// @notice Verifying key of the one circuit a consumer accepts, fixed at deployment
+/// @dev STWOVerifier checks a proof against whatever parameters it is given, so a
+///      consumer that forwards caller-supplied parameters accepts a proof declared for
+///      any circuit shape. Pinning the key rejects proofs declared with other
+///      preprocessed columns, PCS config, component shapes or column log sizes.
+///      Trust assumption: STWOVerifier does not evaluate AIR constraints, so the key
+///      fixes what a proof declares, not that its trace satisfies the circuit. That
+///      rests on the prover having checked the proof off-chain (prove_for_solidity).
+///      Component claimed sums are not part of the key: they depend on the statement,
+///      so consumers with LogUp relations check them against their public inputs.
+abstract contract PinnedVerifyingKey {
+    struct VerifyingKey {
+        /// @notice Channel and Merkle hash; the preprocessed root depends on it
+        uint8 hashFunction;
+        /// @notice Commitment to the preprocessed columns
+        bytes32 preprocessedRoot;
+        /// @notice keccak256(abi.encode(proof.config))
+        bytes32 configHash;
+        /// @notice verificationParamsHash(params)
+        bytes32 paramsHash;
+        /// @notice keccak256(abi.encode(treeColumnLogSizes))
+        bytes32 columnLogSizesHash;
+    }
+
+    uint8 public immutable keyHashFunction;
+    bytes32 public immutable keyPreprocessedRoot;
+    bytes32 public immutable keyConfigHash;
+    bytes32 public immutable keyParamsHash;
+    bytes32 public immutable keyColumnLogSizesHash;
+
+    error VerifyingKeyMismatch(string field);
+
+    constructor(VerifyingKey memory key) {
+        keyHashFunction = key.hashFunction;
+        keyPreprocessedRoot = key.preprocessedRoot;
+        keyConfigHash = key.configHash;
+        keyParamsHash = key.paramsHash;
+        keyColumnLogSizesHash = key.columnLogSizesHash;
+    }
+
+    /// @notice Hash of `params` without the component claimed sums
+    function verificationParamsHash(STWOVerifier.VerificationParams calldata params)
+        public
+        pure
+        returns (bytes32)
+    {
+        bytes memory encoded =
+            abi.encode(params.nPreprocessedColumns, params.componentsCompositionLogDegreeBound);
+        for (uint256 i = 0; i < params.componentParams.length; i++) {
+            STWOVerifier.ComponentParams calldata component = params.componentParams[i];
+            encoded = abi.encodePacked(
+                encoded, keccak256(abi.encode(component.logSize, component.info))
+            );
+        }
+        return keccak256(encoded);
+    }
+
+    /// @notice Revert unless the proof was made for the pinned circuit
+    function _checkVerifyingKey(
+        ProofParser.Proof calldata proof,
+        STWOVerifier.VerificationParams calldata params,
+        bytes32[] calldata treeRoots,
+        uint32[][] calldata treeColumnLogSizes
+    ) internal view {
+        if (proof.header.hashFunction != keyHashFunction) {
+            revert VerifyingKeyMismatch("hashFunction");
+        }
+        if (treeRoots.length == 0 || treeRoots[0] != keyPreprocessedRoot) {
+            revert VerifyingKeyMismatch("preprocessedRoot");
+        }
+        if (keccak256(abi.encode(proof.config)) != keyConfigHash) {
+            revert VerifyingKeyMismatch("config");
+        }
+        if (verificationParamsHash(params) != keyParamsHash) {
+            revert VerifyingKeyMismatch("params");
+        }
+        if (keccak256(abi.encode(treeColumnLogSizes)) != keyColumnLogSizesHash) {
+            revert VerifyingKeyMismatch("treeColumnLogSizes");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../verifier/StwoVerifier.sol";
+import "../verifier/ProofParser.sol";
+import "../vcs/HashFunction.sol";
+import "../fields/M31Field.sol";
+import "../fields/QM31Field.sol";
+import "./PinnedVerifyingKey.sol";
+
+/// @title TinyVmConsumer
+/// @notice Records the outputs of tiny VM runs of one program
+/// @dev The program is held in preprocessed columns, so the pinned preprocessed root
+///      fixes the program and the pinned column log sizes its trace shapes. The prover
+///      mixes the output into the channel before the first commitment, so the
+///      transcript digest handed to STWOVerifier is recomputed here from the claimed
+///      output and tree roots. The CPU, program and memory components are linked by
+///      LogUp lookups, which only balance if their claimed sums add up to zero;
+///      STWOVerifier takes each claimed sum as given, so the total is checked here.
+///      Trust assumption: STWOVerifier does not evaluate AIR constraints, so that the
+///      claimed sums are the ones the trace rows produce, and that the trace is a run
+///      of the program ending on the output, relies on the prover having checked the
+///      proof off-chain. The consumer binds the output to the proof's transcript and
+///      checks the lookups balance.
+contract TinyVmConsumer is PinnedVerifyingKey {
+    using QM31Field for QM31Field.QM31;
+
+    STWOVerifier public immutable verifier;
+
+    /// @notice Output => proven by some run
+    mapping(uint32 => bool) public isProvenOutput;
+
+    /// @notice Number of runs proven so far
+    uint256 public runCount;
+
+    event RunProven(uint32 output, bytes32 digest);
+
+    error InvalidOutput();
+    error LookupsUnbalanced();
+    error DigestMismatch(bytes32 expected, bytes32 actual);
+    error ProofRejected();
+
+    /// @param key Verifying key of the tiny VM circuit for the program and hash used
+    constructor(STWOVerifier verifier_, VerifyingKey memory key) PinnedVerifyingKey(key) {
+        verifier = verifier_;
+    }
+
+    /// @notice Verify a proof that the pinned program halts with `output` and record it
+    /// @param output Output public input
+    /// @param proof Proof in the ABI-words encoding
+    /// @param params Verification parameters of the CPU, program and memory components
+    /// @param treeRoots Preprocessed, trace and interaction commitments
+    /// @param treeColumnLogSizes Extended column log sizes per tree
+    /// @param digest Channel digest after the last commitment
+    function proveRun(
+        uint32 output,
+        ProofParser.Proof calldata proof,
+        STWOVerifier.VerificationParams calldata params,
+        bytes32[] calldata treeRoots,
+        uint32[][] calldata treeColumnLogSizes,
+        bytes32 digest
+    ) external {
+        if (!M31Field.isValid(output)) {
+            revert InvalidOutput();
+        }
+        _checkVerifyingKey(proof, params, treeRoots, treeColumnLogSizes);
+        _checkLookupsBalance(params);
+
+        bytes32 expected = publicInputDigest(proof.header.hashFunction, output, treeRoots);
+        if (expected != digest) {
+            revert DigestMismatch(expected, digest);
+        }
+
+        if (!verifier.verify(proof, params, treeRoots, treeColumnLogSizes, digest, 0)) {
+            revert ProofRejected();
+        }
+
+        isProvenOutput[output] = true;
+        runCount++;
+        emit RunProven(output, digest);
+    }
+
+    /// @notice Channel digest after mixing the output and every tree root
+    /// @dev Mirrors ChannelLib.mixU32s on a zero digest followed by ChannelLib.mixRoot
+    function publicInputDigest(uint8 hashFunction, uint32 output, bytes32[] calldata treeRoots)
+        public
+        pure
+        returns (bytes32 digest)
+    {
+        digest = HashFunction.hash(
+            hashFunction,
+            abi.encodePacked(
+                bytes32(0), uint8(output), uint8(output >> 8), uint8(output >> 16), uint8(output >> 24)
+            )
+        );
+
+        for (uint256 i = 0; i < treeRoots.length; i++) {
+            digest = HashFunction.hash(hashFunction, abi.encodePacked(digest, treeRoots[i]));
+        }
+    }
+
+    /// @notice Revert unless the components' claimed sums are canonical and add up to zero
+    function _checkLookupsBalance(STWOVerifier.VerificationParams calldata params) private pure {
+        QM31Field.QM31 memory total = QM31Field.zero();
+        for (uint256 i = 0; i < params.componentParams.length; i++) {
+            QM31Field.QM31 memory claimedSum = params.componentParams[i].claimedSum;
+            if (!claimedSum.isValid()) {
+                revert LookupsUnbalanced();
+            }
+            total = total.add(claimedSum);
+        }
+        if (!total.isZero()) {
+            revert LookupsUnbalanced();
+        }
+    }
+}
@@ -11,7 +11,7 @@ import "../circle/CirclePointM31.sol";
 import "../fields/QM31Field.sol";
 import "../fields/CM31Field.sol";
 import "../fields/M31Field.sol";
-import "../core/KeccakChannelLib.sol";
+import "../core/ChannelLib.sol";
 import "../vcs/MerkleVerifier.sol";
 
 /// @title FriVerifier
@@ -22,7 +22,7 @@ library FriVerifier {
     using QM31Field for QM31Field.QM31;
     using CM31Field for CM31Field.CM31;
     using CirclePointM31 for CirclePointM31.Point;
-    using KeccakChannelLib for KeccakChannelLib.ChannelState;
+    using ChannelLib for ChannelLib.ChannelState;
     using MerkleVerifier for MerkleVerifier.Verifier;
 
     /// @notice Secure extension degree for field operations
@@ -59,6 +59,7 @@ library FriVerifier {
         CircleDomain.CircleDomainStruct[] columnCommitmentDomains;
         QM31Field.QM31 foldingAlpha;
         FriLayerProof proof;
+        uint8 hashFunction;
     }
 
     /// @notice Inner layer verifier for FRI intermediate layers
@@ -73,12 +74,14 @@ library FriVerifier {
     /// @param foldingAlpha Folding alpha for this layer
     /// @param layerIndex Index of this layer
     /// @param proof Proof data for this layer
+    /// @param hashFunction Merkle hash function identifier (see HashFunction)
     struct FriInnerLayerVerifier {
         uint32 degreeBound;
         CosetM31.CosetStruct domain;  // Changed from domainLogSize to full domain
         QM31Field.QM31 foldingAlpha;
         uint256 layerIndex;
         FriLayerProof proof;
+        uint8 hashFunction;
     }
 
     /// @notice Proof for individual FRI layer
@@ -156,13 +159,13 @@ library FriVerifier {
 
     /// @notice Verify the commitment stage of FRI
     /// @dev Verifies FRI commitments and prepares verifier state for decommitment
-    /// @param channelState Keccak channel state for Fiat-Shamir
+    /// @param channelState Channel state for Fiat-Shamir
     /// @param config FRI configuration parameters
     /// @param proof Complete FRI proof
     /// @param columnBounds Circle polynomial degree bounds in descending order
     /// @return friVerifierState Initialized verifier state for decommitment
     function commit(
-        KeccakChannelLib.ChannelState storage channelState,
+        ChannelLib.ChannelState storage channelState,
         PcsConfig.FriConfig memory config,
         FriProof memory proof,
         CirclePolyDegreeBound.Bound[] memory columnBounds
@@ -214,7 +217,8 @@ library FriVerifier {
             columnBounds: columnBounds,
             columnCommitmentDomains: columnCommitmentDomains,
             foldingAlpha: channelState.drawSecureFelt(),
-            proof: proof.firstLayer
+            proof: proof.firstLayer,
+            hashFunction: channelState.hashFunction
         });
 
         // Process inner layers
@@ -248,7 +252,8 @@ library FriVerifier {
                 domain: layerDomain,
                 foldingAlpha: channelState.drawSecureFelt(),
                 layerIndex: i,
-                proof: proof.innerLayers[i]
+                proof: proof.innerLayers[i],
+                hashFunction: channelState.hashFunction
             });
 
             // Fold for next layer
@@ -297,11 +302,11 @@ library FriVerifier {
     /// @notice Sample query positions for FRI decommitment
     /// @dev Matches Rust implementation: generates unique queries and maps them by log size
     /// @param friVerifierState FRI verifier state
-    /// @param channelState Keccak channel for randomness
+    /// @param channelState Channel for randomness
     /// @return queryPositionsByLogSize Mapping of log sizes to query positions (equivalent to Rust BTreeMap)
     function sampleQueryPositions(
         FriVerifierState storage friVerifierState,
-        KeccakChannelLib.ChannelState storage channelState
+        ChannelLib.ChannelState storage channelState
     )
         internal
         returns (QueryPositionsByLogSize memory queryPositionsByLogSize)
@@ -348,7 +353,7 @@ library FriVerifier {
     /// @param channelState Channel state for mixing
     /// @param values Array of QM31 values to mix
     function _mixQM31Array(
-        KeccakChannelLib.ChannelState storage channelState,
+        ChannelLib.ChannelState storage channelState,
         QM31Field.QM31[] memory values
     ) private {
         for (uint256 i = 0; i < values.length; i++) {
@@ -471,7 +476,7 @@ library FriVerifier {
     /// @param nQueries Number of unique queries to generate
     /// @return queries Generated queries structure
     function _generateQueries(
-        KeccakChannelLib.ChannelState storage channelState,
+        ChannelLib.ChannelState storage channelState,
         uint32 logDomainSize,
         uint32 nQueries
     ) private returns (Queries memory queries) {
@@ -1607,10 +1612,10 @@ library FriVerifier {
         uint32[] memory columnLogSizes = _createColumnLogSizes(firstLayer);
         
         MerkleVerifier.MerkleTree memory verifier = MerkleVerifier.createMerkleTree(
-            firstLayer.proof.commitment, columnLogSizes
+            firstLayer.proof.commitment, columnLogSizes, firstLayer.hashFunction
         );
 
-        MerkleVerifier.Decommitment memory decommitment = _decodeDecommitment(firstLayer.proof.decommitment);
+        MerkleVerifier.Decommitment memory decommitment = MerkleVerifier.decodeDecommitment(firstLayer.proof.decommitment);
         
         // Prepare queries per log size from decommitment positions
         MerkleVerifier.QueriesPerLogSize[] memory queriesPerLogSize = new MerkleVerifier.QueriesPerLogSize[](numUniqueLogSizes);
@@ -2233,11 +2238,12 @@ library FriVerifier {
 
         MerkleVerifier.MerkleTree memory verifier = MerkleVerifier.createMerkleTree(
             layer.proof.commitment,
-            columnLogSizes
+            columnLogSizes,
+            layer.hashFunction
         );
 
         // Decode decommitment proof
-        MerkleVerifier.Decommitment memory decommitment = _decodeDecommitment(
+        MerkleVerifier.Decommitment memory decommitment = MerkleVerifier.decodeDecommitment(
             layer.proof.decommitment
         );
 
@@ -2378,65 +2384,6 @@ library FriVerifier {
         }
     }
 
-    /// @notice Decode Merkle decommitment from bytes
-    /// @param encodedDecommitment Encoded decommitment data
-    /// @return decommitment Decoded Merkle decommitment
-    function _decodeDecommitment(
-        bytes memory encodedDecommitment
-    ) internal pure returns (MerkleVerifier.Decommitment memory decommitment) {
-        // For now, assume the encoded data is structured as:
-        // [hashWitnessLength(32)] + [hashWitness...] + [columnWitnessLength(32)] + [columnWitness...]
-
-        require(encodedDecommitment.length >= 64, "Decommitment too short");
-
-        uint256 offset = 0;
-
-        // Decode hash witness length
-        uint256 hashWitnessLength;
-        assembly {
-            hashWitnessLength := mload(
-                add(add(encodedDecommitment, 0x20), offset)
-            )
-        }
-        offset += 32;
-
-        // Decode hash witness
-        decommitment.hashWitness = new bytes32[](hashWitnessLength);
-        for (uint256 i = 0; i < hashWitnessLength; i++) {
-            assembly {
-                let value := mload(add(add(encodedDecommitment, 0x20), offset))
-                mstore(
-                    add(
-                        add(mload(add(decommitment, 0x00)), 0x20),
-                        mul(i, 0x20)
-                    ),
-                    value
-                )
-            }
-            offset += 32;
-        }
-
-        // Decode column witness length
-        uint256 columnWitnessLength;
-        assembly {
-            columnWitnessLength := mload(
-                add(add(encodedDecommitment, 0x20), offset)
-            )
-        }
-        offset += 32;
-
-        // Decode column witness
-        decommitment.columnWitness = new uint32[](columnWitnessLength);
-        for (uint256 i = 0; i < columnWitnessLength; i++) {
-            uint32 value;
-            assembly {
-                value := mload(add(add(encodedDecommitment, 0x20), offset))
-            }
-            decommitment.columnWitness[i] = value;
-            offset += 32;
-        }
-    }
-
     /// @notice Extract decommitted values from sparse evaluations (matches Rust)
     /// @dev Rust: decommitmented_values.extend(sparse_evaluation.subset_evals.iter().flatten().flat_map(|qm31| qm31.to_m31_array()));
     /// @param sparseEvals Sparse evaluations from first layer
@@ -2852,10 +2799,10 @@ library FriVerifier {
         uint32[] memory columnLogSizes = _createColumnLogSizes(firstLayer);
         
         MerkleVerifier.MerkleTree memory verifier = MerkleVerifier.createMerkleTree(
-            firstLayer.proof.commitment, columnLogSizes
+            firstLayer.proof.commitment, columnLogSizes, firstLayer.hashFunction
         );
 
-        MerkleVerifier.Decommitment memory decommitment = _decodeDecommitment(firstLayer.proof.decommitment);
+        MerkleVerifier.Decommitment memory decommitment = MerkleVerifier.decodeDecommitment(firstLayer.proof.decommitment);
         MerkleVerifier.QueriesPerLogSize[] memory queriesPerLogSize = 
             _createQueriesPerLogSize(uniqueLogSizes, decommitmentsByLogSize, numUniqueLogSizes);
         _sortQueriesPerLogSizeAscending(queriesPerLogSize);
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+/// @title ERC1967
+/// @notice Implementation and admin slots shared by the verifier proxies (EIP-1967)
+/// @dev The slots are hashes far away from slot 0, where STWOVerifier keeps its state.
+library ERC1967 {
+    /// @notice bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)
+    bytes32 internal constant IMPLEMENTATION_SLOT =
+        0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+
+    /// @notice bytes32(uint256(keccak256("eip1967.proxy.admin")) - 1)
+    bytes32 internal constant ADMIN_SLOT =
+        0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103;
+
+    event Upgraded(address indexed implementation);
+    event AdminChanged(address previousAdmin, address newAdmin);
+
+    error InvalidImplementation(address implementation);
+    error InvalidAdmin(address admin);
+
+    function getImplementation() internal view returns (address implementation) {
+        bytes32 slot = IMPLEMENTATION_SLOT;
+        assembly {
+            implementation := sload(slot)
+        }
+    }
+
+    function setImplementation(address implementation) internal {
+        if (implementation.code.length == 0) {
+            revert InvalidImplementation(implementation);
+        }
+        bytes32 slot = IMPLEMENTATION_SLOT;
+        assembly {
+            sstore(slot, implementation)
+        }
+        emit Upgraded(implementation);
+    }
+
+    function getAdmin() internal view returns (address admin) {
+        bytes32 slot = ADMIN_SLOT;
+        assembly {
+            admin := sload(slot)
+        }
+    }
+
+    function setAdmin(address admin) internal {
+        if (admin == address(0)) {
+            revert InvalidAdmin(admin);
+        }
+        emit AdminChanged(getAdmin(), admin);
+        bytes32 slot = ADMIN_SLOT;
+        assembly {
+            sstore(slot, admin)
+        }
+    }
+
+    /// @notice Forward the current call to `implementation` and return or revert with its result
+    function delegate(address implementation) internal {
+        assembly {
+            calldatacopy(0, 0, calldatasize())
+            let result := delegatecall(gas(), implementation, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+            switch result
+            case 0 {
+                revert(0, returndatasize())
+            }
+            default {
+                return(0, returndatasize())
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "./ERC1967.sol";
+
+/// @title TransparentVerifierProxy
+/// @notice Stable verifier address forwarding every call to a plain STWOVerifier
+/// @dev The admin can only call the functions below and every other caller is always
+///      forwarded, so admin and verifier selectors never clash. The admin account
+///      therefore cannot submit proofs through the proxy.
+contract TransparentVerifierProxy {
+    error AdminCannotFallback();
+
+    constructor(address implementation, address admin_) payable {
+        ERC1967.setImplementation(implementation);
+        ERC1967.setAdmin(admin_);
+    }
+
+    /// @notice Switch to `newImplementation`; admin only
+    function upgradeTo(address newImplementation) external {
+        _dispatch();
+        ERC1967.setImplementation(newImplementation);
+    }
+
+    /// @notice Hand the admin role to `newAdmin`; admin only
+    function changeAdmin(address newAdmin) external {
+        _dispatch();
+        ERC1967.setAdmin(newAdmin);
+    }
+
+    /// @notice Current implementation; admin only
+    function implementation() external returns (address) {
+        _dispatch();
+        return ERC1967.getImplementation();
+    }
+
+    /// @notice Current admin; admin only
+    function admin() external returns (address) {
+        _dispatch();
+        return ERC1967.getAdmin();
+    }
+
+    fallback() external payable {
+        if (msg.sender == ERC1967.getAdmin()) {
+            revert AdminCannotFallback();
+        }
+        ERC1967.delegate(ERC1967.getImplementation());
+    }
+
+    /// @dev Forward calls from anyone but the admin, even to the admin functions
+    function _dispatch() private {
+        if (msg.sender != ERC1967.getAdmin()) {
+            ERC1967.delegate(ERC1967.getImplementation());
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "./ERC1967.sol";
+
+/// @title UUPSVerifierProxy
+/// @notice Stable verifier address forwarding every call to an UpgradeableSTWOVerifier
+/// @dev The upgrade logic and its owner check live in the implementation (UUPS), so
+///      this proxy only stores the implementation address and delegates.
+contract UUPSVerifierProxy {
+    /// @param implementation First UpgradeableSTWOVerifier
+    /// @param initData Call run on the implementation in the proxy's context,
+    ///        normally UpgradeableSTWOVerifier.initialize(owner)
+    constructor(address implementation, bytes memory initData) payable {
+        ERC1967.setImplementation(implementation);
+        if (initData.length > 0) {
+            (bool success, bytes memory result) = implementation.delegatecall(initData);
+            if (!success) {
+                assembly {
+                    revert(add(result, 32), mload(result))
+                }
+            }
+        }
+    }
+
+    fallback() external payable {
+        ERC1967.delegate(ERC1967.getImplementation());
+    }
+}
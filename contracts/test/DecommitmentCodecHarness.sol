@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../vcs/MerkleVerifier.sol";
+
+/// @title DecommitmentCodecHarness
+/// @notice Exposes the MerkleVerifier decommitment codec to Rust tests
+contract DecommitmentCodecHarness {
+    function encode(
+        bytes32[] memory hashWitness,
+        uint32[] memory columnWitness
+    ) external pure returns (bytes memory) {
+        return MerkleVerifier.encodeDecommitment(
+            MerkleVerifier.Decommitment({hashWitness: hashWitness, columnWitness: columnWitness})
+        );
+    }
+
+    function decode(
+        bytes memory encoded
+    ) external pure returns (bytes32[] memory hashWitness, uint32[] memory columnWitness) {
+        MerkleVerifier.Decommitment memory decommitment = MerkleVerifier.decodeDecommitment(encoded);
+        return (decommitment.hashWitness, decommitment.columnWitness);
+    }
+}
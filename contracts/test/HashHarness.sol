@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../core/ChannelLib.sol";
+import "../fields/QM31Field.sol";
+import "../vcs/MerkleVerifier.sol";
+
+/// @title HashHarness
+/// @notice Exposes the Merkle node hashing and channel of each hash function to Rust tests
+contract HashHarness {
+    using ChannelLib for ChannelLib.ChannelState;
+
+    ChannelLib.ChannelState private _channel;
+
+    function hashLeaf(uint8 hashFunction, uint32[] memory columnValues) external pure returns (bytes32) {
+        return MerkleVerifier._hashLeaf(hashFunction, columnValues);
+    }
+
+    function hashNode(
+        uint8 hashFunction,
+        bytes32 leftChild,
+        bytes32 rightChild,
+        uint32[] memory columnValues
+    ) external pure returns (bytes32) {
+        return MerkleVerifier._hashNode(hashFunction, leftChild, rightChild, columnValues);
+    }
+
+    /// @notice Mix `data` into a fresh channel and draw one secure felt
+    function mixAndDraw(
+        uint8 hashFunction,
+        uint32[] memory data
+    ) external returns (bytes32 digest, uint32[4] memory felt) {
+        _channel.initializeWith(bytes32(0), 0, hashFunction);
+        _channel.mixU32s(data);
+        digest = _channel.digest;
+        felt = QM31Field.toM31Array(_channel.drawSecureFelt());
+        _channel.clearState();
+    }
+
+    /// @notice Mix `data` into a fresh channel and check a proof-of-work nonce
+    function mixAndVerifyPow(
+        uint8 hashFunction,
+        uint32[] memory data,
+        uint32 nBits,
+        uint64 nonce
+    ) external returns (bool valid) {
+        _channel.initializeWith(bytes32(0), 0, hashFunction);
+        _channel.mixU32s(data);
+        valid = _channel.verifyPowNonce(nBits, nonce);
+        _channel.clearState();
+    }
+}
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../verifier/ProofParser.sol";
+
+/// @title PackedProofHarness
+/// @notice Exposes ProofParser.decodePacked to Rust tests
+contract PackedProofHarness {
+    /// @notice Decode a packed proof and return it ABI-encoded
+    function decodePacked(bytes calldata data) external pure returns (bytes memory) {
+        return abi.encode(ProofParser.decodePacked(data));
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+/// @title Blake2s
+/// @notice BLAKE2s-256 hash (RFC 7693) matching Rust Blake2sHasher
+/// @dev The EVM precompile at 0x09 (EIP-152) implements the BLAKE2b F function
+///      with 64-bit words and 12 rounds, so it cannot compute BLAKE2s. The
+///      compression function is therefore implemented directly over uint32 words.
+library Blake2s {
+    uint32 private constant IV0 = 0x6A09E667;
+    uint32 private constant IV1 = 0xBB67AE85;
+    uint32 private constant IV2 = 0x3C6EF372;
+    uint32 private constant IV3 = 0xA54FF53A;
+    uint32 private constant IV4 = 0x510E527F;
+    uint32 private constant IV5 = 0x9B05688C;
+    uint32 private constant IV6 = 0x1F83D9AB;
+    uint32 private constant IV7 = 0x5BE0CD19;
+
+    /// @notice Parameter block word 0 for an unkeyed 32-byte digest (depth 1, fanout 1)
+    uint32 private constant PARAM_WORD0 = 0x01010020;
+
+    uint256 private constant BLOCK_BYTES = 64;
+
+    /// @notice Message schedule, 16 4-bit indices per round, 4 rounds per word
+    uint256 private constant SIGMA_0_3 = 0x8f04a562ebcd1397491763eadf250c8b357b20c16df984aefedcba9876543210;
+    uint256 private constant SIGMA_4_7 = 0xa2684f05931ce7bdb8293670a4def15c91ef57d438b0a6c2d386cb1efa427509;
+    uint256 private constant SIGMA_8_9 = 0x0dc3e9bf5167482a5a417d2c803b9ef6;
+
+    /// @notice Hash arbitrary bytes with BLAKE2s-256
+    /// @param data Input bytes
+    /// @return digest 32-byte digest in the byte order produced by Rust blake2s
+    function hash(bytes memory data) internal pure returns (bytes32 digest) {
+        uint32[8] memory h = [IV0 ^ PARAM_WORD0, IV1, IV2, IV3, IV4, IV5, IV6, IV7];
+
+        uint256 offset = 0;
+        while (data.length - offset > BLOCK_BYTES) {
+            _compress(h, data, offset, offset + BLOCK_BYTES, false);
+            offset += BLOCK_BYTES;
+        }
+        _compress(h, data, offset, data.length, true);
+
+        uint256 result = 0;
+        for (uint256 i = 0; i < 8; i++) {
+            result = (result << 32) | _byteSwap(h[i]);
+        }
+        return bytes32(result);
+    }
+
+    /// @notice BLAKE2s compression function F
+    /// @param h Chaining value, updated in place
+    /// @param data Input bytes
+    /// @param offset Start of the block within data (bytes past the end read as zero)
+    /// @param counter Total number of bytes hashed including this block
+    /// @param isLast Whether this is the final block
+    function _compress(
+        uint32[8] memory h,
+        bytes memory data,
+        uint256 offset,
+        uint256 counter,
+        bool isLast
+    ) private pure {
+        uint32[16] memory m = _loadBlock(data, offset);
+        uint32[16] memory v;
+
+        for (uint256 i = 0; i < 8; i++) {
+            v[i] = h[i];
+        }
+        v[8] = IV0;
+        v[9] = IV1;
+        v[10] = IV2;
+        v[11] = IV3;
+        v[12] = IV4 ^ uint32(counter);
+        v[13] = IV5 ^ uint32(counter >> 32);
+        v[14] = isLast ? ~IV6 : IV6;
+        v[15] = IV7;
+
+        for (uint256 round = 0; round < 10; round++) {
+            uint256 s = _sigma(round);
+            _g(v, 0, 4, 8, 12, m[s & 0xf], m[(s >> 4) & 0xf]);
+            _g(v, 1, 5, 9, 13, m[(s >> 8) & 0xf], m[(s >> 12) & 0xf]);
+            _g(v, 2, 6, 10, 14, m[(s >> 16) & 0xf], m[(s >> 20) & 0xf]);
+            _g(v, 3, 7, 11, 15, m[(s >> 24) & 0xf], m[(s >> 28) & 0xf]);
+            _g(v, 0, 5, 10, 15, m[(s >> 32) & 0xf], m[(s >> 36) & 0xf]);
+            _g(v, 1, 6, 11, 12, m[(s >> 40) & 0xf], m[(s >> 44) & 0xf]);
+            _g(v, 2, 7, 8, 13, m[(s >> 48) & 0xf], m[(s >> 52) & 0xf]);
+            _g(v, 3, 4, 9, 14, m[(s >> 56) & 0xf], m[(s >> 60) & 0xf]);
+        }
+
+        for (uint256 i = 0; i < 8; i++) {
+            h[i] = h[i] ^ v[i] ^ v[i + 8];
+        }
+    }
+
+    /// @notice Mixing function G
+    function _g(
+        uint32[16] memory v,
+        uint256 a,
+        uint256 b,
+        uint256 c,
+        uint256 d,
+        uint32 x,
+        uint32 y
+    ) private pure {
+        unchecked {
+            v[a] = v[a] + v[b] + x;
+            v[d] = _rotr(v[d] ^ v[a], 16);
+            v[c] = v[c] + v[d];
+            v[b] = _rotr(v[b] ^ v[c], 12);
+            v[a] = v[a] + v[b] + y;
+            v[d] = _rotr(v[d] ^ v[a], 8);
+            v[c] = v[c] + v[d];
+            v[b] = _rotr(v[b] ^ v[c], 7);
+        }
+    }
+
+    /// @notice Load a 64-byte block as 16 little-endian words, zero padded
+    function _loadBlock(bytes memory data, uint256 offset) private pure returns (uint32[16] memory m) {
+        for (uint256 i = 0; i < 16; i++) {
+            uint32 word = 0;
+            for (uint256 k = 0; k < 4; k++) {
+                uint256 pos = offset + i * 4 + k;
+                if (pos < data.length) {
+                    word |= uint32(uint8(data[pos])) << uint32(8 * k);
+                }
+            }
+            m[i] = word;
+        }
+    }
+
+    /// @notice Message schedule permutation for a round
+    function _sigma(uint256 round) private pure returns (uint256) {
+        uint256 packed = round < 4 ? SIGMA_0_3 : (round < 8 ? SIGMA_4_7 : SIGMA_8_9);
+        return (packed >> (64 * (round % 4))) & 0xffffffffffffffff;
+    }
+
+    /// @notice Rotate uint32 right
+    function _rotr(uint32 x, uint32 n) private pure returns (uint32) {
+        return (x >> n) | (x << (32 - n));
+    }
+
+    /// @notice Reverse byte order of a uint32
+    function _byteSwap(uint32 x) private pure returns (uint256) {
+        return uint256(
+            ((x & 0xff) << 24) |
+            ((x & 0xff00) << 8) |
+            ((x >> 8) & 0xff00) |
+            (x >> 24)
+        );
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "./Blake2s.sol";
+
+/// @title HashFunction
+/// @notice Hash function selection shared by the channel and Merkle verifiers
+/// @dev Identifiers match Rust contracts::hash::HashFunction
+library HashFunction {
+    /// @notice Native keccak256 (KeccakChannel / KeccakMerkleChannel)
+    uint8 internal constant KECCAK = 0;
+    /// @notice BLAKE2s-256 (Blake2sChannel / Blake2sMerkleChannel)
+    uint8 internal constant BLAKE2S = 1;
+    /// @notice Poseidon over the Stark252 field (reserved, not verifiable on-chain yet)
+    uint8 internal constant POSEIDON252 = 2;
+
+    /// @notice Error thrown when a proof selects a hash function this verifier cannot compute
+    error UnsupportedHashFunction(uint8 hashFunction);
+
+    /// @notice Check whether a hash function can be verified on-chain
+    /// @param hashFunction Hash function identifier
+    /// @return True if supported
+    function isSupported(uint8 hashFunction) internal pure returns (bool) {
+        return hashFunction == KECCAK || hashFunction == BLAKE2S;
+    }
+
+    /// @notice Hash bytes with the selected hash function
+    /// @param hashFunction Hash function identifier
+    /// @param data Input bytes
+    /// @return Digest as bytes32
+    function hash(uint8 hashFunction, bytes memory data) internal pure returns (bytes32) {
+        if (hashFunction == KECCAK) {
+            return keccak256(data);
+        }
+        if (hashFunction == BLAKE2S) {
+            return Blake2s.hash(data);
+        }
+        revert UnsupportedHashFunction(hashFunction);
+    }
+}
@@ -2,6 +2,7 @@
 pragma solidity ^0.8.20;
 
 import "../fields/M31Field.sol";
+import "./HashFunction.sol";
 
 /// @title MerkleVerifier
 /// @notice Verifies Merkle tree decommitments for vector commitment schemes
@@ -14,6 +15,7 @@ library MerkleVerifier {
         uint32[] columnLogSizes;
         uint32[] logSizes;
         uint256[] nColumnsPerLogSize;
+        uint8 hashFunction;
     }
 
     /// @notice Commitment scheme verifier state
@@ -62,13 +64,30 @@ library MerkleVerifier {
     function newVerifier(
         bytes32[] memory treeRoots,
         uint32[][] memory treeColumnLogSizes
+    ) internal pure returns (Verifier memory verifier) {
+        return newVerifier(treeRoots, treeColumnLogSizes, HashFunction.KECCAK);
+    }
+
+    /// @notice Create new Merkle verifier with multiple trees using the given hash function
+    /// @param treeRoots Array of Merkle tree roots (one per tree)
+    /// @param treeColumnLogSizes Array of column log sizes arrays (one array per tree)
+    /// @param hashFunction Hash function identifier (see HashFunction)
+    /// @return verifier New multi-tree verifier instance
+    function newVerifier(
+        bytes32[] memory treeRoots,
+        uint32[][] memory treeColumnLogSizes,
+        uint8 hashFunction
     ) internal pure returns (Verifier memory verifier) {
         require(treeRoots.length == treeColumnLogSizes.length, "Mismatched trees and column sizes");
         
         verifier.trees = new MerkleTree[](treeRoots.length);
         
         for (uint256 treeIdx = 0; treeIdx < treeRoots.length; treeIdx++) {
-            verifier.trees[treeIdx] = createMerkleTree(treeRoots[treeIdx], treeColumnLogSizes[treeIdx]);
+            verifier.trees[treeIdx] = createMerkleTree(
+                treeRoots[treeIdx],
+                treeColumnLogSizes[treeIdx],
+                hashFunction
+            );
         }
     }
 
@@ -80,8 +99,22 @@ library MerkleVerifier {
     function createMerkleTree(
         bytes32 root,
         uint32[] memory columnLogSizes
+    ) internal pure returns (MerkleTree memory tree) {
+        return createMerkleTree(root, columnLogSizes, HashFunction.KECCAK);
+    }
+
+    /// @notice Create single Merkle tree verifier using the given hash function
+    /// @param root Merkle tree root
+    /// @param columnLogSizes Log sizes for columns
+    /// @param hashFunction Hash function identifier (see HashFunction)
+    /// @return tree New Merkle tree instance
+    function createMerkleTree(
+        bytes32 root,
+        uint32[] memory columnLogSizes,
+        uint8 hashFunction
     ) internal pure returns (MerkleTree memory tree) {
         tree.root = root;
+        tree.hashFunction = hashFunction;
         tree.columnLogSizes = columnLogSizes;
         
         // Build n_columns_per_log_size arrays (matches Rust BTreeMap logic)
@@ -178,6 +211,7 @@ library MerkleVerifier {
             
             // Process layer and get new layer hashes
             lastLayerHashes = _processLayer(
+                tree.hashFunction,
                 layerLogSize,
                 nColumnsInLayer,
                 queriesPerLogSize,
@@ -244,6 +278,7 @@ library MerkleVerifier {
 
     /// @notice Process single layer of Merkle tree (matches Rust layer processing logic)
     function _processLayer(
+        uint8 hashFunction,
         uint32 layerLogSize,
         uint256 nColumnsInLayer,
         QueriesPerLogSize[] memory queriesPerLogSize,
@@ -273,6 +308,7 @@ library MerkleVerifier {
 
         // Process all nodes in this layer (matches Rust while loop)
         (tempLayerHashes, layerHashCount) = _processLayerNodes(
+            hashFunction,
             layerQueries,
             previousLayerHashes,
             nColumnsInLayer,
@@ -291,59 +327,75 @@ library MerkleVerifier {
     }
 
     /// @notice Hash node with column values (matches Rust hash_node with children_hashes: Some)
+    /// @param hashFunction Hash function identifier (see HashFunction)
     /// @param leftChild Left child hash
     /// @param rightChild Right child hash  
     /// @param columnValues Column values for this node
     /// @return Hash of node
     function _hashNode(
+        uint8 hashFunction,
         bytes32 leftChild,
         bytes32 rightChild, 
         uint32[] memory columnValues
     ) internal pure returns (bytes32) {
-        // Match Rust: NODE_PREFIX + left_child + right_child + column_values
-        bytes memory data = new bytes(64 + 64 + columnValues.length * 4);
+        // Match Rust: [NODE_PREFIX +] left_child + right_child + column_values
+        uint256 prefixBytes = _prefixBytes(hashFunction);
+        bytes memory data = new bytes(prefixBytes + 64 + columnValues.length * 4);
         
-        // NODE_PREFIX: "node" + 60 zero bytes
-        data[0] = 0x6e; // 'n'
-        data[1] = 0x6f; // 'o'
-        data[2] = 0x64; // 'd'
-        data[3] = 0x65; // 'e'
-        // bytes 4-63 are already zero
+        // KeccakMerkleHasher NODE_PREFIX: "node" + 60 zero bytes
+        if (prefixBytes != 0) {
+            data[0] = 0x6e; // 'n'
+            data[1] = 0x6f; // 'o'
+            data[2] = 0x64; // 'd'
+            data[3] = 0x65; // 'e'
+            // bytes 4-63 are already zero
+        }
         
         // Add left and right child hashes
         for (uint256 i = 0; i < 32; i++) {
-            data[64 + i] = leftChild[i];
-            data[96 + i] = rightChild[i];
+            data[prefixBytes + i] = leftChild[i];
+            data[prefixBytes + 32 + i] = rightChild[i];
         }
         
         // Add column values in little-endian format
         for (uint256 i = 0; i < columnValues.length; i++) {
-            _writeUint32LE(data, 128 + i * 4, columnValues[i]);
+            _writeUint32LE(data, prefixBytes + 64 + i * 4, columnValues[i]);
         }
         
-        return keccak256(data);
+        return HashFunction.hash(hashFunction, data);
     }
 
     /// @notice Hash leaf with column values (matches Rust hash_node with children_hashes: None)
+    /// @param hashFunction Hash function identifier (see HashFunction)
     /// @param columnValues Column values for this leaf
     /// @return Hash of leaf
-    function _hashLeaf(uint32[] memory columnValues) internal pure returns (bytes32) {
-        // Match Rust: LEAF_PREFIX + column_values
-        bytes memory data = new bytes(64 + columnValues.length * 4);
+    function _hashLeaf(uint8 hashFunction, uint32[] memory columnValues) internal pure returns (bytes32) {
+        // Match Rust: [LEAF_PREFIX +] column_values
+        uint256 prefixBytes = _prefixBytes(hashFunction);
+        bytes memory data = new bytes(prefixBytes + columnValues.length * 4);
         
-        // LEAF_PREFIX: "leaf" + 60 zero bytes
-        data[0] = 0x6c; // 'l'
-        data[1] = 0x65; // 'e'
-        data[2] = 0x61; // 'a'
-        data[3] = 0x66; // 'f'
-        // bytes 4-63 are already zero
+        // KeccakMerkleHasher LEAF_PREFIX: "leaf" + 60 zero bytes
+        if (prefixBytes != 0) {
+            data[0] = 0x6c; // 'l'
+            data[1] = 0x65; // 'e'
+            data[2] = 0x61; // 'a'
+            data[3] = 0x66; // 'f'
+            // bytes 4-63 are already zero
+        }
         
         // Add column values in little-endian format
         for (uint256 i = 0; i < columnValues.length; i++) {
-            _writeUint32LE(data, 64 + i * 4, columnValues[i]);
+            _writeUint32LE(data, prefixBytes + i * 4, columnValues[i]);
         }
         
-        return keccak256(data);
+        return HashFunction.hash(hashFunction, data);
+    }
+
+    /// @notice Length of the domain-separation prefix of node and leaf preimages
+    /// @dev Only the Keccak fork prefixes them; stwo's Blake2sMerkleHasher hashes
+    ///      the children and column values directly
+    function _prefixBytes(uint8 hashFunction) private pure returns (uint256) {
+        return hashFunction == HashFunction.BLAKE2S ? 0 : 64;
     }
 
     /// @notice Write uint32 value as little-endian bytes
@@ -375,6 +427,7 @@ library MerkleVerifier {
 
     /// @notice Process layer nodes to reduce stack depth
     function _processLayerNodes(
+        uint8 hashFunction,
         uint256[] memory layerQueries,
         LayerHash[] memory previousLayerHashes,
         uint256 nColumnsInLayer,
@@ -404,6 +457,7 @@ library MerkleVerifier {
 
             // Get node hashes and values
             (bytes32 nodeHash, uint256 newLayerQueryIndex) = _processNode(
+                hashFunction,
                 nodeIndex,
                 isFromLayerQuery,
                 layerQueryIndex,
@@ -459,6 +513,7 @@ library MerkleVerifier {
 
     /// @notice Process single node and return hash
     function _processNode(
+        uint8 hashFunction,
         uint256 nodeIndex,
         bool isFromLayerQuery,
         uint256 layerQueryIndex,
@@ -498,10 +553,10 @@ library MerkleVerifier {
         bytes32 nodeHash;
         if (hasChildren) {
             // Internal node: NODE_PREFIX + left + right + column_values
-            nodeHash = _hashNode(leftHash, rightHash, nodeValues);
+            nodeHash = _hashNode(hashFunction, leftHash, rightHash, nodeValues);
         } else {
             // Leaf node: LEAF_PREFIX + column_values
-            nodeHash = _hashLeaf(nodeValues);
+            nodeHash = _hashLeaf(hashFunction, nodeValues);
         }
 
         return (nodeHash, newLayerQueryIndex);
@@ -582,6 +637,88 @@ library MerkleVerifier {
         return nodeValues;
     }
 
+    /// @notice Encode a decommitment in the canonical byte layout (matches Rust contracts::decommitment)
+    /// @dev [u32 hashWitness.length][bytes32 each][u32 columnWitness.length][u32 each], big-endian
+    /// @param decommitment Decommitment to encode
+    /// @return encoded Canonical encoding
+    function encodeDecommitment(Decommitment memory decommitment) internal pure returns (bytes memory encoded) {
+        uint256 nHashes = decommitment.hashWitness.length;
+        uint256 nColumns = decommitment.columnWitness.length;
+        require(nHashes <= type(uint32).max && nColumns <= type(uint32).max, "Decommitment too long");
+
+        encoded = new bytes(8 + nHashes * 32 + nColumns * 4);
+        assembly {
+            let ptr := add(encoded, 0x20)
+            mstore(ptr, shl(224, nHashes))
+            ptr := add(ptr, 4)
+            let hashes := add(mload(decommitment), 0x20)
+            for { let i := 0 } lt(i, nHashes) { i := add(i, 1) } {
+                mstore(ptr, mload(add(hashes, mul(i, 0x20))))
+                ptr := add(ptr, 0x20)
+            }
+            // Each column value is written as a full word and the next write
+            // overlaps its low 28 bytes; the final word spills past the array
+            // into free memory, which is never read back
+            mstore(ptr, shl(224, nColumns))
+            ptr := add(ptr, 4)
+            let columns := add(mload(add(decommitment, 0x20)), 0x20)
+            for { let i := 0 } lt(i, nColumns) { i := add(i, 1) } {
+                mstore(ptr, shl(224, mload(add(columns, mul(i, 0x20)))))
+                ptr := add(ptr, 4)
+            }
+        }
+    }
+
+    /// @notice Decode a canonical decommitment encoding (see encodeDecommitment)
+    /// @dev Reverts with InvalidDecommitment on truncated input, trailing bytes
+    ///      or a column value that is not a canonical M31 (>= P)
+    /// @param encoded Canonical encoding
+    /// @return decommitment Decoded decommitment
+    function decodeDecommitment(bytes memory encoded) internal pure returns (Decommitment memory decommitment) {
+        uint256 length = encoded.length;
+        uint256 data;
+        assembly {
+            data := add(encoded, 0x20)
+        }
+
+        if (length < 4) revert InvalidDecommitment("Truncated hash witness length");
+        uint256 nHashes;
+        assembly {
+            nHashes := shr(224, mload(data))
+        }
+        uint256 columnsOffset = 4 + nHashes * 32;
+        if (length < columnsOffset + 4) revert InvalidDecommitment("Truncated hash witness");
+
+        uint256 nColumns;
+        assembly {
+            nColumns := shr(224, mload(add(data, columnsOffset)))
+        }
+        uint256 end = columnsOffset + 4 + nColumns * 4;
+        if (length < end) revert InvalidDecommitment("Truncated column witness");
+        if (length > end) revert InvalidDecommitment("Trailing bytes");
+
+        decommitment.hashWitness = new bytes32[](nHashes);
+        decommitment.columnWitness = new uint32[](nColumns);
+        bytes32[] memory hashWitness = decommitment.hashWitness;
+        uint32[] memory columnWitness = decommitment.columnWitness;
+        uint256 modulus = M31Field.MODULUS;
+        bool nonCanonical;
+        assembly {
+            let src := add(data, 4)
+            let dst := add(hashWitness, 0x20)
+            for { let i := 0 } lt(i, nHashes) { i := add(i, 1) } {
+                mstore(add(dst, mul(i, 0x20)), mload(add(src, mul(i, 0x20))))
+            }
+            src := add(data, add(columnsOffset, 4))
+            dst := add(columnWitness, 0x20)
+            for { let i := 0 } lt(i, nColumns) { i := add(i, 1) } {
+                let value := shr(224, mload(add(src, mul(i, 4))))
+                nonCanonical := or(nonCanonical, iszero(lt(value, modulus)))
+                mstore(add(dst, mul(i, 0x20)), value)
+            }
+        }
+        if (nonCanonical) revert InvalidDecommitment("Non-canonical column value");
+    }
 
     /// @notice Create verifier (alias for newVerifierSingleTree)
     /// @param root Merkle tree root
@@ -622,7 +759,7 @@ library MerkleVerifier {
         if (logSize == 0) return true;
         
         // Start with leaf hash
-        bytes32 currentHash = _hashLeaf(expectedValues);
+        bytes32 currentHash = _hashLeaf(tree.hashFunction, expectedValues);
         
         // Climb up the tree using witness hashes
         uint256 currentPos = position;
@@ -641,10 +778,10 @@ library MerkleVerifier {
             // Determine if current node is left or right child
             if (currentPos % 2 == 0) {
                 // Current is left child
-                currentHash = _hashNode(currentHash, siblingHash, emptyValues);
+                currentHash = _hashNode(tree.hashFunction, currentHash, siblingHash, emptyValues);
             } else {
                 // Current is right child  
-                currentHash = _hashNode(siblingHash, currentHash, emptyValues);
+                currentHash = _hashNode(tree.hashFunction, siblingHash, currentHash, emptyValues);
             }
             
             currentPos = currentPos / 2;
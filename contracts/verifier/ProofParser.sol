@@ -5,17 +5,45 @@ import "../fields/QM31Field.sol";
 import "../pcs/FriVerifier.sol";
 import "../vcs/MerkleVerifier.sol";
 import "../pcs/PcsConfig.sol";
+import "../vcs/HashFunction.sol";
 
 /// @title ProofParser
 /// @notice Helper library to parse proof data into Solidity structs
 library ProofParser {
     using QM31Field for QM31Field.QM31;
 
+    /// @notice Envelope magic, ASCII "STWO"
+    bytes4 internal constant MAGIC = 0x5354574f;
+
+    /// @notice Composition polynomial coefficients shipped and evaluated at the OODS point
+    uint8 internal constant VERSION_FULL_COMPOSITION = 1;
+    /// @notice Only composition OODS samples shipped; composition tree opened via FRI
+    uint8 internal constant VERSION_SAMPLED_COMPOSITION = 2;
+
+    /// @notice Every M31 is an ABI uint32 word (QM31 as four words)
+    uint8 internal constant FIELD_ENCODING_ABI_WORDS = 0;
+    /// @notice Every M31 packed into 4 bytes (QM31 into 16), see decodePacked
+    uint8 internal constant FIELD_ENCODING_PACKED = 1;
+
+    /// @notice Proof envelope describing how the rest of the proof is laid out
+    /// @param magic Must equal MAGIC
+    /// @param version Proof layout, see VERSION_* constants
+    /// @param hashFunction Channel and Merkle hash, see HashFunction
+    /// @param fieldEncoding Field element encoding, see FIELD_ENCODING_* constants
+    struct ProofHeader {
+        bytes4 magic;
+        uint8 version;
+        uint8 hashFunction;
+        uint8 fieldEncoding;
+    }
+
     struct Proof {
+        ProofHeader header;
         PcsConfig.Config config;
         bytes32[] commitments;
         QM31Field.QM31[][][] sampledValues;
-        MerkleVerifier.Decommitment[] decommitments;
+        /// @dev One per tree, each in the MerkleVerifier.encodeDecommitment layout
+        bytes[] decommitments;
         uint32[][] queriedValues;
         uint64 proofOfWork;
         FriVerifier.FriProof friProof;
@@ -84,6 +112,278 @@ library ProofParser {
         }
     }
 
+    /// @notice Check whether the proof layout version is understood by this verifier
+    function isSupportedVersion(uint8 version) internal pure returns (bool) {
+        return version == VERSION_FULL_COMPOSITION || version == VERSION_SAMPLED_COMPOSITION;
+    }
+
+    /// @notice Check whether the field encoding is understood by this verifier
+    function isSupportedFieldEncoding(uint8 fieldEncoding) internal pure returns (bool) {
+        return fieldEncoding == FIELD_ENCODING_ABI_WORDS || fieldEncoding == FIELD_ENCODING_PACKED;
+    }
+
+    /// @notice Reject proofs whose envelope this verifier cannot interpret
+    /// @param header Proof envelope
+    function validateHeader(ProofHeader memory header) internal pure {
+        require(header.magic == MAGIC, "Invalid proof magic");
+        require(isSupportedVersion(header.version), "Unsupported proof version");
+        require(HashFunction.isSupported(header.hashFunction), "Unsupported hash function");
+        require(isSupportedFieldEncoding(header.fieldEncoding), "Unsupported field encoding");
+    }
+
+    /// @notice Check that no composition coefficients were shipped
+    function isCompositionPolyEmpty(CompositionPoly memory compositionPoly) internal pure returns (bool) {
+        return compositionPoly.coeffs0.length == 0 &&
+            compositionPoly.coeffs1.length == 0 &&
+            compositionPoly.coeffs2.length == 0 &&
+            compositionPoly.coeffs3.length == 0;
+    }
+
+    /// @notice Decode a proof produced by Rust contracts::packed::encode_packed_proof
+    /// @dev Layout: header | config | commitments | sampledValues | decommitments |
+    ///      queriedValues | proofOfWork | friProof | compositionPoly, fields in struct order.
+    ///      Integers are big-endian, M31 values take 4 bytes, QM31 values 16 bytes,
+    ///      and every array or byte string is prefixed by a uint32 length.
+    ///      M31 values must be canonical (< P); anything else reverts.
+    /// @param data Packed proof
+    /// @return proof Decoded proof
+    function decodePacked(bytes calldata data) internal pure returns (Proof memory proof) {
+        uint256 pos = 0;
+        (proof.header, pos) = _readHeader(data, pos);
+        require(proof.header.fieldEncoding == FIELD_ENCODING_PACKED, "Not a packed proof");
+
+        (proof.config, pos) = _readConfig(data, pos);
+        (proof.commitments, pos) = _readBytes32Array(data, pos);
+        (proof.sampledValues, pos) = _readSampledValues(data, pos);
+        (proof.decommitments, pos) = _readDecommitments(data, pos);
+        (proof.queriedValues, pos) = _readM31Matrix(data, pos);
+        (proof.proofOfWork, pos) = _readU64(data, pos);
+        (proof.friProof, pos) = _readFriProof(data, pos);
+        (proof.compositionPoly, pos) = _readCompositionPoly(data, pos);
+
+        require(pos == data.length, "Trailing bytes in packed proof");
+    }
+
+    function _readHeader(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (ProofHeader memory header, uint256 next)
+    {
+        next = _advance(data, pos, 7);
+        uint256 word;
+        assembly {
+            word := calldataload(add(data.offset, pos))
+        }
+        header.magic = bytes4(bytes32(word));
+        header.version = uint8(word >> 216);
+        header.hashFunction = uint8(word >> 208);
+        header.fieldEncoding = uint8(word >> 200);
+    }
+
+    function _readConfig(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (PcsConfig.Config memory config, uint256 next)
+    {
+        uint32 nQueries;
+        (config.powBits, next) = _readU32(data, pos);
+        (config.friConfig.logBlowupFactor, next) = _readU32(data, next);
+        (config.friConfig.logLastLayerDegreeBound, next) = _readU32(data, next);
+        (nQueries, next) = _readU32(data, next);
+        config.friConfig.nQueries = nQueries;
+    }
+
+    function _readSampledValues(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (QM31Field.QM31[][][] memory sampledValues, uint256 next)
+    {
+        uint32 nTrees;
+        (nTrees, next) = _readU32(data, pos);
+        sampledValues = new QM31Field.QM31[][][](nTrees);
+        for (uint256 treeIdx = 0; treeIdx < nTrees; treeIdx++) {
+            uint32 nColumns;
+            (nColumns, next) = _readU32(data, next);
+            sampledValues[treeIdx] = new QM31Field.QM31[][](nColumns);
+            for (uint256 colIdx = 0; colIdx < nColumns; colIdx++) {
+                (sampledValues[treeIdx][colIdx], next) = _readQM31Array(data, next);
+            }
+        }
+    }
+
+    /// @notice Main-tree decommitments, each in the MerkleVerifier.encodeDecommitment layout
+    /// @dev Decoded and checked by MerkleVerifier.decodeDecommitment during verification
+    function _readDecommitments(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (bytes[] memory decommitments, uint256 next)
+    {
+        uint32 length;
+        (length, next) = _readU32(data, pos);
+        decommitments = new bytes[](length);
+        for (uint256 i = 0; i < length; i++) {
+            (decommitments[i], next) = _readBytes(data, next);
+        }
+    }
+
+    function _readFriProof(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (FriVerifier.FriProof memory friProof, uint256 next)
+    {
+        (friProof.firstLayer, next) = _readFriLayer(data, pos);
+
+        uint32 nInnerLayers;
+        (nInnerLayers, next) = _readU32(data, next);
+        friProof.innerLayers = new FriVerifier.FriLayerProof[](nInnerLayers);
+        for (uint256 i = 0; i < nInnerLayers; i++) {
+            (friProof.innerLayers[i], next) = _readFriLayer(data, next);
+        }
+
+        (friProof.lastLayerPoly, next) = _readQM31Array(data, next);
+    }
+
+    function _readFriLayer(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (FriVerifier.FriLayerProof memory layer, uint256 next)
+    {
+        (layer.friWitness, next) = _readQM31Array(data, pos);
+        (layer.decommitment, next) = _readBytes(data, next);
+        (layer.commitment, next) = _readBytes32(data, next);
+    }
+
+    function _readCompositionPoly(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (CompositionPoly memory compositionPoly, uint256 next)
+    {
+        (compositionPoly.coeffs0, next) = _readM31Array(data, pos);
+        (compositionPoly.coeffs1, next) = _readM31Array(data, next);
+        (compositionPoly.coeffs2, next) = _readM31Array(data, next);
+        (compositionPoly.coeffs3, next) = _readM31Array(data, next);
+    }
+
+    function _readM31Matrix(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (uint32[][] memory values, uint256 next)
+    {
+        uint32 length;
+        (length, next) = _readU32(data, pos);
+        values = new uint32[][](length);
+        for (uint256 i = 0; i < length; i++) {
+            (values[i], next) = _readM31Array(data, next);
+        }
+    }
+
+    function _readM31Array(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (uint32[] memory values, uint256 next)
+    {
+        uint32 length;
+        (length, pos) = _readU32(data, pos);
+        next = _advance(data, pos, uint256(length) * 4);
+        values = new uint32[](length);
+        uint256 modulus = M31Field.MODULUS;
+        bool nonCanonical;
+        assembly {
+            let src := add(data.offset, pos)
+            let dst := add(values, 0x20)
+            for { let i := 0 } lt(i, length) { i := add(i, 1) } {
+                let value := shr(224, calldataload(add(src, mul(i, 4))))
+                nonCanonical := or(nonCanonical, iszero(lt(value, modulus)))
+                mstore(add(dst, mul(i, 0x20)), value)
+            }
+        }
+        require(!nonCanonical, "Non-canonical M31 in packed proof");
+    }
+
+    function _readBytes32Array(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (bytes32[] memory values, uint256 next)
+    {
+        uint32 length;
+        (length, pos) = _readU32(data, pos);
+        next = _advance(data, pos, uint256(length) * 32);
+        values = new bytes32[](length);
+        assembly {
+            calldatacopy(add(values, 0x20), add(data.offset, pos), mul(length, 0x20))
+        }
+    }
+
+    function _readQM31Array(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (QM31Field.QM31[] memory values, uint256 next)
+    {
+        uint32 length;
+        (length, next) = _readU32(data, pos);
+        values = new QM31Field.QM31[](length);
+        for (uint256 i = 0; i < length; i++) {
+            (values[i], next) = _readQM31(data, next);
+        }
+    }
+
+    function _readQM31(bytes calldata data, uint256 pos)
+        private
+        pure
+        returns (QM31Field.QM31 memory value, uint256 next)
+    {
+        next = _advance(data, pos, 16);
+        uint256 word;
+        assembly {
+            word := calldataload(add(data.offset, pos))
+        }
+        value = QM31Field.QM31({
+            first: CM31Field.CM31({real: uint32(word >> 224), imag: uint32(word >> 192)}),
+            second: CM31Field.CM31({real: uint32(word >> 160), imag: uint32(word >> 128)})
+        });
+        require(
+            value.first.real < M31Field.MODULUS &&
+                value.first.imag < M31Field.MODULUS &&
+                value.second.real < M31Field.MODULUS &&
+                value.second.imag < M31Field.MODULUS,
+            "Non-canonical M31 in packed proof"
+        );
+    }
+
+    function _readBytes(bytes calldata data, uint256 pos) private pure returns (bytes memory value, uint256 next) {
+        uint32 length;
+        (length, pos) = _readU32(data, pos);
+        next = _advance(data, pos, length);
+        value = data[pos:next];
+    }
+
+    function _readBytes32(bytes calldata data, uint256 pos) private pure returns (bytes32 value, uint256 next) {
+        next = _advance(data, pos, 32);
+        assembly {
+            value := calldataload(add(data.offset, pos))
+        }
+    }
+
+    function _readU64(bytes calldata data, uint256 pos) private pure returns (uint64 value, uint256 next) {
+        next = _advance(data, pos, 8);
+        assembly {
+            value := shr(192, calldataload(add(data.offset, pos)))
+        }
+    }
+
+    function _readU32(bytes calldata data, uint256 pos) private pure returns (uint32 value, uint256 next) {
+        next = _advance(data, pos, 4);
+        assembly {
+            value := shr(224, calldataload(add(data.offset, pos)))
+        }
+    }
+
+    /// @notice Bounds-checked cursor advance
+    function _advance(bytes calldata data, uint256 pos, uint256 size) private pure returns (uint256 next) {
+        next = pos + size;
+        require(next <= data.length, "Packed proof truncated");
+    }
+
     // Internal Helper Functions
 
     /// @notice Extract composition trace OODS evaluation from sampled values
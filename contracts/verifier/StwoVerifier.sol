@@ -4,7 +4,7 @@ pragma solidity ^0.8.20;
 import "../core/FrameworkComponentLib.sol";
 import "../core/ComponentsLib.sol";
 import "../core/TraceLocationAllocatorLib.sol";
-import "../core/KeccakChannelLib.sol";
+import "../core/ChannelLib.sol";
 import "../core/CommitmentSchemeVerifierLib.sol";
 import "../pcs/PcsConfig.sol";
 import "../pcs/FriVerifier.sol";
@@ -14,21 +14,28 @@ import "../fields/QM31Field.sol";
 import "../vcs/MerkleVerifier.sol";
 import "./ProofParser.sol";
 import "../secure_poly/SecureCirclePoly.sol";
+import "./modules/FriAnswersModule.sol";
+import "./modules/FriDecommitModule.sol";
+import "./modules/MerkleModule.sol";
+import "./modules/OodsModule.sol";
 
 /// @title STWOVerifier
 /// @notice Generic STARK verifier
+/// @dev FRI, Merkle and OODS checks call the public libraries in ./modules, which must be
+///      deployed first and linked into this contract's bytecode, keeping every piece under
+///      the EIP-170 code size limit.
 contract STWOVerifier {
     using QM31Field for QM31Field.QM31;
     using FrameworkComponentLib for FrameworkComponentLib.ComponentState;
     using ComponentsLib for ComponentsLib.Components;
     using TraceLocationAllocatorLib for TraceLocationAllocatorLib.AllocatorState;
-    using KeccakChannelLib for KeccakChannelLib.ChannelState;
+    using ChannelLib for ChannelLib.ChannelState;
     using CommitmentSchemeVerifierLib for CommitmentSchemeVerifierLib.VerifierState;
     using FriVerifier for FriVerifier.FriVerifierState;
     using PcsConfig for PcsConfig.Config;
 
     /// @notice Channel state for Fiat-Shamir transform
-    KeccakChannelLib.ChannelState private _channel;
+    ChannelLib.ChannelState private _channel;
 
     /// @notice Commitment scheme verifier state
     CommitmentSchemeVerifierLib.VerifierState private _commitmentScheme;
@@ -65,11 +72,28 @@ contract STWOVerifier {
         bytes32 digest,
         uint32 nDraws
     ) external returns (bool) {
+        require(
+            proof.header.fieldEncoding == ProofParser.FIELD_ENCODING_ABI_WORDS,
+            "Packed proofs must use verifyPacked"
+        );
+        return _verifyProof(proof, params, treeRoots, treeColumnLogSizes, digest, nDraws);
+    }
+
+    /// @notice Verify a STARK proof in the packed encoding (see ProofParser.decodePacked)
+    function verifyPacked(
+        bytes calldata packedProof,
+        VerificationParams calldata params,
+        bytes32[] memory treeRoots,
+        uint32[][] memory treeColumnLogSizes,
+        bytes32 digest,
+        uint32 nDraws
+    ) external returns (bool) {
+        ProofParser.Proof memory proof = ProofParser.decodePacked(packedProof);
         return _verifyProof(proof, params, treeRoots, treeColumnLogSizes, digest, nDraws);
     }
 
     function _verifyProof(
-        ProofParser.Proof calldata proof,
+        ProofParser.Proof memory proof,
         VerificationParams calldata params,
         bytes32[] memory treeRoots,
         uint32[][] memory treeColumnLogSizes,
@@ -79,35 +103,35 @@ contract STWOVerifier {
         if (_components.isInitialized) {
             _components.reset();
         }
+        ProofParser.validateHeader(proof.header);
         
-        SecureCirclePoly.SecurePoly memory poly = _createSecurePoly(proof.compositionPoly);
         _initializeVerification(proof, treeRoots, treeColumnLogSizes, digest, nDraws);
         
-        return _performVerificationSteps(proof, params, poly);
+        return _performVerificationSteps(proof, params);
     }
 
     function _initializeVerification(
-        ProofParser.Proof calldata proof,
+        ProofParser.Proof memory proof,
         bytes32[] memory treeRoots,
         uint32[][] memory treeColumnLogSizes,
         bytes32 digest,
         uint32 nDraws
     ) private {
-        KeccakChannelLib.initializeWith(_channel, digest, nDraws);
+        ChannelLib.initializeWith(_channel, digest, nDraws, proof.header.hashFunction);
         CommitmentSchemeVerifierLib.initialize(
             _commitmentScheme,
             proof.config,
             treeRoots,
-            treeColumnLogSizes
+            treeColumnLogSizes,
+            proof.header.hashFunction
         );        
         _channel.drawSecureFelt();
 
     }
 
     function _performVerificationSteps(
-        ProofParser.Proof calldata proof,
-        VerificationParams calldata params,
-        SecureCirclePoly.SecurePoly memory poly
+        ProofParser.Proof memory proof,
+        VerificationParams calldata params
     ) private returns (bool) {
         if (!_performCompositionCommit(proof, params)) return false;
         
@@ -118,12 +142,12 @@ contract STWOVerifier {
             params
         );
         
-        if (!_performOodsVerification(proof, poly, oodsPoint)) return false;
+        if (!_performOodsVerification(proof, oodsPoint)) return false;
         return _performFriVerification(proof, samplePoints);
     }
 
     function _performCompositionCommit(
-        ProofParser.Proof calldata proof,
+        ProofParser.Proof memory proof,
         VerificationParams calldata params
     ) private returns (bool) {
         uint32[] memory compositionSizes = new uint32[](4);
@@ -140,18 +164,29 @@ contract STWOVerifier {
     }
 
     function _performOodsVerification(
-        ProofParser.Proof calldata proof,
-        SecureCirclePoly.SecurePoly memory poly,
+        ProofParser.Proof memory proof,
         CirclePoint.Point memory oodsPoint
     ) private pure returns (bool) {
         (QM31Field.QM31 memory compositionOodsEval, bool extractSuccess) = ProofParser.extractCompositionOodsEval(proof);
         require(extractSuccess, "Failed to extract composition OODS eval");
-        
+
+        if (proof.header.version == ProofParser.VERSION_SAMPLED_COMPOSITION) {
+            // The samples are bound to the composition commitment by the FRI opening
+            // of the last tree, which is all the shipped coefficients are checked
+            // against in VERSION_FULL_COMPOSITION too
+            require(
+                ProofParser.isCompositionPolyEmpty(proof.compositionPoly),
+                "Unexpected composition coefficients"
+            );
+            return true;
+        }
+
+        SecureCirclePoly.SecurePoly memory poly = _createSecurePoly(proof.compositionPoly);
         return _verifyOods(oodsPoint, compositionOodsEval, poly);
     }
 
     function _performFriVerification(
-        ProofParser.Proof calldata proof,
+        ProofParser.Proof memory proof,
         ComponentsLib.TreeVecMaskPoints memory samplePoints
     ) private returns (bool) {
         QM31Field.QM31[] memory flattenedSampledValues = ProofParser.flattenCols(proof.sampledValues);
@@ -178,7 +213,7 @@ contract STWOVerifier {
     }
 
     function _performFinalFriCheck(
-        ProofParser.Proof calldata proof,
+        ProofParser.Proof memory proof,
         QM31Field.QM31 memory randomCoeff2,
         ComponentsLib.TreeVecMaskPoints memory samplePoints
     ) private returns (bool) {
@@ -510,15 +545,7 @@ contract STWOVerifier {
         QM31Field.QM31 memory compositionOodsEval,
         SecureCirclePoly.SecurePoly memory poly
     ) internal pure returns (bool) {
-   
-    
-        QM31Field.QM31 memory finalResult = SecureCirclePoly.evalAtPoint(poly, oodsPoint);
-        
-        require(
-            QM31Field.eq(finalResult, compositionOodsEval),
-            "OODS values do not match"
-        );
-        return true;
+        return OodsModule.verifyOods(oodsPoint, compositionOodsEval, poly);
     }
 
     /// @notice Verify proof of work
@@ -536,7 +563,7 @@ contract STWOVerifier {
     /// @notice Verify FRI proof
     function _verifyFri(
         FriVerifier.PointSample[][][] memory pointSamples,
-        MerkleVerifier.Decommitment[] memory decommitments,
+        bytes[] memory decommitments,
         uint32[][] memory queriedValues,
         QM31Field.QM31 memory randomCoeff
     ) internal returns (bool) {
@@ -559,7 +586,7 @@ contract STWOVerifier {
         uint32[][] memory commitmentColumnLogSizes = _commitmentScheme
             .columnLogSizes();
             
-        QM31Field.QM31[][] memory friAnswersResult = FriVerifier.friAnswers(
+        QM31Field.QM31[][] memory friAnswersResult = FriAnswersModule.friAnswers(
             commitmentColumnLogSizes,
             pointSamples,
             randomCoeff,
@@ -568,7 +595,7 @@ contract STWOVerifier {
             nColumnsPerLogSizeData
         );
         
-        bool decommitSuccess = FriVerifier.decommit(
+        bool decommitSuccess = FriDecommitModule.decommit(
             _friVerifier,
             friAnswersResult
         );
@@ -583,7 +610,7 @@ contract STWOVerifier {
         uint32[] memory queriedValues,
         MerkleVerifier.Decommitment memory decommitment
     ) internal pure {
-        MerkleVerifier.verify(
+        MerkleModule.verify(
             tree,
             queriesPerLogSize,
             queriedValues,
@@ -592,8 +619,9 @@ contract STWOVerifier {
     }
 
     /// @notice Verify Merkle tree decommitments for all trees
+    /// @param decommitments One canonical encoding per tree (MerkleVerifier.encodeDecommitment)
     function _verifyMerkleDecommitments(
-        MerkleVerifier.Decommitment[] memory decommitments,
+        bytes[] memory decommitments,
         uint32[][] memory queriedValues,
         FriVerifier.QueryPositionsByLogSize memory queryPositions
     ) internal view returns (bool) {
@@ -624,7 +652,8 @@ contract STWOVerifier {
                 root: _commitmentScheme.getTreeRoot(treeIdx),
                 columnLogSizes: columnLogSizes,
                 logSizes: logSizes,
-                nColumnsPerLogSize: nColumnsPerLogSize
+                nColumnsPerLogSize: nColumnsPerLogSize,
+                hashFunction: _channel.hashFunction
             });
 
             MerkleVerifier.QueriesPerLogSize[]
@@ -636,7 +665,7 @@ contract STWOVerifier {
                 tree,
                 queriesPerLogSize,
                 queriedValues[treeIdx],
-                decommitments[treeIdx]
+                MerkleVerifier.decodeDecommitment(decommitments[treeIdx])
             );
             
         }
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "./StwoVerifier.sol";
+import "../proxy/ERC1967.sol";
+
+/// @title UpgradeableSTWOVerifier
+/// @notice STWOVerifier with the UUPS upgrade entry point, deployed behind UUPSVerifierProxy
+/// @dev The owner is kept in the EIP-1967 admin slot of the proxy, clear of the
+///      verifier state. Calls to the implementation itself still verify proofs, which
+///      the deployer relies on to check a new implementation before switching to it.
+contract UpgradeableSTWOVerifier is STWOVerifier {
+    address private immutable _self = address(this);
+
+    error NotOwner();
+    error NotDelegated();
+    error OnlyDelegated();
+    error AlreadyInitialized();
+    error UnsupportedProxiableUUID(bytes32 uuid);
+
+    modifier onlyProxy() {
+        if (address(this) == _self || ERC1967.getImplementation() != _self) {
+            revert OnlyDelegated();
+        }
+        _;
+    }
+
+    modifier onlyOwner() {
+        if (msg.sender != ERC1967.getAdmin()) {
+            revert NotOwner();
+        }
+        _;
+    }
+
+    /// @notice Set the owner; called once by UUPSVerifierProxy's constructor
+    function initialize(address owner_) external onlyProxy {
+        if (ERC1967.getAdmin() != address(0)) {
+            revert AlreadyInitialized();
+        }
+        ERC1967.setAdmin(owner_);
+    }
+
+    /// @notice Account allowed to upgrade the proxy
+    function owner() external view onlyProxy returns (address) {
+        return ERC1967.getAdmin();
+    }
+
+    function transferOwnership(address newOwner) external onlyProxy onlyOwner {
+        ERC1967.setAdmin(newOwner);
+    }
+
+    /// @notice Switch the proxy to `newImplementation`, which must be UUPS-compatible
+    function upgradeTo(address newImplementation) external onlyProxy onlyOwner {
+        bytes32 uuid = UpgradeableSTWOVerifier(newImplementation).proxiableUUID();
+        if (uuid != ERC1967.IMPLEMENTATION_SLOT) {
+            revert UnsupportedProxiableUUID(uuid);
+        }
+        ERC1967.setImplementation(newImplementation);
+    }
+
+    /// @notice ERC-1822 marker; reverts through a proxy so proxies cannot be chained
+    function proxiableUUID() external view returns (bytes32) {
+        if (address(this) != _self) {
+            revert NotDelegated();
+        }
+        return ERC1967.IMPLEMENTATION_SLOT;
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../../pcs/FriVerifier.sol";
+
+/// @title FriAnswersModule
+/// @notice Externally linked entry point for the FRI quotient answers
+/// @dev Public library functions are called with DELEGATECALL, so the quotient code
+///      lives in this library's bytecode instead of STWOVerifier's (EIP-170).
+library FriAnswersModule {
+    /// @notice See FriVerifier.friAnswers
+    function friAnswers(
+        uint32[][] memory columnLogSizes,
+        FriVerifier.PointSample[][][] memory samples,
+        QM31Field.QM31 memory randomCoeff,
+        FriVerifier.QueryPositionsByLogSize memory queryPositionsByLogSize,
+        uint32[][] memory queriedValues,
+        uint32[][][] memory nColumnsPerLogSize
+    ) public pure returns (QM31Field.QM31[][] memory) {
+        return FriVerifier.friAnswers(
+            columnLogSizes,
+            samples,
+            randomCoeff,
+            queryPositionsByLogSize,
+            queriedValues,
+            nColumnsPerLogSize
+        );
+    }
+}
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../../pcs/FriVerifier.sol";
+
+/// @title FriDecommitModule
+/// @notice Externally linked entry point for the FRI layer decommitments and folding
+/// @dev Takes the verifier state as a storage reference so it is not ABI-encoded for the
+///      DELEGATECALL; the slot belongs to the calling STWOVerifier.
+library FriDecommitModule {
+    /// @notice See FriVerifier.decommit
+    function decommit(
+        FriVerifier.FriVerifierState storage friVerifierState,
+        QM31Field.QM31[][] memory firstLayerQueryEvals
+    ) public view returns (bool) {
+        return FriVerifier.decommit(friVerifierState, firstLayerQueryEvals);
+    }
+}
@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../../vcs/MerkleVerifier.sol";
+
+/// @title MerkleModule
+/// @notice Externally linked entry point for trace Merkle decommitments
+library MerkleModule {
+    /// @notice See MerkleVerifier.verify; reverts on an invalid decommitment
+    function verify(
+        MerkleVerifier.MerkleTree memory tree,
+        MerkleVerifier.QueriesPerLogSize[] memory queriesPerLogSize,
+        uint32[] memory queriedValues,
+        MerkleVerifier.Decommitment memory decommitment
+    ) public pure {
+        MerkleVerifier.verify(tree, queriesPerLogSize, queriedValues, decommitment);
+    }
+}
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.20;
+
+import "../../secure_poly/SecureCirclePoly.sol";
+
+/// @title OodsModule
+/// @notice Externally linked entry point for the out-of-domain composition check
+library OodsModule {
+    /// @notice Check that the composition polynomial evaluates to `compositionOodsEval`
+    ///         at `oodsPoint`; reverts otherwise
+    function verifyOods(
+        CirclePoint.Point memory oodsPoint,
+        QM31Field.QM31 memory compositionOodsEval,
+        SecureCirclePoly.SecurePoly memory poly
+    ) public pure returns (bool) {
+        QM31Field.QM31 memory finalResult = SecureCirclePoly.evalAtPoint(poly, oodsPoint);
+        require(QM31Field.eq(finalResult, compositionOodsEval), "OODS values do not match");
+        return true;
+    }
+}
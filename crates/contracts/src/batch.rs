@@ -0,0 +1,117 @@
+//! Public inputs of proofs that batch many independent circuit instances.
+//!
+//! Every instance contributes its input and output, all of the same length, so
+//! a consumer contract can decode the list and record each result. The layout
+//! matches `contracts/examples/BatchResultRegistry.sol`:
+//!
+//! ```text
+//! [n_instances, input_len, output_len, input_0.., output_0.., input_1.., output_1.., ...]
+//! ```
+
+/// Number of header words before the first instance
+pub const BATCH_HEADER_LEN: usize = 3;
+
+/// Input and output of one batched instance, as M31 values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceResult {
+    pub input: Vec<u32>,
+    pub output: Vec<u32>,
+}
+
+/// Flatten `results` into batch public inputs.
+///
+/// Fails on an empty batch or when instances disagree on input or output length.
+pub fn encode_batch(results: &[InstanceResult]) -> Result<Vec<u32>, String> {
+    let first = results.first().ok_or("Empty batch")?;
+    let (input_len, output_len) = (first.input.len(), first.output.len());
+    if let Some(index) = results
+        .iter()
+        .position(|r| r.input.len() != input_len || r.output.len() != output_len)
+    {
+        return Err(format!(
+            "Instance {} does not have {} inputs and {} outputs",
+            index, input_len, output_len
+        ));
+    }
+
+    let mut public_inputs =
+        Vec::with_capacity(BATCH_HEADER_LEN + results.len() * (input_len + output_len));
+    public_inputs.extend([results.len() as u32, input_len as u32, output_len as u32]);
+    for result in results {
+        public_inputs.extend(&result.input);
+        public_inputs.extend(&result.output);
+    }
+    Ok(public_inputs)
+}
+
+/// Inverse of [`encode_batch`]
+pub fn decode_batch(public_inputs: &[u32]) -> Result<Vec<InstanceResult>, String> {
+    let [n_instances, input_len, output_len] = match public_inputs {
+        [n, i, o, ..] => [*n as usize, *i as usize, *o as usize],
+        _ => return Err("Missing batch header".to_string()),
+    };
+    let instance_len = input_len + output_len;
+    if public_inputs.len() != BATCH_HEADER_LEN + n_instances * instance_len {
+        return Err(format!(
+            "Expected {} instances of {} values, got {} values",
+            n_instances,
+            instance_len,
+            public_inputs.len() - BATCH_HEADER_LEN
+        ));
+    }
+    if instance_len == 0 {
+        return Ok(vec![
+            InstanceResult {
+                input: vec![],
+                output: vec![],
+            };
+            n_instances
+        ]);
+    }
+
+    Ok(public_inputs[BATCH_HEADER_LEN..]
+        .chunks_exact(instance_len)
+        .map(|instance| InstanceResult {
+            input: instance[..input_len].to_vec(),
+            output: instance[input_len..].to_vec(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_round_trip() {
+        let results: Vec<InstanceResult> = (0..5)
+            .map(|i| InstanceResult {
+                input: vec![i, i + 1],
+                output: vec![i * 10],
+            })
+            .collect();
+        let public_inputs = encode_batch(&results).unwrap();
+        assert_eq!(&public_inputs[..5], &[5, 2, 1, 0, 1]);
+        assert_eq!(public_inputs.len(), BATCH_HEADER_LEN + 5 * 3);
+        assert_eq!(decode_batch(&public_inputs).unwrap(), results);
+    }
+
+    #[test]
+    fn test_batch_errors() {
+        assert!(encode_batch(&[]).is_err());
+        let uneven = [
+            InstanceResult {
+                input: vec![1],
+                output: vec![2],
+            },
+            InstanceResult {
+                input: vec![1, 2],
+                output: vec![3],
+            },
+        ];
+        assert!(encode_batch(&uneven).unwrap_err().contains("Instance 1"));
+
+        assert!(decode_batch(&[1, 2]).is_err());
+        assert!(decode_batch(&[2, 1, 1, 0, 1, 2]).is_err());
+    }
+}
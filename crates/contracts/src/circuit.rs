@@ -0,0 +1,470 @@
+//! Circuit abstraction and registry so tools can drive any AIR by name.
+
+use std::any::Any;
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use stwo::core::channel::Channel;
+use stwo::core::pcs::PcsConfig;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::BackendForChannel;
+use stwo_constraint_framework::{FrameworkComponent, FrameworkEval};
+
+use crate::{
+    hash::{Blake2s, HashFunction, Keccak, SolidityHasher},
+    prover::{
+        max_log_degree_bound, with_committed_traces, SolidityProof, SolidityProverConfig,
+        TraceEvals,
+    },
+    VerifierInput,
+};
+
+/// Traces of one circuit instance plus the metadata fixing its shape
+pub struct CircuitTrace<M> {
+    pub metadata: M,
+    pub preprocessed: TraceEvals,
+    pub trace: TraceEvals,
+    /// Committed only when non-empty
+    pub interaction: TraceEvals,
+}
+
+/// A circuit the tools can prove and verify on-chain
+pub trait SolidityCircuit {
+    type Eval: FrameworkEval + Sync;
+    /// Trace shape, e.g. log sizes; enough to rebuild the components
+    type Metadata;
+    /// Statement being proven, parsed from the command line
+    type Input: FromStr<Err: Display>;
+
+    /// Registry key
+    fn name(&self) -> &'static str;
+
+    /// Input used when none is given
+    fn default_input(&self) -> Self::Input;
+
+    fn components(&self, metadata: &Self::Metadata) -> Vec<FrameworkComponent<Self::Eval>>;
+
+    fn generate_trace(&self, input: &Self::Input) -> CircuitTrace<Self::Metadata>;
+
+    /// Interaction trace of AIRs with lookups, generated once the main trace
+    /// is committed from elements drawn from `channel`. Stores the elements and
+    /// claimed sums in `metadata` for [`Self::components`], and replaces
+    /// [`CircuitTrace::interaction`] when non-empty.
+    fn draw_interaction(
+        &self,
+        _metadata: &mut Self::Metadata,
+        _channel: &mut impl Channel,
+    ) -> TraceEvals {
+        Vec::new()
+    }
+
+    /// M31 values the proof attests to, e.g. a computed output
+    fn public_inputs(&self, input: &Self::Input, metadata: &Self::Metadata) -> Vec<u32>;
+
+    /// Mix [`Self::public_inputs`] into the transcript before the first
+    /// commitment, so a consumer contract can bind them to the channel digest
+    fn binds_public_inputs(&self) -> bool {
+        false
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig::default()
+    }
+}
+
+/// Unconverted proof of a registered circuit, see [`DynCircuit::prove_unconverted`]
+pub struct CircuitProof {
+    /// Name of the circuit that produced the proof
+    pub circuit: &'static str,
+    pub proof: HashedProof,
+    pub public_inputs: Vec<u32>,
+    /// The circuit's [`SolidityCircuit::Metadata`], to rebuild its components
+    metadata: Box<dyn Any>,
+}
+
+/// [`SolidityProof`] for one of the hash functions checked on-chain
+pub enum HashedProof {
+    Keccak(SolidityProof<Keccak>),
+    Blake2s(SolidityProof<Blake2s>),
+}
+
+/// Result of proving a registered circuit
+pub struct ProvedCircuit {
+    pub verifier_input: VerifierInput,
+    pub public_inputs: Vec<u32>,
+}
+
+/// Object-safe view of a [`SolidityCircuit`], as stored in [`CircuitRegistry`]
+pub trait DynCircuit {
+    fn name(&self) -> &'static str;
+
+    fn default_pcs_config(&self) -> PcsConfig;
+
+    /// Prove `input` (or the default input) without checking or converting the proof
+    fn prove_unconverted(
+        &self,
+        input: Option<&str>,
+        hash_function: HashFunction,
+        config: SolidityProverConfig,
+    ) -> Result<CircuitProof, Box<dyn std::error::Error>>;
+
+    /// Check a proof of this circuit off-chain
+    fn verify(&self, proof: &CircuitProof) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Convert a proof of this circuit for `STWOVerifier`
+    fn convert(&self, proof: CircuitProof) -> Result<ProvedCircuit, Box<dyn std::error::Error>>;
+
+    /// Prove, check and convert `input` (or the default input)
+    fn prove(
+        &self,
+        input: Option<&str>,
+        hash_function: HashFunction,
+        config: SolidityProverConfig,
+    ) -> Result<ProvedCircuit, Box<dyn std::error::Error>> {
+        let proof = self.prove_unconverted(input, hash_function, config)?;
+        self.verify(&proof)?;
+        self.convert(proof)
+    }
+}
+
+impl<C: SolidityCircuit> DynCircuit for C
+where
+    C::Metadata: 'static,
+{
+    fn name(&self) -> &'static str {
+        SolidityCircuit::name(self)
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        SolidityCircuit::default_pcs_config(self)
+    }
+
+    fn prove_unconverted(
+        &self,
+        input: Option<&str>,
+        hash_function: HashFunction,
+        config: SolidityProverConfig,
+    ) -> Result<CircuitProof, Box<dyn std::error::Error>> {
+        let input = match input {
+            Some(input) => input
+                .parse()
+                .map_err(|e| format!("Invalid input for {}: {}", SolidityCircuit::name(self), e))?,
+            None => self.default_input(),
+        };
+
+        let CircuitTrace {
+            mut metadata,
+            preprocessed,
+            trace,
+            interaction,
+        } = self.generate_trace(&input);
+        let public_inputs = self.public_inputs(&input, &metadata);
+        let mixed_inputs: &[u32] = if self.binds_public_inputs() {
+            &public_inputs
+        } else {
+            &[]
+        };
+
+        let traces = (preprocessed, trace, interaction);
+        let proof = match hash_function {
+            HashFunction::Keccak => HashedProof::Keccak(prove_circuit(
+                self,
+                &mut metadata,
+                mixed_inputs,
+                traces,
+                config,
+            )?),
+            HashFunction::Blake2s => HashedProof::Blake2s(prove_circuit(
+                self,
+                &mut metadata,
+                mixed_inputs,
+                traces,
+                config,
+            )?),
+            HashFunction::Poseidon252 => {
+                return Err(
+                    format!("{:?} proofs cannot be verified on-chain yet", hash_function).into(),
+                )
+            }
+        };
+
+        Ok(CircuitProof {
+            circuit: SolidityCircuit::name(self),
+            proof,
+            public_inputs,
+            metadata: Box::new(metadata),
+        })
+    }
+
+    fn verify(&self, proof: &CircuitProof) -> Result<(), Box<dyn std::error::Error>> {
+        let components = self.components(metadata_of(self, proof)?);
+        match &proof.proof {
+            HashedProof::Keccak(proof) => proof.verify(&components),
+            HashedProof::Blake2s(proof) => proof.verify(&components),
+        }
+    }
+
+    fn convert(&self, proof: CircuitProof) -> Result<ProvedCircuit, Box<dyn std::error::Error>> {
+        let components = self.components(metadata_of(self, &proof)?);
+        let verifier_input = match proof.proof {
+            HashedProof::Keccak(proof) => proof.convert(components)?,
+            HashedProof::Blake2s(proof) => proof.convert(components)?,
+        };
+        Ok(ProvedCircuit {
+            verifier_input,
+            public_inputs: proof.public_inputs,
+        })
+    }
+}
+
+/// Commit the preprocessed, main and interaction traces of `circuit` in turn,
+/// drawing its interaction trace after the main commitment, and prove
+fn prove_circuit<C, H>(
+    circuit: &C,
+    metadata: &mut C::Metadata,
+    public_inputs: &[u32],
+    (preprocessed, trace, interaction): (TraceEvals, TraceEvals, TraceEvals),
+    config: SolidityProverConfig,
+) -> Result<SolidityProof<H>, Box<dyn std::error::Error>>
+where
+    C: SolidityCircuit,
+    H: SolidityHasher,
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    with_committed_traces::<H, _>(
+        max_log_degree_bound(&circuit.components(metadata))?,
+        public_inputs,
+        preprocessed,
+        trace,
+        config,
+        |mut prover| {
+            let drawn = circuit.draw_interaction(metadata, prover.channel());
+            let interaction = if drawn.is_empty() { interaction } else { drawn };
+            if !interaction.is_empty() {
+                prover.commit(interaction);
+            }
+            prover.prove_unconverted(&circuit.components(metadata))
+        },
+    )
+}
+
+/// Metadata of `proof`, provided it was produced by `circuit`
+fn metadata_of<'a, C: SolidityCircuit>(
+    circuit: &C,
+    proof: &'a CircuitProof,
+) -> Result<&'a C::Metadata, String>
+where
+    C::Metadata: 'static,
+{
+    let name = SolidityCircuit::name(circuit);
+    if proof.circuit != name {
+        return Err(format!("Proof of {} passed to {}", proof.circuit, name));
+    }
+    proof
+        .metadata
+        .downcast_ref()
+        .ok_or_else(|| format!("Proof metadata does not belong to {}", name))
+}
+
+/// Circuits available to the CLI, tests and benchmarks, keyed by name
+#[derive(Default)]
+pub struct CircuitRegistry {
+    circuits: BTreeMap<&'static str, Box<dyn DynCircuit>>,
+}
+
+impl CircuitRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a circuit, failing if another circuit is registered under the same name
+    pub fn register<C: SolidityCircuit + 'static>(
+        &mut self,
+        circuit: C,
+    ) -> Result<&mut Self, String>
+    where
+        C::Metadata: 'static,
+    {
+        match self.circuits.entry(SolidityCircuit::name(&circuit)) {
+            Entry::Occupied(entry) => Err(format!("Circuit {} already registered", entry.key())),
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(circuit));
+                Ok(self)
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&dyn DynCircuit, String> {
+        self.circuits
+            .get(name)
+            .map(|circuit| circuit.as_ref())
+            .ok_or_else(|| {
+                format!(
+                    "Unknown circuit: {} (available: {})",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                )
+            })
+    }
+
+    /// Registered names in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.circuits.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynCircuit> {
+        self.circuits.values().map(|circuit| circuit.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Zero;
+    use stwo::{
+        core::{fields::m31::BaseField, fields::qm31::SecureField, poly::circle::CanonicCoset},
+        prover::{
+            backend::{simd::SimdBackend, Col, Column},
+            poly::circle::CircleEvaluation,
+        },
+    };
+    use stwo_constraint_framework::{EvalAtRow, TraceLocationAllocator};
+
+    /// `a^2 = b` on every row
+    struct SquareEval {
+        log_size: u32,
+    }
+
+    impl FrameworkEval for SquareEval {
+        fn log_size(&self) -> u32 {
+            self.log_size
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            self.log_size + 1
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = eval.next_trace_mask();
+            let b = eval.next_trace_mask();
+            eval.add_constraint(a.clone() * a - b);
+            eval
+        }
+    }
+
+    /// Squares over `2^input` rows, registered as `name`
+    struct SquareCircuit {
+        name: &'static str,
+    }
+
+    impl SolidityCircuit for SquareCircuit {
+        type Eval = SquareEval;
+        type Metadata = u32;
+        type Input = u32;
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn default_input(&self) -> u32 {
+            5
+        }
+
+        fn components(&self, log_size: &u32) -> Vec<FrameworkComponent<SquareEval>> {
+            vec![FrameworkComponent::new(
+                &mut TraceLocationAllocator::default(),
+                SquareEval {
+                    log_size: *log_size,
+                },
+                SecureField::zero(),
+            )]
+        }
+
+        fn generate_trace(&self, log_size: &u32) -> CircuitTrace<u32> {
+            let mut a = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+            let mut b = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+            for row in 0..1 << log_size {
+                let value = BaseField::from(row as u32 + 2);
+                a.set(row, value);
+                b.set(row, value * value);
+            }
+            let domain = CanonicCoset::new(*log_size).circle_domain();
+            CircuitTrace {
+                metadata: *log_size,
+                preprocessed: vec![],
+                trace: vec![
+                    CircleEvaluation::new(domain, a),
+                    CircleEvaluation::new(domain, b),
+                ],
+                interaction: vec![],
+            }
+        }
+
+        fn public_inputs(&self, _input: &u32, _log_size: &u32) -> Vec<u32> {
+            vec![]
+        }
+    }
+
+    fn registry() -> CircuitRegistry {
+        let mut registry = CircuitRegistry::new();
+        registry
+            .register(SquareCircuit { name: "square" })
+            .unwrap()
+            .register(SquareCircuit { name: "other" })
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let mut registry = registry();
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            vec!["other", "square"]
+        );
+        assert_eq!(registry.get("square").unwrap().name(), "square");
+
+        let missing = registry.get("cube").err().unwrap();
+        assert!(missing.contains("available: other, square"), "{}", missing);
+
+        let duplicate = registry
+            .register(SquareCircuit { name: "square" })
+            .err()
+            .unwrap();
+        assert!(duplicate.contains("already registered"), "{}", duplicate);
+        assert_eq!(registry.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_prove_verify_convert() {
+        let registry = registry();
+        let circuit = registry.get("square").unwrap();
+        let config = SolidityProverConfig::from(circuit.default_pcs_config());
+
+        let proof = circuit
+            .prove_unconverted(Some("4"), HashFunction::Keccak, config)
+            .unwrap();
+        circuit.verify(&proof).unwrap();
+
+        // A proof is only accepted by the circuit that produced it
+        let other = registry.get("other").unwrap();
+        assert!(other.verify(&proof).is_err());
+
+        let log_blowup = config.pcs_config.fri_config.log_blowup_factor;
+        let proved = circuit.convert(proof).unwrap();
+        assert_eq!(
+            proved.verifier_input.treeColumnLogSizes,
+            vec![vec![], vec![4 + log_blowup; 2]]
+        );
+
+        let proved = circuit.prove(None, HashFunction::Blake2s, config).unwrap();
+        assert_eq!(
+            proved.verifier_input.treeColumnLogSizes[1],
+            vec![5 + log_blowup; 2]
+        );
+        assert!(circuit
+            .prove(None, HashFunction::Poseidon252, config)
+            .is_err());
+    }
+}
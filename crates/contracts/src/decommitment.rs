@@ -0,0 +1,177 @@
+//! Canonical byte encoding of Merkle decommitments, shared with
+//! `MerkleVerifier.encodeDecommitment` / `MerkleVerifier.decodeDecommitment`.
+//!
+//! Layout, big-endian: `u32` hash witness count, 32 bytes per hash, `u32`
+//! column witness count, 4 bytes per M31. The encoding must be consumed exactly
+//! and column values must be canonical M31s (`< P`).
+
+use alloy_primitives::{Bytes, FixedBytes};
+use stwo::core::fields::m31::P;
+
+use crate::MerkleVerifier::Decommitment;
+
+/// Encode a decommitment in the canonical layout
+pub fn encode_decommitment(decommitment: &Decommitment) -> Bytes {
+    let mut out = Vec::with_capacity(encoded_len(
+        decommitment.hashWitness.len(),
+        decommitment.columnWitness.len(),
+    ));
+
+    out.extend_from_slice(&len_prefix(decommitment.hashWitness.len()));
+    for hash in &decommitment.hashWitness {
+        out.extend_from_slice(hash.as_slice());
+    }
+
+    out.extend_from_slice(&len_prefix(decommitment.columnWitness.len()));
+    for value in &decommitment.columnWitness {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    Bytes::from(out)
+}
+
+/// Decode the canonical layout, rejecting truncated input, trailing bytes and
+/// non-canonical column values
+pub fn decode_decommitment(bytes: &[u8]) -> Result<Decommitment, String> {
+    let n_hashes = read_u32(bytes, 0).ok_or("Truncated hash witness length")? as usize;
+    let columns_offset = 4 + n_hashes * 32;
+    let n_columns = read_u32(bytes, columns_offset).ok_or("Truncated hash witness")? as usize;
+
+    let end = encoded_len(n_hashes, n_columns);
+    if bytes.len() < end {
+        return Err("Truncated column witness".to_string());
+    }
+    if bytes.len() > end {
+        return Err(format!(
+            "Trailing bytes: expected {} bytes, got {}",
+            end,
+            bytes.len()
+        ));
+    }
+
+    let hash_witness = bytes[4..columns_offset]
+        .chunks_exact(32)
+        .map(FixedBytes::from_slice)
+        .collect();
+    let column_witness: Vec<u32> = bytes[columns_offset + 4..end]
+        .chunks_exact(4)
+        .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+        .collect();
+    if let Some(value) = column_witness.iter().find(|&&value| value >= P) {
+        return Err(format!("Non-canonical column value {:#x}", value));
+    }
+
+    Ok(Decommitment {
+        hashWitness: hash_witness,
+        columnWitness: column_witness,
+    })
+}
+
+fn encoded_len(n_hashes: usize, n_columns: usize) -> usize {
+    8 + n_hashes * 32 + n_columns * 4
+}
+
+fn len_prefix(len: usize) -> [u8; 4] {
+    u32::try_from(len)
+        .expect("decommitment too long")
+        .to_be_bytes()
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let chunk = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes(chunk.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{providers::ProviderBuilder, sol};
+
+    sol!(
+        #[sol(rpc)]
+        DecommitmentCodecHarness,
+        "../../out/DecommitmentCodecHarness.sol/DecommitmentCodecHarness.json"
+    );
+
+    fn sample_decommitment() -> Decommitment {
+        Decommitment {
+            hashWitness: vec![FixedBytes::repeat_byte(0xab), FixedBytes::repeat_byte(0x01)],
+            columnWitness: vec![0, 1, 0x7fff_fffe],
+        }
+    }
+
+    fn malformed_encodings() -> Vec<(&'static str, Vec<u8>)> {
+        let valid = encode_decommitment(&sample_decommitment()).to_vec();
+        let mut trailing = valid.clone();
+        trailing.push(0);
+        let mut huge_count = valid.clone();
+        huge_count[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let mut non_canonical = valid.clone();
+        let last = non_canonical.len() - 4;
+        non_canonical[last..].copy_from_slice(&P.to_be_bytes());
+
+        vec![
+            ("empty", vec![]),
+            ("short length", vec![0, 0]),
+            ("missing column count", valid[..4 + 64].to_vec()),
+            (
+                "truncated column witness",
+                valid[..valid.len() - 1].to_vec(),
+            ),
+            ("trailing byte", trailing),
+            ("oversized hash count", huge_count),
+            ("non-canonical column value", non_canonical),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for decommitment in [
+            sample_decommitment(),
+            Decommitment {
+                hashWitness: vec![],
+                columnWitness: vec![],
+            },
+        ] {
+            let encoded = encode_decommitment(&decommitment);
+            let decoded = decode_decommitment(&encoded).unwrap();
+            assert_eq!(decoded.hashWitness, decommitment.hashWitness);
+            assert_eq!(decoded.columnWitness, decommitment.columnWitness);
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        for (name, bytes) in malformed_encodings() {
+            assert!(decode_decommitment(&bytes).is_err(), "accepted {}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_solidity_codec_matches() -> Result<(), Box<dyn std::error::Error>> {
+        let provider = ProviderBuilder::new().connect_anvil_with_wallet();
+        let harness = DecommitmentCodecHarness::deploy(&provider).await?;
+
+        let decommitment = sample_decommitment();
+        let encoded = encode_decommitment(&decommitment);
+
+        let solidity_encoded = harness
+            .encode(
+                decommitment.hashWitness.clone(),
+                decommitment.columnWitness.clone(),
+            )
+            .call()
+            .await?;
+        assert_eq!(solidity_encoded, encoded);
+
+        let decoded = harness.decode(encoded).call().await?;
+        assert_eq!(decoded.hashWitness, decommitment.hashWitness);
+        assert_eq!(decoded.columnWitness, decommitment.columnWitness);
+
+        for (name, bytes) in malformed_encodings() {
+            let result = harness.decode(Bytes::from(bytes)).call().await;
+            assert!(result.is_err(), "Solidity decoder accepted {}", name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+//! Symbolic constraint-degree checker for `FrameworkEval` AIRs.
+//!
+//! [`DegreeEvaluator`] runs `evaluate` on [`Degree`] values, which carry an
+//! upper bound on the total degree of an expression in the trace columns
+//! instead of a field element. [`check_degree_bound`] compares the highest
+//! constraint degree with the component's declared
+//! `max_constraint_log_degree_bound`, which the prover uses for the
+//! composition domain and [`crate::prepare_verification_params`] forwards
+//! on-chain as `maxConstraintLogDegreeBound`.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num_traits::{One, Zero};
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fields::FieldExpOps;
+use stwo::core::fraction::Fraction;
+use stwo_constraint_framework::{Batching, EvalAtRow, FrameworkEval};
+use tracing::warn;
+
+/// Degree bound of a symbolic expression. `None` is the zero polynomial,
+/// absorbing under multiplication and neutral under addition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Degree(pub Option<u32>);
+
+impl Degree {
+    /// A trace or preprocessed column
+    pub const COLUMN: Degree = Degree(Some(1));
+    /// A non-zero constant
+    pub const CONSTANT: Degree = Degree(Some(0));
+
+    /// Degree as a number, zero for the zero polynomial
+    pub fn value(self) -> u32 {
+        self.0.unwrap_or(0)
+    }
+
+    fn of_constant(is_zero: bool) -> Self {
+        if is_zero {
+            Self(None)
+        } else {
+            Self::CONSTANT
+        }
+    }
+
+    fn sum(self, rhs: Self) -> Self {
+        match (self.0, rhs.0) {
+            (Some(a), Some(b)) => Self(Some(a.max(b))),
+            (a, b) => Self(a.or(b)),
+        }
+    }
+
+    fn product(self, rhs: Self) -> Self {
+        match (self.0, rhs.0) {
+            (Some(a), Some(b)) => Self(Some(a + b)),
+            _ => Self(None),
+        }
+    }
+}
+
+impl From<BaseField> for Degree {
+    fn from(value: BaseField) -> Self {
+        Self::of_constant(value.is_zero())
+    }
+}
+
+impl From<SecureField> for Degree {
+    fn from(value: SecureField) -> Self {
+        Self::of_constant(value.is_zero())
+    }
+}
+
+macro_rules! impl_degree_ops {
+    ($($rhs:ty),*) => {$(
+        impl Add<$rhs> for Degree {
+            type Output = Degree;
+            fn add(self, rhs: $rhs) -> Degree {
+                self.sum(Degree::from(rhs))
+            }
+        }
+
+        impl Sub<$rhs> for Degree {
+            type Output = Degree;
+            fn sub(self, rhs: $rhs) -> Degree {
+                self.sum(Degree::from(rhs))
+            }
+        }
+
+        impl Mul<$rhs> for Degree {
+            type Output = Degree;
+            fn mul(self, rhs: $rhs) -> Degree {
+                self.product(Degree::from(rhs))
+            }
+        }
+
+        impl AddAssign<$rhs> for Degree {
+            fn add_assign(&mut self, rhs: $rhs) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign<$rhs> for Degree {
+            fn sub_assign(&mut self, rhs: $rhs) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign<$rhs> for Degree {
+            fn mul_assign(&mut self, rhs: $rhs) {
+                *self = *self * rhs;
+            }
+        }
+    )*};
+}
+
+impl_degree_ops!(Degree, BaseField, SecureField);
+
+impl Neg for Degree {
+    type Output = Degree;
+    fn neg(self) -> Degree {
+        self
+    }
+}
+
+impl Zero for Degree {
+    fn zero() -> Self {
+        Self(None)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl One for Degree {
+    fn one() -> Self {
+        Self::CONSTANT
+    }
+}
+
+impl FieldExpOps for Degree {
+    /// Constraints are polynomials: only constants can be inverted
+    fn inverse(&self) -> Self {
+        match self.0 {
+            Some(0) => *self,
+            Some(degree) => panic!("Constraint divides by a degree {} expression", degree),
+            None => panic!("Constraint divides by zero"),
+        }
+    }
+}
+
+/// `EvalAtRow` computing the degree of every constraint, LogUp constraints
+/// included
+#[derive(Debug, Default)]
+pub struct DegreeEvaluator {
+    pub constraint_degrees: Vec<u32>,
+    /// Pending LogUp fractions as (numerator, denominator) degrees
+    fractions: Vec<(Degree, Degree)>,
+}
+
+impl DegreeEvaluator {
+    /// Sum the pending fractions per batch. Each batch becomes one constraint
+    /// `(S_i - S_{i-1}) * den - num` on the cumulative sum columns.
+    fn finalize_batches(&mut self, batching: &[usize]) {
+        assert_eq!(
+            batching.len(),
+            self.fractions.len(),
+            "Batching does not cover every LogUp fraction"
+        );
+        let n_batches = batching.iter().max().map_or(0, |&last| last + 1);
+        for batch in 0..n_batches {
+            let (numerator, denominator) = batching
+                .iter()
+                .zip(&self.fractions)
+                .filter(|(&b, _)| b == batch)
+                .map(|(_, &fraction)| fraction)
+                .reduce(|(n0, d0), (n1, d1)| (n0 * d1 + n1 * d0, d0 * d1))
+                .expect("LogUp batches are numbered without gaps");
+            let constraint = Degree::COLUMN * denominator - numerator;
+            self.constraint_degrees.push(constraint.value());
+        }
+        self.fractions.clear();
+    }
+}
+
+impl EvalAtRow for DegreeEvaluator {
+    type F = Degree;
+    type EF = Degree;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        _interaction: usize,
+        _offsets: [isize; N],
+    ) -> [Self::F; N] {
+        [Degree::COLUMN; N]
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        self.constraint_degrees
+            .push(Degree::from(constraint).value());
+    }
+
+    fn combine_ef(values: [Self::F; 4]) -> Self::EF {
+        values.into_iter().fold(Degree::zero(), Degree::sum)
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fractions
+            .push((fraction.numerator, fraction.denominator));
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        self.finalize_batches(batching);
+    }
+
+    fn finalize_logup(&mut self) {
+        let batching: Vec<usize> = (0..self.fractions.len()).collect();
+        self.finalize_batches(&batching);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        let batching: Vec<usize> = (0..self.fractions.len()).map(|i| i / 2).collect();
+        self.finalize_batches(&batching);
+    }
+}
+
+/// Degree of every constraint of `eval`, in evaluation order
+pub fn constraint_degrees<E: FrameworkEval>(eval: &E) -> Vec<u32> {
+    eval.evaluate(DegreeEvaluator::default()).constraint_degrees
+}
+
+/// Smallest `max_constraint_log_degree_bound` for constraints of `degree` on
+/// `2^log_size` rows: the quotients have degree `(degree - 1) * 2^log_size`,
+/// and the composition domain is always at least twice the trace.
+pub fn required_log_degree_bound(log_size: u32, degree: u32) -> u32 {
+    let expand = degree.saturating_sub(1).next_power_of_two().ilog2().max(1);
+    log_size + expand
+}
+
+/// Declared and computed degree bounds of one component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DegreeReport {
+    pub log_size: u32,
+    pub max_constraint_degree: u32,
+    pub declared_bound: u32,
+    pub required_bound: u32,
+}
+
+impl DegreeReport {
+    /// Whether the declared bound is larger than the constraints need
+    pub fn is_oversized(&self) -> bool {
+        self.declared_bound > self.required_bound
+    }
+}
+
+/// Check that `eval` declares exactly the log degree bound its constraints need.
+///
+/// A smaller bound makes the composition polynomial too large for its domain
+/// and the proof fails. A larger one is still sound but multiplies the
+/// composition domain, and with it proving time and the composition
+/// commitment, so it is rejected too unless `allow_oversized` is set, in
+/// which case it is logged and flagged in the report
+/// ([`DegreeReport::is_oversized`]).
+pub fn check_degree_bound<E: FrameworkEval>(
+    eval: &E,
+    allow_oversized: bool,
+) -> Result<DegreeReport, String> {
+    let log_size = eval.log_size();
+    let max_constraint_degree = constraint_degrees(eval).into_iter().max().unwrap_or(0);
+    let report = DegreeReport {
+        log_size,
+        max_constraint_degree,
+        declared_bound: eval.max_constraint_log_degree_bound(),
+        required_bound: required_log_degree_bound(log_size, max_constraint_degree),
+    };
+
+    if report.declared_bound < report.required_bound {
+        return Err(format!(
+            "max_constraint_log_degree_bound {} is too small: degree {} constraints on 2^{} rows need {}",
+            report.declared_bound, max_constraint_degree, log_size, report.required_bound
+        ));
+    }
+    if report.is_oversized() {
+        if !allow_oversized {
+            return Err(format!(
+                "max_constraint_log_degree_bound {} is larger than needed: degree {} constraints on 2^{} rows need {}",
+                report.declared_bound, max_constraint_degree, log_size, report.required_bound
+            ));
+        }
+        warn!(
+            declared = report.declared_bound,
+            required = report.required_bound,
+            max_constraint_degree,
+            log_size,
+            "max_constraint_log_degree_bound is larger than the constraints need"
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stwo_constraint_framework::{relation, RelationEntry};
+
+    relation!(TestRelation, 2);
+
+    /// `a^degree - b`, plus a LogUp pair when `lookups` is set
+    struct TestEval {
+        degree: u32,
+        log_expand: u32,
+        lookups: Option<TestRelation>,
+    }
+
+    impl FrameworkEval for TestEval {
+        fn log_size(&self) -> u32 {
+            4
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            4 + self.log_expand
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = eval.next_trace_mask();
+            let b = eval.next_trace_mask();
+            let flag = eval.next_trace_mask();
+            eval.add_constraint(a.clone().pow(self.degree as u128) - b.clone());
+            if let Some(relation) = &self.lookups {
+                eval.add_to_relation(RelationEntry::new(
+                    relation,
+                    E::EF::from(flag.clone()),
+                    &[a.clone(), b.clone()],
+                ));
+                eval.add_to_relation(RelationEntry::new(relation, -E::EF::one(), &[a, b]));
+                eval.finalize_logup_in_pairs();
+            }
+            eval
+        }
+    }
+
+    #[test]
+    fn test_constraint_degrees() {
+        let eval = TestEval {
+            degree: 5,
+            log_expand: 2,
+            lookups: None,
+        };
+        assert_eq!(constraint_degrees(&eval), vec![5]);
+        assert_eq!(check_degree_bound(&eval, true).unwrap().required_bound, 6);
+
+        // flag/den0 - 1/den1 in one batch: (S - S') * den0 * den1 - (flag * den1 - den0)
+        let eval = TestEval {
+            degree: 1,
+            log_expand: 1,
+            lookups: Some(TestRelation::dummy()),
+        };
+        assert_eq!(constraint_degrees(&eval), vec![1, 3]);
+        assert!(check_degree_bound(&eval, false).is_ok());
+    }
+
+    #[test]
+    fn test_check_degree_bound() {
+        let too_small = TestEval {
+            degree: 5,
+            log_expand: 1,
+            lookups: None,
+        };
+        for allow_oversized in [false, true] {
+            assert!(check_degree_bound(&too_small, allow_oversized)
+                .unwrap_err()
+                .contains("too small"));
+        }
+
+        let too_large = TestEval {
+            degree: 3,
+            log_expand: 2,
+            lookups: None,
+        };
+        // Oversized bounds are sound, so opting out reports them instead
+        assert!(check_degree_bound(&too_large, false)
+            .unwrap_err()
+            .contains("larger than needed"));
+        let report = check_degree_bound(&too_large, true).unwrap();
+        assert!(report.is_oversized());
+        assert_eq!((report.declared_bound, report.required_bound), (6, 5));
+
+        let exact = TestEval {
+            degree: 3,
+            log_expand: 1,
+            lookups: None,
+        };
+        assert!(!check_degree_bound(&exact, false).unwrap().is_oversized());
+    }
+}
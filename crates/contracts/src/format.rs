@@ -0,0 +1,425 @@
+use alloy_primitives::FixedBytes;
+use alloy_sol_types::SolValue;
+
+use crate::{hash::HashFunction, ProofParser::ProofHeader, VerifierInput};
+
+/// Proof layout tag, must match the `ProofParser.VERSION_*` constants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProofVersion {
+    /// Composition polynomial coefficients are shipped and evaluated at the OODS point
+    FullComposition = 1,
+    /// Only the composition OODS samples are shipped, as in upstream stwo
+    SampledComposition = 2,
+}
+
+impl ProofVersion {
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for ProofVersion {
+    type Error = String;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(ProofVersion::FullComposition),
+            2 => Ok(ProofVersion::SampledComposition),
+            _ => Err(format!("Unknown proof version: {}", id)),
+        }
+    }
+}
+
+/// Encoding of field elements inside the proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FieldEncoding {
+    /// Every M31 is an ABI `uint32` word, a QM31 is four words
+    AbiWords = 0,
+    /// Every M31 packed into 4 bytes, see [`crate::packed`]
+    Packed = 1,
+}
+
+impl FieldEncoding {
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for FieldEncoding {
+    type Error = String;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(FieldEncoding::AbiWords),
+            1 => Ok(FieldEncoding::Packed),
+            _ => Err(format!("Unknown field encoding: {}", id)),
+        }
+    }
+}
+
+/// Envelope magic, ASCII "STWO" (`ProofParser.MAGIC`)
+pub const PROOF_MAGIC: [u8; 4] = *b"STWO";
+
+/// Build the envelope placed at the start of every converted proof
+pub fn proof_header(
+    version: ProofVersion,
+    hash_function: HashFunction,
+    field_encoding: FieldEncoding,
+) -> ProofHeader {
+    ProofHeader {
+        magic: FixedBytes::from(PROOF_MAGIC),
+        version: version.id(),
+        hashFunction: hash_function.id(),
+        fieldEncoding: field_encoding.id(),
+    }
+}
+
+/// Parse an envelope, mirroring `ProofParser.validateHeader`
+pub fn check_header(
+    header: &ProofHeader,
+) -> Result<(ProofVersion, HashFunction, FieldEncoding), String> {
+    if header.magic != FixedBytes::from(PROOF_MAGIC) {
+        return Err(format!("Invalid proof magic: {}", header.magic));
+    }
+    Ok((
+        ProofVersion::try_from(header.version)?,
+        HashFunction::try_from(header.hashFunction)?,
+        FieldEncoding::try_from(header.fieldEncoding)?,
+    ))
+}
+
+/// ABI-encode a verifier input for archiving
+pub fn encode_archived_input(input: &VerifierInput) -> Vec<u8> {
+    input.abi_encode()
+}
+
+/// Decode an archived verifier input in the current layout or any legacy layout,
+/// migrating legacy proofs to the current one. Fails if the proof was produced
+/// with a hash function other than `hash_function`.
+pub fn decode_archived_input(
+    bytes: &[u8],
+    hash_function: HashFunction,
+) -> Result<VerifierInput, Box<dyn std::error::Error>> {
+    let input = match VerifierInput::abi_decode_validate(bytes) {
+        Ok(input) if input.proof.header.magic == FixedBytes::from(PROOF_MAGIC) => input,
+        _ => match legacy::VerifierInputV1::abi_decode_validate(bytes) {
+            Ok(input) if input.proof.header.magic == FixedBytes::from(PROOF_MAGIC) => {
+                legacy::migrate_v1(input)
+            }
+            _ => legacy::VerifierInputV0::abi_decode_validate(bytes)
+                .map(legacy::migrate_v0)
+                .map_err(|e| format!("Unrecognised archived proof: {}", e))?,
+        },
+    };
+
+    let (_, archived_hash, _) = check_header(&input.proof.header)?;
+    if archived_hash != hash_function {
+        return Err(format!(
+            "Archived proof uses {:?}, expected {:?}",
+            archived_hash, hash_function
+        )
+        .into());
+    }
+    Ok(input)
+}
+
+/// Superseded layouts, migrated to the current one on decode
+pub mod legacy {
+    use alloy::sol;
+
+    use super::{proof_header, FieldEncoding, ProofVersion};
+    use crate::{
+        decommitment::encode_decommitment,
+        hash::HashFunction,
+        FriVerifier::FriProof,
+        MerkleVerifier::Decommitment,
+        PcsConfig::Config,
+        ProofParser::{CompositionPoly, Proof, ProofHeader},
+        QM31Field::QM31,
+        VerificationParams, VerifierInput,
+    };
+
+    sol!(
+        /// Proof without header: Keccak only, full composition polynomial
+        struct ProofV0 {
+            Config config;
+            bytes32[] commitments;
+            QM31[][][] sampledValues;
+            Decommitment[] decommitments;
+            uint32[][] queriedValues;
+            uint64 proofOfWork;
+            FriProof friProof;
+            CompositionPoly compositionPoly;
+        }
+
+        struct VerifierInputV0 {
+            ProofV0 proof;
+            VerificationParams verificationParams;
+            bytes32[] treeRoots;
+            uint32[][] treeColumnLogSizes;
+            bytes32 digest;
+            uint32 nDraws;
+        }
+
+        /// Proof with header, main-tree decommitments as ABI structs
+        struct ProofV1 {
+            ProofHeader header;
+            Config config;
+            bytes32[] commitments;
+            QM31[][][] sampledValues;
+            Decommitment[] decommitments;
+            uint32[][] queriedValues;
+            uint64 proofOfWork;
+            FriProof friProof;
+            CompositionPoly compositionPoly;
+        }
+
+        struct VerifierInputV1 {
+            ProofV1 proof;
+            VerificationParams verificationParams;
+            bytes32[] treeRoots;
+            uint32[][] treeColumnLogSizes;
+            bytes32 digest;
+            uint32 nDraws;
+        }
+    );
+
+    /// Migrate a header-less proof to the current layout
+    pub fn migrate_v0(input: VerifierInputV0) -> VerifierInput {
+        migrate_v1(upgrade_v0(input))
+    }
+
+    /// Wrap a header-less proof in the envelope
+    pub fn upgrade_v0(input: VerifierInputV0) -> VerifierInputV1 {
+        let proof = input.proof;
+        VerifierInputV1 {
+            proof: ProofV1 {
+                header: proof_header(
+                    ProofVersion::FullComposition,
+                    HashFunction::Keccak,
+                    FieldEncoding::AbiWords,
+                ),
+                config: proof.config,
+                commitments: proof.commitments,
+                sampledValues: proof.sampledValues,
+                decommitments: proof.decommitments,
+                queriedValues: proof.queriedValues,
+                proofOfWork: proof.proofOfWork,
+                friProof: proof.friProof,
+                compositionPoly: proof.compositionPoly,
+            },
+            verificationParams: input.verificationParams,
+            treeRoots: input.treeRoots,
+            treeColumnLogSizes: input.treeColumnLogSizes,
+            digest: input.digest,
+            nDraws: input.nDraws,
+        }
+    }
+
+    /// Re-encode main-tree decommitments in the canonical layout
+    pub fn migrate_v1(input: VerifierInputV1) -> VerifierInput {
+        let proof = input.proof;
+        VerifierInput {
+            proof: Proof {
+                header: proof.header,
+                config: proof.config,
+                commitments: proof.commitments,
+                sampledValues: proof.sampledValues,
+                decommitments: proof
+                    .decommitments
+                    .iter()
+                    .map(encode_decommitment)
+                    .collect(),
+                queriedValues: proof.queriedValues,
+                proofOfWork: proof.proofOfWork,
+                friProof: proof.friProof,
+                compositionPoly: proof.compositionPoly,
+            },
+            verificationParams: input.verificationParams,
+            treeRoots: input.treeRoots,
+            treeColumnLogSizes: input.treeColumnLogSizes,
+            digest: input.digest,
+            nDraws: input.nDraws,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decommitment::decode_decommitment;
+
+    #[test]
+    fn test_check_header() {
+        let header = proof_header(
+            ProofVersion::SampledComposition,
+            HashFunction::Blake2s,
+            FieldEncoding::AbiWords,
+        );
+        assert_eq!(
+            check_header(&header),
+            Ok((
+                ProofVersion::SampledComposition,
+                HashFunction::Blake2s,
+                FieldEncoding::AbiWords
+            ))
+        );
+
+        let bad_magic = ProofHeader {
+            magic: FixedBytes::from(*b"STWX"),
+            ..header.clone()
+        };
+        assert!(check_header(&bad_magic).is_err());
+
+        for version in [0, 3] {
+            let bad_version = ProofHeader {
+                version,
+                ..header.clone()
+            };
+            assert!(check_header(&bad_version).is_err());
+        }
+    }
+
+    fn sample_v0() -> legacy::VerifierInputV0 {
+        use crate::{
+            FriVerifier::{FriLayerProof, FriProof},
+            MerkleVerifier::Decommitment,
+            PcsConfig::{Config, FriConfig},
+            ProofParser::CompositionPoly,
+            VerificationParams,
+        };
+        use alloy_primitives::{Bytes, U256};
+
+        let layer = FriLayerProof {
+            friWitness: vec![],
+            decommitment: Bytes::from(vec![0, 0, 0, 0, 0, 0, 0, 0]),
+            commitment: FixedBytes::repeat_byte(0x22),
+        };
+        legacy::VerifierInputV0 {
+            proof: legacy::ProofV0 {
+                config: Config {
+                    powBits: 10,
+                    friConfig: FriConfig {
+                        logBlowupFactor: 1,
+                        logLastLayerDegreeBound: 0,
+                        nQueries: U256::from(3),
+                    },
+                },
+                commitments: vec![FixedBytes::repeat_byte(0x11)],
+                sampledValues: vec![],
+                decommitments: vec![Decommitment {
+                    hashWitness: vec![FixedBytes::repeat_byte(0x33)],
+                    columnWitness: vec![1, 2],
+                }],
+                queriedValues: vec![vec![5, 6, 7]],
+                proofOfWork: 42,
+                friProof: FriProof {
+                    firstLayer: layer.clone(),
+                    innerLayers: vec![layer],
+                    lastLayerPoly: vec![],
+                },
+                compositionPoly: CompositionPoly {
+                    coeffs0: vec![],
+                    coeffs1: vec![],
+                    coeffs2: vec![],
+                    coeffs3: vec![],
+                },
+            },
+            verificationParams: VerificationParams {
+                componentParams: vec![],
+                nPreprocessedColumns: U256::from(1),
+                componentsCompositionLogDegreeBound: 5,
+            },
+            treeRoots: vec![FixedBytes::repeat_byte(0x44)],
+            treeColumnLogSizes: vec![vec![5, 5]],
+            digest: FixedBytes::repeat_byte(0x55),
+            nDraws: 2,
+        }
+    }
+
+    #[test]
+    fn test_decode_archived_v0() {
+        let v0 = sample_v0();
+        let migrated = legacy::migrate_v0(v0.clone());
+        let decoded =
+            decode_archived_input(&v0.abi_encode(), HashFunction::Keccak).expect("v0 rejected");
+
+        assert_eq!(
+            check_header(&decoded.proof.header),
+            Ok((
+                ProofVersion::FullComposition,
+                HashFunction::Keccak,
+                FieldEncoding::AbiWords
+            ))
+        );
+        assert_eq!(decoded.proof.proofOfWork, 42);
+        assert_eq!(decoded.treeRoots, v0.treeRoots);
+        assert_eq!(decoded.digest, v0.digest);
+        assert_eq!(
+            encode_archived_input(&decoded),
+            encode_archived_input(&migrated)
+        );
+
+        // A migrated proof is archived in the current layout and read back unchanged
+        let current = encode_archived_input(&migrated);
+        let decoded = decode_archived_input(&current, HashFunction::Keccak).unwrap();
+        assert_eq!(encode_archived_input(&decoded), current);
+    }
+
+    #[test]
+    fn test_decode_archived_v1() {
+        let v1 = legacy::upgrade_v0(sample_v0());
+        let decoded =
+            decode_archived_input(&v1.abi_encode(), HashFunction::Keccak).expect("v1 rejected");
+
+        // Main-tree decommitments move to the canonical codec, everything else is kept
+        assert_eq!(decoded.proof.decommitments.len(), 1);
+        let decommitment = decode_decommitment(&decoded.proof.decommitments[0]).unwrap();
+        assert_eq!(
+            decommitment.columnWitness,
+            v1.proof.decommitments[0].columnWitness
+        );
+        assert_eq!(
+            encode_archived_input(&decoded),
+            encode_archived_input(&legacy::migrate_v1(v1))
+        );
+    }
+
+    #[test]
+    fn test_decode_archived_rejects() {
+        let input = legacy::migrate_v0(sample_v0());
+        let with_header = |header: ProofHeader| {
+            let mut input = input.clone();
+            input.proof.header = header;
+            encode_archived_input(&input)
+        };
+
+        let bad_magic = with_header(ProofHeader {
+            magic: FixedBytes::from(*b"STWX"),
+            ..input.proof.header.clone()
+        });
+        assert!(decode_archived_input(&bad_magic, HashFunction::Keccak).is_err());
+
+        let unknown_version = with_header(ProofHeader {
+            version: 9,
+            ..input.proof.header.clone()
+        });
+        assert!(decode_archived_input(&unknown_version, HashFunction::Keccak).is_err());
+
+        let blake2s = with_header(proof_header(
+            ProofVersion::FullComposition,
+            HashFunction::Blake2s,
+            FieldEncoding::AbiWords,
+        ));
+        assert!(decode_archived_input(&blake2s, HashFunction::Blake2s).is_ok());
+        assert!(decode_archived_input(&blake2s, HashFunction::Keccak).is_err());
+
+        // Header-less proofs are always Keccak
+        let v0 = sample_v0().abi_encode();
+        assert!(decode_archived_input(&v0, HashFunction::Blake2s).is_err());
+
+        assert!(decode_archived_input(&[], HashFunction::Keccak).is_err());
+    }
+}
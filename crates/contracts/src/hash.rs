@@ -0,0 +1,275 @@
+use alloy_primitives::FixedBytes;
+use stwo::core::{
+    channel::{Blake2sChannel, KeccakChannel, MerkleChannel, Poseidon252Channel},
+    vcs::{
+        blake2_merkle::Blake2sMerkleChannel, keccak_merkle::KeccakMerkleChannel,
+        poseidon252_merkle::Poseidon252MerkleChannel, MerkleHasher,
+    },
+};
+
+/// Hash function identifier shared with the Solidity `HashFunction` library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashFunction {
+    Keccak = 0,
+    Blake2s = 1,
+    Poseidon252 = 2,
+}
+
+impl HashFunction {
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Whether `STWOVerifier` can check proofs committed with this hash
+    pub fn is_supported_on_chain(self) -> bool {
+        matches!(self, HashFunction::Keccak | HashFunction::Blake2s)
+    }
+}
+
+impl TryFrom<u8> for HashFunction {
+    type Error = String;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(HashFunction::Keccak),
+            1 => Ok(HashFunction::Blake2s),
+            2 => Ok(HashFunction::Poseidon252),
+            _ => Err(format!("Unknown hash function id: {}", id)),
+        }
+    }
+}
+
+impl std::str::FromStr for HashFunction {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "keccak" => Ok(HashFunction::Keccak),
+            "blake2s" => Ok(HashFunction::Blake2s),
+            "poseidon252" => Ok(HashFunction::Poseidon252),
+            _ => Err(format!("Unknown hash function: {}", name)),
+        }
+    }
+}
+
+/// Merkle hasher used by proofs committed through `H`
+pub type HasherOf<H> = <<H as SolidityHasher>::MerkleChannel as MerkleChannel>::H;
+/// Hash (commitment) type of `H`
+pub type HashOf<H> = <HasherOf<H> as MerkleHasher>::Hash;
+/// Fiat-Shamir channel used together with `H`
+pub type ChannelOf<H> = <<H as SolidityHasher>::MerkleChannel as MerkleChannel>::C;
+
+/// Hash backend that can be converted into the Solidity proof format
+pub trait SolidityHasher {
+    const HASH_FUNCTION: HashFunction;
+
+    type MerkleChannel: MerkleChannel;
+
+    /// Convert a commitment into the `bytes32` seen by the contract
+    fn hash_to_bytes32(hash: &HashOf<Self>) -> FixedBytes<32>;
+
+    /// Current channel digest, used to resume the transcript on-chain
+    fn channel_digest(channel: &ChannelOf<Self>) -> FixedBytes<32>;
+}
+
+/// Keccak-256 channel and Merkle tree (native `keccak256` on-chain)
+pub struct Keccak;
+
+impl SolidityHasher for Keccak {
+    const HASH_FUNCTION: HashFunction = HashFunction::Keccak;
+
+    type MerkleChannel = KeccakMerkleChannel;
+
+    fn hash_to_bytes32(hash: &HashOf<Self>) -> FixedBytes<32> {
+        FixedBytes::from(hash.0)
+    }
+
+    fn channel_digest(channel: &KeccakChannel) -> FixedBytes<32> {
+        FixedBytes::from(channel.digest().0)
+    }
+}
+
+/// BLAKE2s channel and Merkle tree, as used by standard stwo proofs
+pub struct Blake2s;
+
+impl SolidityHasher for Blake2s {
+    const HASH_FUNCTION: HashFunction = HashFunction::Blake2s;
+
+    type MerkleChannel = Blake2sMerkleChannel;
+
+    fn hash_to_bytes32(hash: &HashOf<Self>) -> FixedBytes<32> {
+        FixedBytes::from(hash.0)
+    }
+
+    fn channel_digest(channel: &Blake2sChannel) -> FixedBytes<32> {
+        FixedBytes::from(channel.digest().0)
+    }
+}
+
+/// Poseidon252 channel and Merkle tree. Proofs convert, but the contract
+/// rejects them until an on-chain Poseidon252 implementation exists.
+pub struct Poseidon252;
+
+impl SolidityHasher for Poseidon252 {
+    const HASH_FUNCTION: HashFunction = HashFunction::Poseidon252;
+
+    type MerkleChannel = Poseidon252MerkleChannel;
+
+    fn hash_to_bytes32(hash: &HashOf<Self>) -> FixedBytes<32> {
+        FixedBytes::from(hash.to_bytes_be())
+    }
+
+    fn channel_digest(channel: &Poseidon252Channel) -> FixedBytes<32> {
+        FixedBytes::from(channel.digest().to_bytes_be())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{providers::ProviderBuilder, sol};
+    use alloy_primitives::b256;
+    use stwo::core::{channel::Channel, fields::m31::BaseField};
+
+    sol!(
+        #[sol(rpc)]
+        HashHarness,
+        "../../out/HashHarness.sol/HashHarness.json"
+    );
+
+    const LEAF_VALUES: [u32; 3] = [1, 2, 3];
+    const NODE_VALUES: [u32; 2] = [4, 5];
+    const MIXED: [u32; 3] = [1, 2, 3];
+    const POW_BITS: u32 = 12;
+
+    /// Merkle and channel outputs of one hash function, for the inputs above
+    #[derive(Debug, PartialEq, Eq)]
+    struct KnownAnswers {
+        /// Leaf over `LEAF_VALUES`
+        leaf: FixedBytes<32>,
+        /// Node over two copies of the leaf and `NODE_VALUES`
+        node: FixedBytes<32>,
+        /// Channel digest after mixing `MIXED` into a fresh channel
+        digest: FixedBytes<32>,
+        /// First secure felt drawn after that
+        felt: [u32; 4],
+    }
+
+    fn base_felts(values: &[u32]) -> Vec<BaseField> {
+        values.iter().copied().map(BaseField::from).collect()
+    }
+
+    fn mixed_channel<H: SolidityHasher>() -> ChannelOf<H> {
+        let mut channel = ChannelOf::<H>::default();
+        channel.mix_u32s(&MIXED);
+        channel
+    }
+
+    fn rust_answers<H: SolidityHasher>() -> KnownAnswers {
+        let leaf = HasherOf::<H>::hash_node(None, &base_felts(&LEAF_VALUES));
+        let node = HasherOf::<H>::hash_node(Some((leaf, leaf)), &base_felts(&NODE_VALUES));
+        let mut channel = mixed_channel::<H>();
+        let digest = H::channel_digest(&channel);
+        let felt = channel
+            .draw_secure_felt()
+            .to_m31_array()
+            .map(|value| value.0);
+        KnownAnswers {
+            leaf: H::hash_to_bytes32(&leaf),
+            node: H::hash_to_bytes32(&node),
+            digest,
+            felt,
+        }
+    }
+
+    /// Smallest nonce passing a `POW_BITS` proof of work on the mixed channel
+    fn grind<H: SolidityHasher>() -> u64 {
+        let channel = mixed_channel::<H>();
+        (0..)
+            .find(|&nonce| channel.verify_pow_nonce(POW_BITS, nonce))
+            .unwrap()
+    }
+
+    /// Computed independently from the BLAKE2s specification with the upstream
+    /// stwo layouts: unprefixed Merkle preimages, a 32-byte draw counter and a
+    /// 16-byte proof-of-work prefix
+    const BLAKE2S_ANSWERS: KnownAnswers = KnownAnswers {
+        leaf: b256!("9549ce18083a73c794b0fe338635a4ab0950333ecb2e3f1945adf7db5f0ef4d0"),
+        node: b256!("d572fb112cff6becec1bbb428fa2f9a60788ecb64d7620be927796187a0e6a4d"),
+        digest: b256!("0b0c053dd869e359f38b026269bd0434fee51266e39016a4e2e4c3067aeb64a0"),
+        felt: [1210511627, 573043073, 1333158719, 525420366],
+    };
+    const BLAKE2S_POW_NONCE: u64 = 507;
+
+    #[test]
+    fn test_blake2s_known_answers() {
+        assert_eq!(rust_answers::<Blake2s>(), BLAKE2S_ANSWERS);
+        assert_eq!(grind::<Blake2s>(), BLAKE2S_POW_NONCE);
+    }
+
+    async fn solidity_answers(
+        harness: &HashHarness::HashHarnessInstance<impl alloy::providers::Provider>,
+        hash: HashFunction,
+    ) -> Result<KnownAnswers, Box<dyn std::error::Error>> {
+        let leaf = harness
+            .hashLeaf(hash.id(), LEAF_VALUES.to_vec())
+            .call()
+            .await?;
+        let node = harness
+            .hashNode(hash.id(), leaf, leaf, NODE_VALUES.to_vec())
+            .call()
+            .await?;
+        let drawn = harness.mixAndDraw(hash.id(), MIXED.to_vec()).call().await?;
+        Ok(KnownAnswers {
+            leaf,
+            node,
+            digest: drawn.digest,
+            felt: drawn.felt,
+        })
+    }
+
+    async fn solidity_pow(
+        harness: &HashHarness::HashHarnessInstance<impl alloy::providers::Provider>,
+        hash: HashFunction,
+        nonce: u64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(harness
+            .mixAndVerifyPow(hash.id(), MIXED.to_vec(), POW_BITS, nonce)
+            .call()
+            .await?)
+    }
+
+    #[tokio::test]
+    async fn test_solidity_hashes_match() -> Result<(), Box<dyn std::error::Error>> {
+        let provider = ProviderBuilder::new().connect_anvil_with_wallet();
+        let harness = HashHarness::deploy(&provider).await?;
+
+        let blake2s = HashFunction::Blake2s;
+        assert_eq!(solidity_answers(&harness, blake2s).await?, BLAKE2S_ANSWERS);
+        assert!(solidity_pow(&harness, blake2s, BLAKE2S_POW_NONCE).await?);
+        assert!(!solidity_pow(&harness, blake2s, 0).await?);
+
+        let keccak = HashFunction::Keccak;
+        assert_eq!(
+            solidity_answers(&harness, keccak).await?,
+            rust_answers::<Keccak>()
+        );
+        let nonce = grind::<Keccak>();
+        assert!(solidity_pow(&harness, keccak, nonce).await?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_function_ids_round_trip() {
+        for hash in [
+            HashFunction::Keccak,
+            HashFunction::Blake2s,
+            HashFunction::Poseidon252,
+        ] {
+            assert_eq!(HashFunction::try_from(hash.id()), Ok(hash));
+        }
+        assert!(HashFunction::try_from(3).is_err());
+        assert_eq!("Blake2s".parse(), Ok(HashFunction::Blake2s));
+    }
+}
@@ -1,6 +1,16 @@
 use alloy::sol;
 use stwo_constraint_framework::{FrameworkComponent, FrameworkEval};
 
+pub mod batch;
+pub mod circuit;
+pub mod decommitment;
+pub mod degree;
+pub mod format;
+pub mod hash;
+pub mod packed;
+pub mod prover;
+pub mod verifying_key;
+
 // Main contract with all nested types included
 sol!(
     #[sol(rpc)]
@@ -10,6 +20,9 @@ sol!(
 );
 
 // Re-export main contract types
+use crate::decommitment::encode_decommitment;
+use crate::format::{proof_header, FieldEncoding, ProofVersion};
+use crate::hash::{HashOf, HasherOf, SolidityHasher};
 use crate::{
     CM31Field::CM31,
     FrameworkComponentLib::ComponentInfo,
@@ -23,9 +36,9 @@ use alloy_primitives::{Bytes, FixedBytes, U256};
 use stwo::{
     core::{
         air::{Component, Components},
+        fields::m31::BaseField,
         proof::StarkProof,
         utils::bit_reverse,
-        vcs::keccak_merkle::KeccakMerkleHasher,
     },
     prover::{backend::simd::SimdBackend, poly::circle::SecureCirclePoly},
 };
@@ -42,30 +55,79 @@ sol!(
 );
 pub use STWOVerifier::*;
 
-/// Recreate Solidity abi.encodePacked for decommitment
-fn encode_decommitment_packed(hash_witness: &[FixedBytes<32>], column_witness: &[u32]) -> Bytes {
-    let mut encoded = Vec::new();
+/// Convert a Merkle decommitment into the Solidity struct
+fn convert_decommitment<H: SolidityHasher>(
+    hash_witness: &[HashOf<H>],
+    column_witness: &[BaseField],
+) -> Decommitment {
+    Decommitment {
+        hashWitness: hash_witness.iter().map(H::hash_to_bytes32).collect(),
+        columnWitness: column_witness.iter().map(|m| m.0).collect(),
+    }
+}
 
-    let length_bytes: [u8; 32] = U256::from(hash_witness.len()).to_be_bytes();
-    encoded.extend_from_slice(&length_bytes);
+/// Convert a proof shipping the full composition polynomial (`ProofVersion::FullComposition`)
+pub fn convert_to_solidity_proof<H: SolidityHasher>(
+    proof: StarkProof<HasherOf<H>>,
+    composition_polynomial: SecureCirclePoly<SimdBackend>,
+) -> Proof {
+    let composition_polynomial_to_solidity: Vec<Vec<u32>> = composition_polynomial
+        .into_coordinate_polys()
+        .iter()
+        .map(|poly| {
+            let mut layer = Vec::new();
+            for coeff in &poly.coeffs.data {
+                let coeff_as_u32: Vec<u32> = coeff.to_array().iter().map(|m| m.0).collect();
+                layer.extend_from_slice(&coeff_as_u32);
+            }
+            layer
+        })
+        .collect();
 
-    for witness in hash_witness {
-        encoded.extend_from_slice(witness.as_slice());
-    }
+    let comp_poly = CompositionPoly {
+        coeffs0: composition_polynomial_to_solidity[0].clone(),
+        coeffs1: composition_polynomial_to_solidity[1].clone(),
+        coeffs2: composition_polynomial_to_solidity[2].clone(),
+        coeffs3: composition_polynomial_to_solidity[3].clone(),
+    };
 
-    let column_length_bytes: [u8; 32] = U256::from(column_witness.len()).to_be_bytes();
-    encoded.extend_from_slice(&column_length_bytes);
+    convert_proof::<H>(proof, comp_poly, ProofVersion::FullComposition)
+}
 
-    for &val in column_witness {
-        encoded.extend_from_slice(&val.to_be_bytes());
-    }
+/// Convert a proof carrying only the composition OODS samples
+/// (`ProofVersion::SampledComposition`). The composition tree is opened by FRI
+/// like every other tree, so calldata no longer grows with the composition degree.
+pub fn convert_to_solidity_proof_sampled<H: SolidityHasher>(
+    proof: StarkProof<HasherOf<H>>,
+) -> Proof {
+    let comp_poly = CompositionPoly {
+        coeffs0: vec![],
+        coeffs1: vec![],
+        coeffs2: vec![],
+        coeffs3: vec![],
+    };
 
-    Bytes::from(encoded)
+    convert_proof::<H>(proof, comp_poly, ProofVersion::SampledComposition)
 }
 
-pub fn convert_to_solidity_proof(
-    proof: StarkProof<KeccakMerkleHasher>,
+/// Convert a proof in the layout selected by `version`
+pub fn convert_to_solidity_proof_versioned<H: SolidityHasher>(
+    proof: StarkProof<HasherOf<H>>,
     composition_polynomial: SecureCirclePoly<SimdBackend>,
+    version: ProofVersion,
+) -> Proof {
+    match version {
+        ProofVersion::FullComposition => {
+            convert_to_solidity_proof::<H>(proof, composition_polynomial)
+        }
+        ProofVersion::SampledComposition => convert_to_solidity_proof_sampled::<H>(proof),
+    }
+}
+
+fn convert_proof<H: SolidityHasher>(
+    proof: StarkProof<HasherOf<H>>,
+    comp_poly: CompositionPoly,
+    version: ProofVersion,
 ) -> Proof {
     let sol_config = Config {
         powBits: proof.config.pow_bits,
@@ -76,12 +138,8 @@ pub fn convert_to_solidity_proof(
         },
     };
 
-    let commitments: Vec<FixedBytes<32>> = proof
-        .0
-        .commitments
-        .iter()
-        .map(|commitment| FixedBytes::from(commitment.0))
-        .collect();
+    let commitments: Vec<FixedBytes<32>> =
+        proof.0.commitments.iter().map(H::hash_to_bytes32).collect();
 
     let sampled_values: Vec<Vec<Vec<QM31>>> = proof
         .sampled_values
@@ -107,17 +165,15 @@ pub fn convert_to_solidity_proof(
         })
         .collect();
 
-    let decommitments: Vec<Decommitment> = proof
+    let decommitments: Vec<Bytes> = proof
         .0
         .decommitments
         .iter()
-        .map(|decom| Decommitment {
-            hashWitness: decom
-                .hash_witness
-                .iter()
-                .map(|h| FixedBytes::from(h.0))
-                .collect::<Vec<_>>(),
-            columnWitness: decom.column_witness.iter().map(|m| m.0).collect::<Vec<_>>(),
+        .map(|decom| {
+            encode_decommitment(&convert_decommitment::<H>(
+                &decom.hash_witness,
+                &decom.column_witness,
+            ))
         })
         .collect();
 
@@ -138,21 +194,11 @@ pub fn convert_to_solidity_proof(
                     },
                 })
                 .collect(),
-            decommitment: encode_decommitment_packed(
-                &layer
-                    .decommitment
-                    .hash_witness
-                    .iter()
-                    .map(|h| FixedBytes::from(h.0))
-                    .collect::<Vec<_>>(),
-                &layer
-                    .decommitment
-                    .column_witness
-                    .iter()
-                    .map(|m| m.0)
-                    .collect::<Vec<_>>(),
-            ),
-            commitment: FixedBytes::from(layer.commitment.0),
+            decommitment: encode_decommitment(&convert_decommitment::<H>(
+                &layer.decommitment.hash_witness,
+                &layer.decommitment.column_witness,
+            )),
+            commitment: H::hash_to_bytes32(&layer.commitment),
         }
     };
 
@@ -176,21 +222,11 @@ pub fn convert_to_solidity_proof(
                     },
                 })
                 .collect(),
-            decommitment: encode_decommitment_packed(
-                &layer
-                    .decommitment
-                    .hash_witness
-                    .iter()
-                    .map(|h| FixedBytes::from(h.0))
-                    .collect::<Vec<_>>(),
-                &layer
-                    .decommitment
-                    .column_witness
-                    .iter()
-                    .map(|m| m.0)
-                    .collect::<Vec<_>>(),
-            ),
-            commitment: FixedBytes::from(layer.commitment.0),
+            decommitment: encode_decommitment(&convert_decommitment::<H>(
+                &layer.decommitment.hash_witness,
+                &layer.decommitment.column_witness,
+            )),
+            commitment: H::hash_to_bytes32(&layer.commitment),
         })
         .collect();
 
@@ -221,26 +257,6 @@ pub fn convert_to_solidity_proof(
         firstLayer: first_layer,
     };
 
-    let composition_polynomial_to_solidity: Vec<Vec<u32>> = composition_polynomial
-        .into_coordinate_polys()
-        .iter()
-        .map(|poly| {
-            let mut layer = Vec::new();
-            for coeff in &poly.coeffs.data {
-                let coeff_as_u32: Vec<u32> = coeff.to_array().iter().map(|m| m.0).collect();
-                layer.extend_from_slice(&coeff_as_u32);
-            }
-            layer
-        })
-        .collect();
-
-    let comp_poly = CompositionPoly {
-        coeffs0: composition_polynomial_to_solidity[0].clone(),
-        coeffs1: composition_polynomial_to_solidity[1].clone(),
-        coeffs2: composition_polynomial_to_solidity[2].clone(),
-        coeffs3: composition_polynomial_to_solidity[3].clone(),
-    };
-
     let queried_values: Vec<Vec<u32>> = proof
         .0
         .queried_values
@@ -249,6 +265,7 @@ pub fn convert_to_solidity_proof(
         .collect();
 
     Proof {
+        header: proof_header(version, H::HASH_FUNCTION, FieldEncoding::AbiWords),
         config: sol_config,
         commitments,
         sampledValues: sampled_values,
@@ -265,6 +282,9 @@ pub fn prepare_verification_params<C: FrameworkEval>(
     n_preprocessed_columns: usize,
 ) -> Result<VerificationParams, Box<dyn std::error::Error>> {
     let mut component_params = Vec::new();
+    // Preprocessed columns are identified on-chain by the index of their first
+    // appearance across components, as the Rust allocator shares them by id
+    let mut preprocessed_ids = Vec::new();
     for comp in &components {
         let info = ComponentInfo {
             maxConstraintLogDegreeBound: comp.max_constraint_log_degree_bound(),
@@ -284,8 +304,16 @@ pub fn prepare_verification_params<C: FrameworkEval>(
                 .info
                 .preprocessed_columns
                 .iter()
-                .enumerate()
-                .map(|(idx, _)| U256::from(idx))
+                .map(|id| {
+                    let idx = preprocessed_ids
+                        .iter()
+                        .position(|seen| seen == &id)
+                        .unwrap_or_else(|| {
+                            preprocessed_ids.push(id);
+                            preprocessed_ids.len() - 1
+                        });
+                    U256::from(idx)
+                })
                 .collect(),
         };
         let params = ComponentParams {
@@ -0,0 +1,307 @@
+//! Compact calldata encoding decoded on-chain by `ProofParser.decodePacked`.
+//!
+//! Fields follow the `Proof` struct order. Integers are big-endian, an M31 takes
+//! 4 bytes and a QM31 16 bytes, and every array or byte string is prefixed by a
+//! `u32` length. The ABI encoding instead spends a 32-byte word per M31.
+//! The decoder rejects M31 values that are not canonical (`>= P`).
+
+use alloy_primitives::{Bytes, FixedBytes};
+
+use crate::{
+    format::FieldEncoding,
+    FriVerifier::{FriLayerProof, FriProof},
+    PcsConfig::Config,
+    ProofParser::{CompositionPoly, Proof, ProofHeader},
+    QM31Field::QM31,
+};
+
+/// Calldata gas per zero byte (EIP-2028)
+pub const CALLDATA_ZERO_BYTE_GAS: u64 = 4;
+/// Calldata gas per non-zero byte (EIP-2028)
+pub const CALLDATA_NONZERO_BYTE_GAS: u64 = 16;
+
+/// Encode a proof for `STWOVerifier.verifyPacked`, setting the header's
+/// field encoding to [`FieldEncoding::Packed`]. Fails when a length or
+/// `nQueries` does not fit in a `u32`.
+pub fn encode_packed_proof(proof: &Proof) -> Result<Bytes, String> {
+    let mut out = Vec::new();
+    write_header(&mut out, &proof.header);
+    write_config(&mut out, &proof.config)?;
+    write_bytes32_array(&mut out, &proof.commitments)?;
+
+    write_len(&mut out, proof.sampledValues.len())?;
+    for tree in &proof.sampledValues {
+        write_len(&mut out, tree.len())?;
+        for column in tree {
+            write_qm31_array(&mut out, column)?;
+        }
+    }
+
+    write_len(&mut out, proof.decommitments.len())?;
+    for decommitment in &proof.decommitments {
+        write_bytes(&mut out, decommitment)?;
+    }
+
+    write_len(&mut out, proof.queriedValues.len())?;
+    for tree in &proof.queriedValues {
+        write_u32_array(&mut out, tree)?;
+    }
+
+    out.extend_from_slice(&proof.proofOfWork.to_be_bytes());
+    write_fri_proof(&mut out, &proof.friProof)?;
+    write_composition_poly(&mut out, &proof.compositionPoly)?;
+    Ok(Bytes::from(out))
+}
+
+/// Intrinsic calldata gas of `data`
+pub fn calldata_gas(data: &[u8]) -> u64 {
+    data.iter()
+        .map(|&byte| {
+            if byte == 0 {
+                CALLDATA_ZERO_BYTE_GAS
+            } else {
+                CALLDATA_NONZERO_BYTE_GAS
+            }
+        })
+        .sum()
+}
+
+fn write_header(out: &mut Vec<u8>, header: &ProofHeader) {
+    out.extend_from_slice(header.magic.as_slice());
+    out.push(header.version);
+    out.push(header.hashFunction);
+    out.push(FieldEncoding::Packed.id());
+}
+
+fn write_config(out: &mut Vec<u8>, config: &Config) -> Result<(), String> {
+    let n_queries: u32 = config
+        .friConfig
+        .nQueries
+        .try_into()
+        .map_err(|_| format!("nQueries {} does not fit in u32", config.friConfig.nQueries))?;
+    write_u32(out, config.powBits);
+    write_u32(out, config.friConfig.logBlowupFactor);
+    write_u32(out, config.friConfig.logLastLayerDegreeBound);
+    write_u32(out, n_queries);
+    Ok(())
+}
+
+fn write_fri_proof(out: &mut Vec<u8>, fri_proof: &FriProof) -> Result<(), String> {
+    write_fri_layer(out, &fri_proof.firstLayer)?;
+    write_len(out, fri_proof.innerLayers.len())?;
+    for layer in &fri_proof.innerLayers {
+        write_fri_layer(out, layer)?;
+    }
+    write_qm31_array(out, &fri_proof.lastLayerPoly)
+}
+
+fn write_fri_layer(out: &mut Vec<u8>, layer: &FriLayerProof) -> Result<(), String> {
+    write_qm31_array(out, &layer.friWitness)?;
+    write_bytes(out, &layer.decommitment)?;
+    out.extend_from_slice(layer.commitment.as_slice());
+    Ok(())
+}
+
+fn write_composition_poly(out: &mut Vec<u8>, poly: &CompositionPoly) -> Result<(), String> {
+    write_u32_array(out, &poly.coeffs0)?;
+    write_u32_array(out, &poly.coeffs1)?;
+    write_u32_array(out, &poly.coeffs2)?;
+    write_u32_array(out, &poly.coeffs3)
+}
+
+fn write_qm31_array(out: &mut Vec<u8>, values: &[QM31]) -> Result<(), String> {
+    write_len(out, values.len())?;
+    for value in values {
+        write_u32(out, value.first.real);
+        write_u32(out, value.first.imag);
+        write_u32(out, value.second.real);
+        write_u32(out, value.second.imag);
+    }
+    Ok(())
+}
+
+fn write_bytes32_array(out: &mut Vec<u8>, values: &[FixedBytes<32>]) -> Result<(), String> {
+    write_len(out, values.len())?;
+    for value in values {
+        out.extend_from_slice(value.as_slice());
+    }
+    Ok(())
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) -> Result<(), String> {
+    write_len(out, bytes.len())?;
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn write_u32_array(out: &mut Vec<u8>, values: &[u32]) -> Result<(), String> {
+    write_len(out, values.len())?;
+    for &value in values {
+        write_u32(out, value);
+    }
+    Ok(())
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    write_u32(out, check_len(len)?);
+    Ok(())
+}
+
+fn check_len(len: usize) -> Result<u32, String> {
+    u32::try_from(len).map_err(|_| format!("Length {} does not fit in u32", len))
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decommitment::encode_decommitment,
+        format::{proof_header, ProofVersion},
+        hash::HashFunction,
+        CM31Field::CM31,
+        MerkleVerifier::Decommitment,
+        PcsConfig::FriConfig,
+    };
+    use alloy::{providers::ProviderBuilder, sol};
+    use alloy_primitives::U256;
+    use alloy_sol_types::SolValue;
+
+    sol!(
+        #[sol(rpc)]
+        PackedProofHarness,
+        "../../out/PackedProofHarness.sol/PackedProofHarness.json"
+    );
+
+    fn qm31(seed: u32) -> QM31 {
+        QM31 {
+            first: CM31 {
+                real: seed,
+                imag: seed + 1,
+            },
+            second: CM31 {
+                real: seed + 2,
+                imag: 0x7fff_fffe,
+            },
+        }
+    }
+
+    fn sample_proof() -> Proof {
+        let layer = |seed: u8| FriLayerProof {
+            friWitness: vec![qm31(seed as u32)],
+            decommitment: encode_decommitment(&Decommitment {
+                hashWitness: vec![FixedBytes::repeat_byte(seed)],
+                columnWitness: vec![seed as u32],
+            }),
+            commitment: FixedBytes::repeat_byte(seed + 1),
+        };
+        Proof {
+            header: proof_header(
+                ProofVersion::FullComposition,
+                HashFunction::Blake2s,
+                FieldEncoding::AbiWords,
+            ),
+            config: Config {
+                powBits: 10,
+                friConfig: FriConfig {
+                    logBlowupFactor: 1,
+                    logLastLayerDegreeBound: 0,
+                    nQueries: U256::from(3),
+                },
+            },
+            commitments: vec![FixedBytes::repeat_byte(0x11), FixedBytes::repeat_byte(0x12)],
+            sampledValues: vec![vec![vec![qm31(1), qm31(5)], vec![]], vec![vec![qm31(9)]]],
+            decommitments: vec![encode_decommitment(&Decommitment {
+                hashWitness: vec![FixedBytes::repeat_byte(0x33)],
+                columnWitness: vec![1, 2],
+            })],
+            queriedValues: vec![vec![5, 6, 7], vec![]],
+            proofOfWork: 0x0102_0304_0506_0708,
+            friProof: FriProof {
+                firstLayer: layer(0x20),
+                innerLayers: vec![layer(0x30), layer(0x40)],
+                lastLayerPoly: vec![qm31(13)],
+            },
+            compositionPoly: CompositionPoly {
+                coeffs0: vec![1, 2],
+                coeffs1: vec![3, 4],
+                coeffs2: vec![5, 6],
+                coeffs3: vec![7, 0x7fff_fffe],
+            },
+        }
+    }
+
+    #[test]
+    fn test_oversized_n_queries_is_an_error() {
+        let mut proof = sample_proof();
+        proof.config.friConfig.nQueries = U256::from(u64::from(u32::MAX) + 1);
+        assert!(encode_packed_proof(&proof).is_err());
+    }
+
+    #[test]
+    fn test_calldata_gas() {
+        assert_eq!(calldata_gas(&[]), 0);
+        assert_eq!(calldata_gas(&[0, 0, 1, 0xff]), 4 + 4 + 16 + 16);
+    }
+
+    #[tokio::test]
+    async fn test_solidity_decode_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let provider = ProviderBuilder::new().connect_anvil_with_wallet();
+        let harness = PackedProofHarness::deploy(&provider).await?;
+
+        let proof = sample_proof();
+        let packed = encode_packed_proof(&proof)?;
+        let decoded = harness.decodePacked(packed.clone()).call().await?;
+
+        let mut expected = proof;
+        expected.header.fieldEncoding = FieldEncoding::Packed.id();
+        assert_eq!(decoded.to_vec(), expected.abi_encode());
+
+        let mut trailing = packed.to_vec();
+        trailing.push(0);
+        for (name, bytes) in [
+            ("truncated", packed[..packed.len() - 1].to_vec()),
+            ("header only", packed[..7].to_vec()),
+            ("trailing byte", trailing),
+        ] {
+            let result = harness.decodePacked(Bytes::from(bytes)).call().await;
+            assert!(result.is_err(), "Solidity decoder accepted {}", name);
+        }
+
+        // Every M31 slot must hold a canonical value below P = 2^31 - 1.
+        // Main-tree decommitments are checked by MerkleVerifier.decodeDecommitment.
+        let p = 0x7fff_ffff;
+        let non_canonical: [(&str, fn(&mut Proof, u32)); 4] = [
+            ("sampled value", |proof, v| {
+                proof.sampledValues[0][0][1].second.imag = v
+            }),
+            ("queried value", |proof, v| proof.queriedValues[0][2] = v),
+            ("last layer", |proof, v| {
+                proof.friProof.lastLayerPoly[0].first.real = v
+            }),
+            ("composition coefficient", |proof, v| {
+                proof.compositionPoly.coeffs3[1] = v
+            }),
+        ];
+        for (name, set) in non_canonical {
+            for value in [p, u32::MAX] {
+                let mut proof = sample_proof();
+                set(&mut proof, value);
+                let result = harness
+                    .decodePacked(encode_packed_proof(&proof)?)
+                    .call()
+                    .await;
+                assert!(
+                    result.is_err(),
+                    "Solidity decoder accepted {} {:#x}",
+                    name,
+                    value
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+//! Proving pipeline producing a ready [`VerifierInput`] for any `FrameworkEval` AIR.
+//!
+//! The on-chain verifier resumes the Fiat-Shamir transcript from the channel
+//! digest after the last trace commitment, so the digest, tree roots and
+//! extended column log sizes are all taken from the prover run here instead of
+//! being reconstructed by every circuit.
+
+use stwo::{
+    core::{
+        air::Component,
+        channel::Channel,
+        fields::m31::BaseField,
+        pcs::{CommitmentSchemeVerifier, PcsConfig},
+        poly::circle::CanonicCoset,
+        proof::StarkProof,
+        ColumnVec,
+    },
+    prover::{
+        backend::{simd::SimdBackend, BackendForChannel},
+        poly::{
+            circle::{CircleEvaluation, PolyOps, SecureCirclePoly},
+            twiddles::TwiddleTree,
+            BitReversedOrder,
+        },
+        CommitmentSchemeProver, ComponentProver,
+    },
+};
+use stwo_constraint_framework::{FrameworkComponent, FrameworkEval};
+use stwo_polynomial::{prove::prove, verify::verify};
+use tracing::{info, info_span};
+
+use crate::{
+    convert_to_solidity_proof_versioned,
+    degree::check_degree_bound,
+    format::ProofVersion,
+    hash::{ChannelOf, HasherOf, SolidityHasher},
+    prepare_verification_params, VerifierInput,
+};
+
+/// Trace columns committed as one tree
+pub type TraceEvals = ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+/// Proof parameters for [`prove_for_solidity`]
+#[derive(Debug, Clone, Copy)]
+pub struct SolidityProverConfig {
+    pub pcs_config: PcsConfig,
+    pub proof_version: ProofVersion,
+    /// Accept components declaring a larger `max_constraint_log_degree_bound`
+    /// than their constraints need, see [`check_degree_bound`]
+    pub allow_oversized_degree_bound: bool,
+}
+
+impl Default for SolidityProverConfig {
+    fn default() -> Self {
+        PcsConfig::default().into()
+    }
+}
+
+impl From<PcsConfig> for SolidityProverConfig {
+    fn from(pcs_config: PcsConfig) -> Self {
+        Self {
+            pcs_config,
+            proof_version: ProofVersion::FullComposition,
+            allow_oversized_degree_bound: false,
+        }
+    }
+}
+
+/// Twiddles large enough for every tree of a proof whose highest constraint
+/// log degree bound is `max_constraint_log_degree_bound`
+pub fn twiddles_for(
+    max_constraint_log_degree_bound: u32,
+    pcs_config: &PcsConfig,
+) -> TwiddleTree<SimdBackend> {
+    SimdBackend::precompute_twiddles(
+        CanonicCoset::new(
+            max_constraint_log_degree_bound + pcs_config.fri_config.log_blowup_factor,
+        )
+        .circle_domain()
+        .half_coset,
+    )
+}
+
+/// Prove `components` over the given traces and convert the proof for `STWOVerifier`.
+///
+/// `preprocessed` is always committed (possibly empty); `interaction` is committed
+/// only when non-empty. AIRs whose interaction trace depends on channel draws,
+/// such as lookups, use [`SolidityProver`] directly.
+pub fn prove_for_solidity<H, E>(
+    components: Vec<FrameworkComponent<E>>,
+    preprocessed: TraceEvals,
+    trace: TraceEvals,
+    interaction: TraceEvals,
+    config: impl Into<SolidityProverConfig>,
+) -> Result<VerifierInput, Box<dyn std::error::Error>>
+where
+    H: SolidityHasher,
+    E: FrameworkEval + Sync,
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    prove_for_solidity_with_public_inputs::<H, E>(
+        components,
+        &[],
+        preprocessed,
+        trace,
+        interaction,
+        config,
+    )
+}
+
+/// [`prove_for_solidity`], first mixing `public_inputs` into the transcript
+/// (see [`SolidityProver::mix_public_inputs`]). Nothing is mixed when empty.
+pub fn prove_for_solidity_with_public_inputs<H, E>(
+    components: Vec<FrameworkComponent<E>>,
+    public_inputs: &[u32],
+    preprocessed: TraceEvals,
+    trace: TraceEvals,
+    interaction: TraceEvals,
+    config: impl Into<SolidityProverConfig>,
+) -> Result<VerifierInput, Box<dyn std::error::Error>>
+where
+    H: SolidityHasher,
+    E: FrameworkEval + Sync,
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    let proof = prove_unconverted_with_public_inputs::<H, E>(
+        &components,
+        public_inputs,
+        preprocessed,
+        trace,
+        interaction,
+        config,
+    )?;
+    proof.verify(&components)?;
+    proof.convert(components)
+}
+
+/// [`prove_for_solidity_with_public_inputs`] without the off-chain check and
+/// the conversion, see [`SolidityProof`]
+pub fn prove_unconverted_with_public_inputs<H, E>(
+    components: &[FrameworkComponent<E>],
+    public_inputs: &[u32],
+    preprocessed: TraceEvals,
+    trace: TraceEvals,
+    interaction: TraceEvals,
+    config: impl Into<SolidityProverConfig>,
+) -> Result<SolidityProof<H>, Box<dyn std::error::Error>>
+where
+    H: SolidityHasher,
+    E: FrameworkEval + Sync,
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    with_committed_traces::<H, _>(
+        max_log_degree_bound(components)?,
+        public_inputs,
+        preprocessed,
+        trace,
+        config,
+        |mut prover| {
+            if !interaction.is_empty() {
+                prover.commit(interaction);
+            }
+            prover.prove_unconverted(components)
+        },
+    )
+}
+
+/// Run the commitment sequence shared by every proof and hand the prover to
+/// `finish`, which commits any remaining trees and proves: twiddles for
+/// `max_log_degree_bound`, `public_inputs` mixed when non-empty, then the
+/// preprocessed and main traces.
+pub fn with_committed_traces<H, R>(
+    max_log_degree_bound: u32,
+    public_inputs: &[u32],
+    preprocessed: TraceEvals,
+    trace: TraceEvals,
+    config: impl Into<SolidityProverConfig>,
+    finish: impl FnOnce(SolidityProver<'_, H>) -> R,
+) -> R
+where
+    H: SolidityHasher,
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    let config = config.into();
+    let twiddles = twiddles_for(max_log_degree_bound, &config.pcs_config);
+
+    let mut prover = SolidityProver::<H>::new(config, &twiddles);
+    if !public_inputs.is_empty() {
+        prover.mix_public_inputs(public_inputs);
+    }
+    prover.commit(preprocessed);
+    prover.commit(trace);
+    finish(prover)
+}
+
+/// Highest constraint log degree bound among `components`
+pub fn max_log_degree_bound<E: FrameworkEval>(
+    components: &[FrameworkComponent<E>],
+) -> Result<u32, String> {
+    components
+        .iter()
+        .map(|component| component.max_constraint_log_degree_bound())
+        .max()
+        .ok_or_else(|| "No components to prove".to_string())
+}
+
+/// Step-by-step prover: commit trees in order, drawing from [`Self::channel`]
+/// in between where the AIR needs it, then [`Self::prove`].
+/// The first committed tree is the preprocessed trace.
+pub struct SolidityProver<'a, H: SolidityHasher>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    config: SolidityProverConfig,
+    channel: ChannelOf<H>,
+    commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, H::MerkleChannel>,
+    tree_log_sizes: Vec<Vec<u32>>,
+}
+
+impl<'a, H: SolidityHasher> SolidityProver<'a, H>
+where
+    SimdBackend: BackendForChannel<H::MerkleChannel>,
+{
+    pub fn new(
+        config: impl Into<SolidityProverConfig>,
+        twiddles: &'a TwiddleTree<SimdBackend>,
+    ) -> Self {
+        let config = config.into();
+        Self {
+            config,
+            channel: ChannelOf::<H>::default(),
+            commitment_scheme: CommitmentSchemeProver::new(config.pcs_config, twiddles),
+            tree_log_sizes: Vec::new(),
+        }
+    }
+
+    /// Channel at the current point of the transcript
+    pub fn channel(&mut self) -> &mut ChannelOf<H> {
+        &mut self.channel
+    }
+
+    /// Mix public inputs into the transcript. Called before the first commit,
+    /// the resulting digest is `H(0^32 || le(values)) -> mixRoot(root_i)...`,
+    /// which a consumer contract can recompute from its public inputs and the
+    /// tree roots to bind them to the proof.
+    pub fn mix_public_inputs(&mut self, values: &[u32]) {
+        self.channel.mix_u32s(values);
+    }
+
+    /// Commit the next tree
+    pub fn commit(&mut self, evals: TraceEvals) {
+        self.tree_log_sizes
+            .push(evals.iter().map(|eval| eval.domain.log_size()).collect());
+
+        let mut tree_builder = self.commitment_scheme.tree_builder();
+        tree_builder.extend_evals(evals);
+        tree_builder.commit(&mut self.channel);
+    }
+
+    /// Check every component's declared degree bound, prove, check the proof
+    /// off-chain and build the contract input
+    pub fn prove<E>(
+        self,
+        components: Vec<FrameworkComponent<E>>,
+    ) -> Result<VerifierInput, Box<dyn std::error::Error>>
+    where
+        E: FrameworkEval + Sync,
+    {
+        let proof = self.prove_unconverted(&components)?;
+        proof.verify(&components)?;
+        proof.convert(components)
+    }
+
+    /// Check every component's declared degree bound and prove, leaving
+    /// verification and conversion to the returned [`SolidityProof`]
+    pub fn prove_unconverted<E>(
+        self,
+        components: &[FrameworkComponent<E>],
+    ) -> Result<SolidityProof<H>, Box<dyn std::error::Error>>
+    where
+        E: FrameworkEval + Sync,
+    {
+        for component in components {
+            check_degree_bound(&**component, self.config.allow_oversized_degree_bound)?;
+        }
+
+        let Self {
+            config,
+            mut channel,
+            commitment_scheme,
+            tree_log_sizes,
+        } = self;
+        // The contract resumes the transcript from here
+        let resume_channel = channel.clone();
+
+        let _prove_span = info_span!("prove", components = components.len()).entered();
+        let component_provers: Vec<&dyn ComponentProver<SimdBackend>> = components
+            .iter()
+            .map(|component| component as &dyn ComponentProver<SimdBackend>)
+            .collect();
+        let (stark_proof, composition_polynomial) =
+            prove(&component_provers, &mut channel, commitment_scheme)?;
+
+        Ok(SolidityProof {
+            stark_proof,
+            composition_polynomial,
+            channel: resume_channel,
+            tree_log_sizes,
+            config,
+        })
+    }
+}
+
+/// Proof from [`SolidityProver::prove_unconverted`], before conversion
+pub struct SolidityProof<H: SolidityHasher> {
+    pub stark_proof: StarkProof<HasherOf<H>>,
+    pub composition_polynomial: SecureCirclePoly<SimdBackend>,
+    /// Channel after the last trace commitment, where the contract resumes
+    pub channel: ChannelOf<H>,
+    /// Trace log sizes of every committed tree, preprocessed first
+    pub tree_log_sizes: Vec<Vec<u32>>,
+    pub config: SolidityProverConfig,
+}
+
+impl<H: SolidityHasher> SolidityProof<H> {
+    /// Check the proof off-chain against `components`
+    pub fn verify<E: FrameworkEval + Sync>(
+        &self,
+        components: &[FrameworkComponent<E>],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Trees are registered with a scratch channel since `self.channel`
+        // already holds the post-commitment state
+        let mut commitment_scheme =
+            CommitmentSchemeVerifier::<H::MerkleChannel>::new(self.config.pcs_config);
+        let scratch_channel = &mut ChannelOf::<H>::default();
+        for (root, log_sizes) in self
+            .stark_proof
+            .commitments
+            .iter()
+            .zip(&self.tree_log_sizes)
+        {
+            commitment_scheme.commit(*root, log_sizes, scratch_channel);
+        }
+        let component_refs: Vec<&dyn Component> = components
+            .iter()
+            .map(|component| component as &dyn Component)
+            .collect();
+        verify(
+            &component_refs,
+            &mut self.channel.clone(),
+            &mut commitment_scheme,
+            self.stark_proof.clone(),
+            self.composition_polynomial.clone(),
+        )?;
+        info!("Proof verified off-chain");
+        Ok(())
+    }
+
+    /// Build the contract input for `components`
+    pub fn convert<E: FrameworkEval>(
+        self,
+        components: Vec<FrameworkComponent<E>>,
+    ) -> Result<VerifierInput, Box<dyn std::error::Error>> {
+        let Self {
+            stark_proof,
+            composition_polynomial,
+            channel,
+            tree_log_sizes,
+            config,
+        } = self;
+        let _convert_span = info_span!("convert", version = ?config.proof_version).entered();
+
+        let n_preprocessed_columns = tree_log_sizes.first().map_or(0, Vec::len);
+        let tree_roots = stark_proof
+            .commitments
+            .iter()
+            .take(tree_log_sizes.len())
+            .map(H::hash_to_bytes32)
+            .collect();
+        let tree_column_log_sizes = tree_log_sizes
+            .iter()
+            .map(|log_sizes| {
+                log_sizes
+                    .iter()
+                    .map(|&log_size| log_size + config.pcs_config.fri_config.log_blowup_factor)
+                    .collect()
+            })
+            .collect();
+
+        let verification_params = prepare_verification_params(components, n_preprocessed_columns)?;
+        let proof = convert_to_solidity_proof_versioned::<H>(
+            stark_proof,
+            composition_polynomial,
+            config.proof_version,
+        );
+        info!("Proof converted for the Solidity verifier");
+
+        Ok(VerifierInput {
+            proof,
+            verificationParams: verification_params,
+            treeRoots: tree_roots,
+            treeColumnLogSizes: tree_column_log_sizes,
+            digest: H::channel_digest(&channel),
+            nDraws: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        format::{check_header, FieldEncoding},
+        hash::{HashFunction, Keccak},
+    };
+    use num_traits::Zero;
+    use stwo::{
+        core::{fields::qm31::SecureField, vcs::keccak_merkle::KeccakMerkleChannel},
+        prover::backend::{Col, Column},
+    };
+    use stwo_constraint_framework::{EvalAtRow, TraceLocationAllocator};
+
+    const LOG_SIZE: u32 = 5;
+
+    /// `a * b = c` on every row
+    struct ProductEval;
+
+    impl FrameworkEval for ProductEval {
+        fn log_size(&self) -> u32 {
+            LOG_SIZE
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            LOG_SIZE + 1
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = eval.next_trace_mask();
+            let b = eval.next_trace_mask();
+            let c = eval.next_trace_mask();
+            eval.add_constraint(a * b - c);
+            eval
+        }
+    }
+
+    fn product_trace() -> TraceEvals {
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let mut columns = [(); 3].map(|_| Col::<SimdBackend, BaseField>::zeros(1 << LOG_SIZE));
+        for row in 0..1 << LOG_SIZE {
+            let a = BaseField::from(row as u32 + 3);
+            let b = BaseField::from(row as u32 * 7 + 1);
+            columns[0].set(row, a);
+            columns[1].set(row, b);
+            columns[2].set(row, a * b);
+        }
+        columns
+            .into_iter()
+            .map(|column| CircleEvaluation::new(domain, column))
+            .collect()
+    }
+
+    #[test]
+    fn test_prove_for_solidity() {
+        let config = SolidityProverConfig::default();
+        let component = FrameworkComponent::new(
+            &mut TraceLocationAllocator::default(),
+            ProductEval,
+            SecureField::zero(),
+        );
+        let input = prove_for_solidity::<Keccak, _>(
+            vec![component],
+            vec![],
+            product_trace(),
+            vec![],
+            config,
+        )
+        .unwrap();
+
+        // Commit the same trees independently
+        let twiddles = twiddles_for(LOG_SIZE + 1, &config.pcs_config);
+        let mut channel = ChannelOf::<Keccak>::default();
+        let mut commitment_scheme: CommitmentSchemeProver<SimdBackend, KeccakMerkleChannel> =
+            CommitmentSchemeProver::new(config.pcs_config, &twiddles);
+        for evals in [vec![], product_trace()] {
+            let mut tree_builder = commitment_scheme.tree_builder();
+            tree_builder.extend_evals(evals);
+            tree_builder.commit(&mut channel);
+        }
+
+        assert_eq!(input.digest, Keccak::channel_digest(&channel));
+        let roots: Vec<_> = commitment_scheme
+            .roots()
+            .iter()
+            .map(Keccak::hash_to_bytes32)
+            .collect();
+        assert_eq!(input.treeRoots, roots);
+
+        let extended_log_size = LOG_SIZE + config.pcs_config.fri_config.log_blowup_factor;
+        assert_eq!(
+            input.treeColumnLogSizes,
+            vec![vec![], vec![extended_log_size; 3]]
+        );
+        assert_eq!(input.nDraws, 0);
+        assert_eq!(
+            check_header(&input.proof.header),
+            Ok((
+                ProofVersion::FullComposition,
+                HashFunction::Keccak,
+                FieldEncoding::AbiWords
+            ))
+        );
+    }
+}
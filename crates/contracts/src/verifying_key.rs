@@ -0,0 +1,95 @@
+//! Verifying key a consumer contract pins at deployment, matching
+//! `contracts/examples/PinnedVerifyingKey.sol`.
+//!
+//! STWOVerifier checks a proof against the parameters it is given, so a
+//! consumer has to fix those parameters to accept a single circuit.
+
+use alloy::sol;
+use alloy_primitives::{keccak256, FixedBytes};
+use alloy_sol_types::SolValue;
+
+use crate::{VerificationParams, VerifierInput};
+
+sol!(
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct VerifyingKey {
+        uint8 hashFunction;
+        bytes32 preprocessedRoot;
+        bytes32 configHash;
+        bytes32 paramsHash;
+        bytes32 columnLogSizesHash;
+    }
+);
+
+impl VerifyingKey {
+    /// Key of the circuit `input` was proven for
+    pub fn of(input: &VerifierInput) -> Self {
+        Self {
+            hashFunction: input.proof.header.hashFunction,
+            preprocessedRoot: input.treeRoots.first().copied().unwrap_or_default(),
+            configHash: keccak256(input.proof.config.abi_encode()),
+            paramsHash: verification_params_hash(&input.verificationParams),
+            columnLogSizesHash: keccak256(input.treeColumnLogSizes.abi_encode()),
+        }
+    }
+}
+
+/// `PinnedVerifyingKey.verificationParamsHash`: `params` without the component
+/// claimed sums, which depend on the statement rather than the circuit
+pub fn verification_params_hash(params: &VerificationParams) -> FixedBytes<32> {
+    let mut encoded = (
+        params.nPreprocessedColumns,
+        params.componentsCompositionLogDegreeBound,
+    )
+        .abi_encode_params();
+    for component in &params.componentParams {
+        let component_hash =
+            keccak256((component.logSize, component.info.clone()).abi_encode_params());
+        encoded.extend_from_slice(component_hash.as_slice());
+    }
+    keccak256(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CM31Field::CM31, ComponentParams, FrameworkComponentLib::ComponentInfo, QM31Field::QM31,
+    };
+    use alloy_primitives::U256;
+
+    fn sample_params(claimed_sum: u32) -> VerificationParams {
+        let cm31 = |real| CM31 { real, imag: 0 };
+        VerificationParams {
+            componentParams: vec![ComponentParams {
+                logSize: 5,
+                claimedSum: QM31 {
+                    first: cm31(claimed_sum),
+                    second: cm31(0),
+                },
+                info: ComponentInfo {
+                    maxConstraintLogDegreeBound: 6,
+                    logSize: 5,
+                    maskOffsets: vec![vec![vec![0]], vec![vec![0], vec![-1, 0]]],
+                    preprocessedColumns: vec![U256::from(1)],
+                },
+            }],
+            nPreprocessedColumns: U256::from(1),
+            componentsCompositionLogDegreeBound: 6,
+        }
+    }
+
+    #[test]
+    fn test_params_hash_ignores_claimed_sums() {
+        let hash = verification_params_hash(&sample_params(0));
+        assert_eq!(verification_params_hash(&sample_params(7)), hash);
+
+        let mut other = sample_params(0);
+        other.componentParams[0].info.maskOffsets[1][1] = vec![0, 1];
+        assert_ne!(verification_params_hash(&other), hash);
+
+        let mut other = sample_params(0);
+        other.componentParams.push(other.componentParams[0].clone());
+        assert_ne!(verification_params_hash(&other), hash);
+    }
+}
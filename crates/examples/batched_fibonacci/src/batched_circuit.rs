@@ -0,0 +1,366 @@
+use std::str::FromStr;
+
+use contracts::batch::{encode_batch, InstanceResult};
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use contracts::prover::TraceEvals;
+use num_traits::{One, Zero};
+use stwo::core::channel::Channel;
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use stwo::prover::backend::simd::qm31::PackedSecureField;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{
+    relation, EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation,
+    RelationEntry, TraceLocationAllocator,
+};
+
+/// Largest batch the example proves
+pub const MAX_INSTANCES: usize = 1 << 16;
+
+/// Input `(a, b)` and output of one instance
+relation!(BatchResultRelation, 3);
+
+/// One instance per row: the row runs `n_steps` Fibonacci steps from its
+/// input `(a, b)` across `n_steps + 2` columns.
+///
+/// Every row adds `1 / combine(a, b, output)` to the LogUp sum, so the claimed
+/// sum commits to the instance table and `BatchResultRegistry` checks it
+/// against the public inputs. Padding rows are the instance `(0, 0) -> 0`.
+#[derive(Clone)]
+pub struct BatchedFibonacciEval {
+    pub log_n_rows: u32,
+    pub n_steps: usize,
+    pub relation: BatchResultRelation,
+}
+
+impl FrameworkEval for BatchedFibonacciEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_rows + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let input_a = eval.next_trace_mask();
+        let input_b = eval.next_trace_mask();
+        let (mut a, mut b) = (input_a.clone(), input_b.clone());
+        for _ in 0..self.n_steps {
+            let c = eval.next_trace_mask();
+            eval.add_constraint(c.clone() - (a + b.clone()));
+            a = b;
+            b = c;
+        }
+        // Same value order as gen_instance_table
+        eval.add_to_relation(RelationEntry::new(
+            &self.relation,
+            E::EF::one(),
+            &[input_a, input_b, b],
+        ));
+        eval.finalize_logup_in_pairs();
+        eval
+    }
+}
+
+pub type BatchedFibonacciComponent = FrameworkComponent<BatchedFibonacciEval>;
+
+/// Value after `n_steps` Fibonacci steps from `(a, b)`
+pub fn fibonacci(a: BaseField, b: BaseField, n_steps: usize) -> BaseField {
+    let (mut a, mut b) = (a, b);
+    for _ in 0..n_steps {
+        (a, b) = (b, a + b);
+    }
+    b
+}
+
+/// Rows for `n_instances` instances: a power of two, never below one SIMD vector
+pub fn calculate_log_size(n_instances: usize) -> u32 {
+    n_instances.next_power_of_two().ilog2().max(LOG_N_LANES)
+}
+
+/// Batch shape, parsed from `N_INSTANCES:N_STEPS`. Instance `i` starts from
+/// `(i, i + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInput {
+    pub n_instances: usize,
+    pub n_steps: usize,
+}
+
+impl FromStr for BatchInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (n_instances, n_steps) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Expected N_INSTANCES:N_STEPS, got {}", s))?;
+        let n_instances: usize = n_instances
+            .parse()
+            .map_err(|e| format!("Invalid instance count {}: {}", n_instances, e))?;
+        let n_steps: usize = n_steps
+            .parse()
+            .map_err(|e| format!("Invalid step count {}: {}", n_steps, e))?;
+        if n_instances == 0 || n_instances > MAX_INSTANCES {
+            return Err(format!(
+                "Instance count must be between 1 and {}",
+                MAX_INSTANCES
+            ));
+        }
+        if n_steps == 0 {
+            return Err("At least one step is needed".to_string());
+        }
+        Ok(Self {
+            n_instances,
+            n_steps,
+        })
+    }
+}
+
+impl BatchInput {
+    /// Inputs `(a, b)` of every instance
+    pub fn instances(&self) -> Vec<(BaseField, BaseField)> {
+        (0..self.n_instances as u32)
+            .map(|i| (BaseField::from(i), BaseField::from(i + 1)))
+            .collect()
+    }
+
+    /// Inputs and outputs of every instance
+    pub fn results(&self) -> Vec<InstanceResult> {
+        self.instances()
+            .into_iter()
+            .map(|(a, b)| InstanceResult {
+                input: vec![a.0, b.0],
+                output: vec![fibonacci(a, b, self.n_steps).0],
+            })
+            .collect()
+    }
+}
+
+fn column(
+    values: impl IntoIterator<Item = BaseField>,
+    log_size: u32,
+) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+    let mut col = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+    for (row, value) in values.into_iter().enumerate() {
+        col.set(row, value);
+    }
+    CircleEvaluation::new(CanonicCoset::new(log_size).circle_domain(), col)
+}
+
+/// Instance table: input `a`, input `b` and output per row, the values each
+/// row adds to the LogUp sum
+pub fn gen_instance_table(
+    input: &BatchInput,
+) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+    let log_size = calculate_log_size(input.n_instances);
+    let instances = input.instances();
+    vec![
+        column(instances.iter().map(|&(a, _)| a), log_size),
+        column(instances.iter().map(|&(_, b)| b), log_size),
+        column(
+            instances
+                .iter()
+                .map(|&(a, b)| fibonacci(a, b, input.n_steps)),
+            log_size,
+        ),
+    ]
+}
+
+/// Every intermediate value of every instance, one column per step
+pub fn gen_trace(
+    input: &BatchInput,
+) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+    let log_size = calculate_log_size(input.n_instances);
+    let mut cols: Vec<Col<SimdBackend, BaseField>> = (0..input.n_steps + 2)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(1 << log_size))
+        .collect();
+    for (row, (mut a, mut b)) in input.instances().into_iter().enumerate() {
+        cols[0].set(row, a);
+        cols[1].set(row, b);
+        for col in cols.iter_mut().skip(2) {
+            (a, b) = (b, a + b);
+            col.set(row, b);
+        }
+    }
+
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    cols.into_iter()
+        .map(|col| CircleEvaluation::new(domain, col))
+        .collect()
+}
+
+/// LogUp column of the instance table and its claimed sum, the sum of
+/// `1 / combine(a, b, output)` over every row
+pub fn gen_interaction_trace(
+    input: &BatchInput,
+    relation: &BatchResultRelation,
+) -> (TraceEvals, SecureField) {
+    let log_size = calculate_log_size(input.n_instances);
+    let table = gen_instance_table(input);
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..1 << (log_size - LOG_N_LANES) {
+        let values: Vec<PackedBaseField> =
+            table.iter().map(|col| col.values.data[vec_row]).collect();
+        let denominator: PackedSecureField = relation.combine(&values);
+        col_gen.write_frac(vec_row, PackedSecureField::one(), denominator);
+    }
+    col_gen.finalize_col();
+    logup_gen.finalize_last()
+}
+
+/// Batch shape plus the lookup elements drawn while proving
+#[derive(Clone)]
+pub struct BatchedFibonacciMetadata {
+    pub input: BatchInput,
+    /// Dummy until drawn in [`SolidityCircuit::draw_interaction`]
+    pub relation: BatchResultRelation,
+    pub claimed_sum: SecureField,
+}
+
+/// Many Fibonacci instances in one proof, each result exposed as a public input
+pub struct BatchedFibonacciCircuit;
+
+impl SolidityCircuit for BatchedFibonacciCircuit {
+    type Eval = BatchedFibonacciEval;
+    type Metadata = BatchedFibonacciMetadata;
+    type Input = BatchInput;
+
+    fn name(&self) -> &'static str {
+        "batched-fibonacci"
+    }
+
+    fn default_input(&self) -> BatchInput {
+        BatchInput {
+            n_instances: 64,
+            n_steps: 32,
+        }
+    }
+
+    fn components(&self, metadata: &BatchedFibonacciMetadata) -> Vec<BatchedFibonacciComponent> {
+        vec![BatchedFibonacciComponent::new(
+            &mut TraceLocationAllocator::default(),
+            BatchedFibonacciEval {
+                log_n_rows: calculate_log_size(metadata.input.n_instances),
+                n_steps: metadata.input.n_steps,
+                relation: metadata.relation.clone(),
+            },
+            metadata.claimed_sum,
+        )]
+    }
+
+    /// No preprocessed columns, so the verifying key does not depend on the
+    /// batch contents
+    fn generate_trace(&self, input: &BatchInput) -> CircuitTrace<BatchedFibonacciMetadata> {
+        CircuitTrace {
+            metadata: BatchedFibonacciMetadata {
+                input: input.clone(),
+                relation: BatchResultRelation::dummy(),
+                claimed_sum: SecureField::zero(),
+            },
+            preprocessed: vec![],
+            trace: gen_trace(input),
+            interaction: vec![],
+        }
+    }
+
+    fn draw_interaction(
+        &self,
+        metadata: &mut BatchedFibonacciMetadata,
+        channel: &mut impl Channel,
+    ) -> TraceEvals {
+        metadata.relation = BatchResultRelation::draw(channel);
+        let (interaction, claimed_sum) = gen_interaction_trace(&metadata.input, &metadata.relation);
+        metadata.claimed_sum = claimed_sum;
+        interaction
+    }
+
+    /// Every instance's `(a, b)` and output in the `contracts::batch` layout,
+    /// as `BatchResultRegistry` decodes it
+    fn public_inputs(&self, input: &BatchInput, _metadata: &BatchedFibonacciMetadata) -> Vec<u32> {
+        encode_batch(&input.results()).expect("instances have uniform shapes")
+    }
+
+    fn binds_public_inputs(&self) -> bool {
+        true
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts::batch::decode_batch;
+    use stwo::core::fields::FieldExpOps;
+
+    #[test]
+    fn test_trace_matches_public_inputs() {
+        let input: BatchInput = "20:10".parse().unwrap();
+        let log_size = calculate_log_size(20);
+        assert_eq!(log_size, 5);
+
+        let table = gen_instance_table(&input);
+        let trace = gen_trace(&input);
+        assert_eq!(trace.len(), 12);
+
+        let circuit = BatchedFibonacciCircuit;
+        let metadata = circuit.generate_trace(&input).metadata;
+        let results = decode_batch(&circuit.public_inputs(&input, &metadata)).unwrap();
+        assert_eq!(results.len(), 20);
+        for (row, result) in results.iter().enumerate() {
+            assert_eq!(trace[0].values.at(row).0, result.input[0]);
+            assert_eq!(trace[1].values.at(row).0, result.input[1]);
+            assert_eq!(trace[11].values.at(row).0, result.output[0]);
+            assert_eq!(table[2].values.at(row), trace[11].values.at(row));
+        }
+        // Row 1 starts from (F(2), F(3)) = (1, 2): ten steps reach F(13) = 233
+        assert_eq!(results[1].output, vec![233]);
+        // Padding rows are all zero
+        assert!(trace.iter().all(|col| col.values.at(25).is_zero()));
+    }
+
+    #[test]
+    fn test_claimed_sum_matches_public_inputs() {
+        let input: BatchInput = "20:10".parse().unwrap();
+        let relation = BatchResultRelation::dummy();
+        let (_, claimed_sum) = gen_interaction_trace(&input, &relation);
+
+        // The sum BatchResultRegistry recomputes from the public inputs, with
+        // the 12 padding rows as the all-zero instance
+        let fraction = |values: &[BaseField]| {
+            let denominator: SecureField = relation.combine(values);
+            denominator.inverse()
+        };
+        let expected_sum = |results: &[InstanceResult]| {
+            let padding = fraction(&[BaseField::zero(); 3]) * BaseField::from(32 - 20);
+            results.iter().fold(padding, |sum, result| {
+                let values: Vec<BaseField> = result
+                    .input
+                    .iter()
+                    .chain(&result.output)
+                    .map(|&value| BaseField::from(value))
+                    .collect();
+                sum + fraction(&values)
+            })
+        };
+        let mut results = input.results();
+        assert_eq!(claimed_sum, expected_sum(&results));
+
+        results[3].output[0] += 1;
+        assert_ne!(claimed_sum, expected_sum(&results));
+    }
+}
@@ -0,0 +1,350 @@
+use alloy::{
+    network::EthereumWallet,
+    providers::ProviderBuilder,
+    sol,
+    sol_types::{SolType, SolValue},
+};
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::batch::{decode_batch, BATCH_HEADER_LEN};
+use contracts::circuit::DynCircuit;
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use contracts::verifying_key::VerifyingKey;
+use serde_json::json;
+use std::time::Instant;
+use tracing::info;
+use verifier::deploy::{AnvilConfig, DeploymentResult, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+
+use batched_fibonacci::batched_circuit::BatchedFibonacciCircuit;
+
+sol!(
+    #[sol(rpc)]
+    BatchResultRegistry,
+    "../../../out/BatchResultRegistry.sol/BatchResultRegistry.json"
+);
+
+/// Batched Fibonacci STARK proof whose per-instance results are recorded by a
+/// consumer contract
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Batched Fibonacci STARK Verifier")
+        .version("1.0")
+        .about("Example proving many Fibonacci instances in one proof and recording each result on-chain")
+        .arg(
+            Arg::new("instances")
+                .long("instances")
+                .short('n')
+                .help("Number of instances in the batch")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("64"),
+        )
+        .arg(
+            Arg::new("steps")
+                .long("steps")
+                .short('s')
+                .help("Fibonacci steps each instance runs")
+                .value_name("STEPS")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("32"),
+        )
+        .arg(
+            Arg::new("lookup")
+                .long("lookup")
+                .help("Comma-separated instance indices to look up after proving")
+                .value_name("I,I,...")
+                .default_value("0,1"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if !json {
+        println!("📦 Batched Fibonacci STARK Verifier Example");
+        println!("===========================================");
+    }
+
+    let n_instances = *matches.get_one::<usize>("instances").unwrap();
+    let n_steps = *matches.get_one::<usize>("steps").unwrap();
+    let lookups = matches
+        .get_one::<String>("lookup")
+        .unwrap()
+        .split(',')
+        .map(|index| index.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+
+    let (deployment_result, deployer) =
+        deploy_verifier(matches.get_one::<String>("network")).await?;
+
+    if !json {
+        println!(
+            "\n📊 Proving {} instances of {} Fibonacci steps",
+            n_instances, n_steps
+        );
+        println!("   Hash function: {:?}", hash_function);
+    }
+
+    let circuit = BatchedFibonacciCircuit;
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+
+    let proving_start = Instant::now();
+    let proved = circuit.prove(
+        Some(&format!("{}:{}", n_instances, n_steps)),
+        hash_function,
+        config,
+    )?;
+    let proving_time = proving_start.elapsed();
+    if !json {
+        println!("  ✅ STARK proof generated and verified off-chain");
+    }
+    let results = decode_batch(&proved.public_inputs)?;
+
+    let rpc_url = deployer.get_info().rpc_url.parse()?;
+    let wallet = EthereumWallet::from(deployer.get_signer());
+    let provider = ProviderBuilder::new().wallet(wallet).connect_http(rpc_url);
+
+    let key: PinnedVerifyingKey::VerifyingKey =
+        reencode(&VerifyingKey::of(&proved.verifier_input))?;
+    let registry = BatchResultRegistry::deploy(
+        &provider,
+        deployment_result.verifier_address,
+        key,
+        results[0].input.len() as u32,
+        results[0].output.len() as u32,
+    )
+    .await?;
+    info!(address = %registry.address(), "BatchResultRegistry deployed");
+
+    let input = proved.verifier_input;
+    let proof: ProofParser::Proof = reencode(&input.proof)?;
+    let params: STWOVerifier::VerificationParams = reencode(&input.verificationParams)?;
+
+    // Claiming another output for any instance changes the digest
+    let mut tampered = proved.public_inputs.clone();
+    tampered[BATCH_HEADER_LEN + results[0].input.len()] += 1;
+    let rejected = registry
+        .proveBatch(
+            tampered,
+            proof.clone(),
+            params.clone(),
+            input.treeRoots.clone(),
+            input.treeColumnLogSizes.clone(),
+            input.digest,
+        )
+        .call()
+        .await
+        .is_err();
+    if !json {
+        println!("\n🔒 Proof rejected for a tampered output: {}", rejected);
+    }
+
+    let receipt = registry
+        .proveBatch(
+            proved.public_inputs,
+            proof,
+            params,
+            input.treeRoots,
+            input.treeColumnLogSizes,
+            input.digest,
+        )
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+    let mut fields = json!({
+        "registry_address": registry.address(),
+        "instances": results.len(),
+        "steps": n_steps,
+        "tampered_output_rejected": rejected,
+        "proving_ms": proving_time.as_millis() as u64,
+    });
+    let verification = json!({
+        "gas_used": receipt.gas_used,
+        "tx_hash": receipt.transaction_hash,
+        "passed": receipt.status(),
+    });
+    if !receipt.status() {
+        if json {
+            print_json(&example_result(&deployment_result, fields, &verification)?)?;
+        }
+        return Err("Batch proof was rejected".into());
+    }
+    let batch = registry.batchCount().call().await?;
+    if !json {
+        println!(
+            "🎯 Batch {} PROVEN with {} instances!",
+            batch,
+            results.len()
+        );
+        println!("\n🔎 Looking up instance results");
+    }
+
+    let mut lookup_records = Vec::new();
+    for index in lookups {
+        let Some(result) = results.get(index) else {
+            if !json {
+                println!("   #{}: not in the batch", index);
+            }
+            lookup_records.push(json!({ "index": index, "in_batch": false }));
+            continue;
+        };
+        let record = registry.resultOf(result.input.clone()).call().await?;
+        if !json {
+            println!(
+                "   #{} {:?} -> {:?} (proven: {}, batch {})",
+                index, result.input, record.output, record.proven, record.batch
+            );
+        }
+        lookup_records.push(json!({
+            "index": index,
+            "in_batch": true,
+            "input": result.input,
+            "output": record.output,
+            "proven": record.proven,
+            "batch": record.batch.to::<u64>(),
+        }));
+    }
+
+    let missing = vec![n_instances as u32, n_instances as u32 + 1];
+    let record = registry.resultOf(missing.clone()).call().await?;
+
+    if json {
+        fields["batch"] = json!(batch.to::<u64>());
+        fields["lookups"] = json!(lookup_records);
+        fields["outside_batch_proven"] = json!(record.proven);
+        print_json(&example_result(&deployment_result, fields, &verification)?)?;
+        return Ok(());
+    }
+
+    println!(
+        "   {:?} outside the batch (proven: {})",
+        missing, record.proven
+    );
+
+    println!("\n📏 Benchmark");
+    println!("   Instances: {}", results.len());
+    println!("   Proving time: {:.2?}", proving_time);
+    println!("   Gas used: {}", receipt.gas_used);
+    println!(
+        "   Gas per instance: {}",
+        receipt.gas_used / results.len() as u64
+    );
+
+    println!("\n🎉 Batched Fibonacci example completed!");
+    Ok(())
+}
+
+/// Convert between the `contracts` bindings and this crate's bindings of the
+/// same Solidity type
+fn reencode<T, U>(value: &T) -> Result<U>
+where
+    T: SolValue,
+    U: SolValue + From<<U::SolType as SolType>::RustType>,
+{
+    Ok(U::abi_decode(&value.abi_encode())?)
+}
+
+/// Deploy the STWOVerifier contract on `network`, or on a local Anvil with a
+/// block gas limit high enough for large batches
+async fn deploy_verifier(
+    network: Option<&String>,
+) -> Result<(DeploymentResult, STWOVerifierDeployer)> {
+    let anvil_config = AnvilConfig {
+        gas_limit: "1000000000".to_string(),
+        fork_url: None,
+        ..AnvilConfig::default()
+    };
+
+    let deployer = STWOVerifierDeployer::for_network(network.map(String::as_str), anvil_config)?;
+    let result = deployer.deploy_or_attach().await?;
+
+    info!(
+        address = %result.verifier_address,
+        chain_id = result.chain_id,
+        "STWOVerifier ready"
+    );
+
+    Ok((result, deployer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts::STWOVerifier as Verifier;
+
+    #[tokio::test]
+    async fn test_registry_binds_results_to_circuit() -> Result<(), Box<dyn std::error::Error>> {
+        let deployer = STWOVerifierDeployer::with_anvil()?;
+        let deployment = deployer.deploy().await?;
+        let wallet = EthereumWallet::from(deployer.get_signer());
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .connect_http(deployer.get_info().rpc_url.parse()?);
+
+        let circuit = BatchedFibonacciCircuit;
+        let config = SolidityProverConfig::from(circuit.default_pcs_config());
+        let proved = circuit.prove(Some("8:4"), HashFunction::Keccak, config)?;
+        let key: PinnedVerifyingKey::VerifyingKey =
+            reencode(&VerifyingKey::of(&proved.verifier_input))?;
+        let registry =
+            BatchResultRegistry::deploy(&provider, deployment.verifier_address, key, 2, 1).await?;
+
+        // Another circuit claims other outputs for the same inputs; its proof is
+        // valid on its own
+        let forged = circuit.prove(Some("8:5"), HashFunction::Keccak, config)?;
+        assert_ne!(forged.public_inputs, proved.public_inputs);
+        let verifier = Verifier::new(deployment.verifier_address, &provider);
+        let input = &forged.verifier_input;
+        let accepted = verifier
+            .verify(
+                input.proof.clone(),
+                input.verificationParams.clone(),
+                input.treeRoots.clone(),
+                input.treeColumnLogSizes.clone(),
+                input.digest,
+                input.nDraws,
+            )
+            .call()
+            .await?;
+        assert!(accepted);
+
+        // A claimed sum that does not match the recorded results
+        let mut wrong_sum = proved.verifier_input.clone();
+        let claimed = &mut wrong_sum.verificationParams.componentParams[0].claimedSum;
+        claimed.first.real = (claimed.first.real + 1) % ((1 << 31) - 1);
+
+        let cases = [
+            (forged.public_inputs, forged.verifier_input, false),
+            (proved.public_inputs.clone(), wrong_sum, false),
+            (proved.public_inputs, proved.verifier_input, true),
+        ];
+        for (public_inputs, input, expected) in cases {
+            let result = registry
+                .proveBatch(
+                    public_inputs,
+                    reencode(&input.proof)?,
+                    reencode(&input.verificationParams)?,
+                    input.treeRoots,
+                    input.treeColumnLogSizes,
+                    input.digest,
+                )
+                .call()
+                .await;
+            assert_eq!(result.is_ok(), expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use clap::{Arg, Command};
+use serde_json::json;
+use std::path::PathBuf;
+use tracing::info;
+use verifier::devnet::{Devnet, DevnetConfig, DEFAULT_ADDRESSES_PATH};
+use verifier::logging::{self, json_arg, print_json};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("STWO Devnet")
+        .version("1.0")
+        .about("Starts Anvil with the STWO Verifier and the example consumer contracts deployed")
+        .arg(
+            Arg::new("port")
+                .long("port")
+                .help("Anvil port")
+                .value_name("PORT")
+                .default_value("8545")
+                .value_parser(clap::value_parser!(u16)),
+        )
+        .arg(
+            Arg::new("addresses")
+                .long("addresses")
+                .help("JSON file the contract addresses are written to")
+                .value_name("PATH")
+                .default_value(DEFAULT_ADDRESSES_PATH),
+        )
+        .arg(
+            Arg::new("state")
+                .long("state")
+                .help("Anvil state snapshot, loaded on start when present and saved on Ctrl+C")
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::new("fresh")
+                .long("fresh")
+                .help("Ignore an existing snapshot and deploy again")
+                .action(clap::ArgAction::SetTrue)
+                .requires("state"),
+        )
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    let config = DevnetConfig {
+        port: matches.get_one::<u16>("port").copied(),
+        addresses: PathBuf::from(matches.get_one::<String>("addresses").unwrap()),
+        state: matches.get_one::<String>("state").map(PathBuf::from),
+    };
+    if let Some(state) = config.state.as_ref().filter(|_| matches.get_flag("fresh")) {
+        if state.exists() {
+            std::fs::remove_file(state)?;
+        }
+    }
+
+    info!("Proving the example circuits for the consumers' verifying keys");
+    let consumers = circuits::devnet::example_consumers()?;
+    info!("Starting STWO devnet");
+    let devnet = Devnet::start(&config, &consumers).await?;
+
+    let addresses = &devnet.addresses;
+    let funded = devnet.deployer().get_signer().address();
+    if json {
+        // Printed once the devnet is ready; the process keeps running
+        print_json(&json!({ "addresses": addresses, "funded_account": funded }))?;
+    } else {
+        println!("\n🎉 Devnet ready!");
+        println!("📋 Results:");
+        println!("   RPC URL: {}", addresses.rpc_url);
+        println!("   Chain ID: {}", addresses.chain_id);
+        println!("   STWOVerifier: {:?}", addresses.verifier);
+        for (library, address) in &addresses.libraries {
+            println!("   {}: {:?}", library, address);
+        }
+        for (consumer, address) in &addresses.consumers {
+            println!("   {}: {:?}", consumer, address);
+        }
+        println!("   Funded account: {:?}", funded);
+    }
+
+    devnet.run_until_shutdown().await?;
+    Ok(())
+}
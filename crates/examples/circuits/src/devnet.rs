@@ -0,0 +1,126 @@
+//! Consumer contracts the devnet deploys, each pinning the verifying key of an
+//! example circuit proven on its default input with Keccak.
+
+use alloy::sol_types::SolValue;
+use contracts::batch::decode_batch;
+use contracts::circuit::{DynCircuit, ProvedCircuit};
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use contracts::verifying_key::VerifyingKey;
+use verifier::artifact::{BATCH_RESULT_REGISTRY, MERKLE_ROOT_CONSUMER, TINY_VM_CONSUMER};
+use verifier::devnet::DevnetConsumer;
+
+use batched_fibonacci::batched_circuit::BatchedFibonacciCircuit;
+use merkle_membership::merkle_circuit::{MerkleMembershipCircuit, DIGEST_SIZE};
+use tiny_vm::vm_circuit::TinyVmCircuit;
+
+/// Proof of `circuit` for its default input, as the devnet consumers expect it
+pub fn prove_default(
+    circuit: &dyn DynCircuit,
+) -> Result<ProvedCircuit, Box<dyn std::error::Error>> {
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+    circuit.prove(None, HashFunction::Keccak, config)
+}
+
+/// `MerkleRootConsumer` holding the root of the default tree,
+/// `BatchResultRegistry` for the default batch size and `TinyVmConsumer` for
+/// the default program
+pub fn example_consumers() -> Result<Vec<DevnetConsumer>, Box<dyn std::error::Error>> {
+    let merkle = prove_default(&MerkleMembershipCircuit)?;
+    let merkle_key = VerifyingKey::of(&merkle.verifier_input);
+    let root: [u32; DIGEST_SIZE] = merkle.public_inputs[DIGEST_SIZE..].try_into()?;
+
+    let batched = prove_default(&BatchedFibonacciCircuit)?;
+    let batched_key = VerifyingKey::of(&batched.verifier_input);
+    let instance = decode_batch(&batched.public_inputs)?.remove(0);
+    let (input_len, output_len) = (instance.input.len() as u32, instance.output.len() as u32);
+
+    let tiny_vm_key = VerifyingKey::of(&prove_default(&TinyVmCircuit)?.verifier_input);
+
+    Ok(vec![
+        DevnetConsumer {
+            artifact: MERKLE_ROOT_CONSUMER,
+            constructor_args: Box::new(move |verifier| {
+                (verifier, root, merkle_key.clone()).abi_encode_params()
+            }),
+        },
+        DevnetConsumer {
+            artifact: BATCH_RESULT_REGISTRY,
+            constructor_args: Box::new(move |verifier| {
+                (verifier, batched_key.clone(), input_len, output_len).abi_encode_params()
+            }),
+        },
+        DevnetConsumer {
+            artifact: TINY_VM_CONSUMER,
+            constructor_args: Box::new(move |verifier| {
+                (verifier, tiny_vm_key.clone()).abi_encode_params()
+            }),
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{network::EthereumWallet, providers::ProviderBuilder, sol, sol_types::SolType};
+    use verifier::devnet::{Devnet, DevnetConfig};
+
+    sol!(
+        #[sol(rpc)]
+        MerkleRootConsumer,
+        "../../../out/MerkleRootConsumer.sol/MerkleRootConsumer.json"
+    );
+
+    fn reencode<T, U>(value: &T) -> Result<U, Box<dyn std::error::Error>>
+    where
+        T: SolValue,
+        U: SolValue + From<<U::SolType as SolType>::RustType>,
+    {
+        Ok(U::abi_decode(&value.abi_encode())?)
+    }
+
+    #[tokio::test]
+    async fn test_devnet_consumer_accepts_example_proof() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let dir = std::env::temp_dir().join("stwo_devnet_consumers");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = DevnetConfig {
+            port: None,
+            addresses: dir.join("devnet.json"),
+            state: None,
+        };
+        let devnet = Devnet::start(&config, &example_consumers()?).await?;
+        assert_eq!(devnet.addresses.consumers.len(), 3);
+
+        let wallet = EthereumWallet::from(devnet.deployer().get_signer());
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .connect_http(devnet.addresses.rpc_url.parse()?);
+        let consumer = MerkleRootConsumer::new(
+            devnet.addresses.consumers[MERKLE_ROOT_CONSUMER.name],
+            &provider,
+        );
+
+        let proved = prove_default(&MerkleMembershipCircuit)?;
+        let leaf: [u32; DIGEST_SIZE] = proved.public_inputs[..DIGEST_SIZE].try_into()?;
+        let root: [u32; DIGEST_SIZE] = proved.public_inputs[DIGEST_SIZE..].try_into()?;
+        let input = proved.verifier_input;
+        let receipt = consumer
+            .proveMembership(
+                leaf,
+                root,
+                reencode(&input.proof)?,
+                reencode(&input.verificationParams)?,
+                input.treeRoots,
+                input.treeColumnLogSizes,
+                input.digest,
+            )
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        assert!(receipt.status());
+        assert!(consumer.isMember(leaf).call().await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! Every example circuit in one [`CircuitRegistry`], so the CLI, tests and
+//! benchmarks enumerate the same set.
+
+use batched_fibonacci::batched_circuit::BatchedFibonacciCircuit;
+use contracts::circuit::CircuitRegistry;
+use fibonacci::fibonacci_circuit::FibonacciCircuit;
+use fibonacci::single_column_circuit::SingleColumnFibonacciCircuit;
+use merkle_membership::merkle_circuit::MerkleMembershipCircuit;
+use poseidon2::poseidon2_circuit::Poseidon2Circuit;
+use tiny_vm::vm_circuit::TinyVmCircuit;
+use wide_fibonacci::wide_fibonacci_circuit::WideFibonacciCircuit;
+
+pub mod devnet;
+
+/// Defines [`registry`] and [`N_EXAMPLES`] from one list of circuits
+macro_rules! examples {
+    ($($circuit:expr),+ $(,)?) => {
+        /// Number of example circuits
+        pub const N_EXAMPLES: usize = [$(stringify!($circuit)),+].len();
+
+        /// Registry holding all example circuits
+        pub fn registry() -> Result<CircuitRegistry, String> {
+            let mut registry = CircuitRegistry::new();
+            $(registry.register($circuit)?;)+
+            Ok(registry)
+        }
+    };
+}
+
+examples!(
+    FibonacciCircuit,
+    SingleColumnFibonacciCircuit,
+    BatchedFibonacciCircuit,
+    MerkleMembershipCircuit,
+    Poseidon2Circuit,
+    TinyVmCircuit,
+    WideFibonacciCircuit,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts::{hash::HashFunction, prover::SolidityProverConfig};
+
+    #[test]
+    fn test_registry_holds_every_example() {
+        let registry = registry().unwrap();
+        assert_eq!(registry.names().count(), N_EXAMPLES);
+
+        for circuit in registry.iter() {
+            let config = SolidityProverConfig::from(circuit.default_pcs_config());
+            let proof = circuit
+                .prove_unconverted(None, HashFunction::Keccak, config)
+                .unwrap_or_else(|e| panic!("{} failed to prove: {}", circuit.name(), e));
+            circuit.verify(&proof).unwrap();
+            let proved = circuit.convert(proof).unwrap();
+            assert!(!proved.verifier_input.treeRoots.is_empty());
+        }
+    }
+}
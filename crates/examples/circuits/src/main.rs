@@ -0,0 +1,127 @@
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use serde_json::json;
+use std::time::Instant;
+use verifier::deploy::{AnvilConfig, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+use verifier::submit::submit_verification;
+
+/// Prove any registered example circuit by name and verify it on-chain
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Circuit Runner")
+        .version("1.0")
+        .about("Prove any registered example circuit and verify it with STWO verifier")
+        .arg(
+            Arg::new("list")
+                .long("list")
+                .help("List the registered circuits and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("circuit")
+                .long("circuit")
+                .short('c')
+                .help("Registered circuit to prove")
+                .value_name("NAME")
+                .required_unless_present("list"),
+        )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .short('i')
+                .help("Circuit input, in the circuit's own format (default input when omitted)")
+                .value_name("INPUT"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    let registry = circuits::registry()?;
+    if matches.get_flag("list") {
+        if json {
+            print_json(&registry.names().collect::<Vec<_>>())?;
+        } else {
+            for name in registry.names() {
+                println!("{}", name);
+            }
+        }
+        return Ok(());
+    }
+
+    let circuit = registry.get(matches.get_one::<String>("circuit").unwrap())?;
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+    if !json {
+        println!("🔐 Proving {} ({:?})", circuit.name(), hash_function);
+    }
+
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+    let proving_start = Instant::now();
+    let proof = circuit.prove_unconverted(
+        matches.get_one::<String>("input").map(String::as_str),
+        hash_function,
+        config,
+    )?;
+    let proving_time = proving_start.elapsed();
+    circuit.verify(&proof)?;
+    let proved = circuit.convert(proof)?;
+    let public_inputs = proved.public_inputs.clone();
+    if !json {
+        println!("  ✅ STARK proof generated and verified off-chain");
+        println!("   Public inputs: {:?}", public_inputs);
+    }
+
+    let deployer = STWOVerifierDeployer::for_network(
+        matches.get_one::<String>("network").map(String::as_str),
+        AnvilConfig {
+            gas_limit: "1000000000".to_string(),
+            fork_url: None,
+            ..AnvilConfig::default()
+        },
+    )?;
+    let deployment = deployer.deploy_or_attach().await?;
+    let report = submit_verification(
+        deployment.verifier_address,
+        proved.verifier_input,
+        &deployer,
+        false,
+    )
+    .await?;
+
+    if json {
+        print_json(&example_result(
+            &deployment,
+            json!({
+                "circuit": circuit.name(),
+                "hash_function": format!("{:?}", hash_function),
+                "public_inputs": public_inputs,
+                "proving_ms": proving_time.as_millis() as u64,
+            }),
+            &report,
+        )?)?;
+        report.ensure_passed()?;
+        return Ok(());
+    }
+
+    println!("\n📏 Benchmark");
+    println!("   Proving time: {:.2?}", proving_time);
+    println!(
+        "   Calldata: {} bytes, {} gas",
+        report.calldata_size, report.calldata_gas
+    );
+    println!("   Gas used: {}", report.gas_used);
+    report.ensure_passed()?;
+    Ok(())
+}
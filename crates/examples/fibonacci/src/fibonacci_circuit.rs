@@ -1,16 +1,36 @@
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use num_traits::{One, Zero};
 use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
 use stwo::core::poly::circle::CanonicCoset;
 
 use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
 use stwo::prover::backend::simd::SimdBackend;
 use stwo::prover::backend::{Col, Column};
 use stwo::prover::poly::circle::CircleEvaluation;
 use stwo::prover::poly::BitReversedOrder;
-use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval};
-
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator, ORIGINAL_TRACE_IDX,
+};
+
+use crate::single_column_circuit::{gen_selector, row_position};
+
+/// Row `i` holds `(f(i), f(i + 1), f(i + 2))` for `f(0) = 0, f(1) = 1`.
+///
+/// Each row is linked to the next through mask offset 1, except the last row
+/// where the offset wraps around. The first row is pinned to `(0, 1)` and the
+/// row selected by the preprocessed `is_target` column to the claimed value, so
+/// the preprocessed root fixes `n` and the constraints fix `f(n)`.
 #[derive(Clone)]
 pub struct FibonacciEval {
     pub log_n_rows: u32,
+    /// `n`, the row whose first column holds `f(n)`
+    pub target_row: usize,
+    pub target_value: BaseField,
 }
 
 impl FrameworkEval for FibonacciEval {
@@ -23,11 +43,26 @@ impl FrameworkEval for FibonacciEval {
     }
 
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let a = eval.next_trace_mask(); // f(n-2)
-        let b = eval.next_trace_mask(); // f(n-1)
-        let c = eval.next_trace_mask(); // f(n)
+        let is_first = eval.get_preprocessed_column(is_first_id(self.log_n_rows));
+        let is_last = eval.get_preprocessed_column(is_last_id(self.log_n_rows));
+        let is_target =
+            eval.get_preprocessed_column(is_target_id(self.target_row, self.log_n_rows));
+        let [a, next_a] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1]); // f(n), f(n+1)
+        let [b, next_b] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1]); // f(n+1), f(n+2)
+        let c = eval.next_trace_mask(); // f(n+2)
+
+        eval.add_constraint(c.clone() - (a.clone() + b.clone()));
+
+        // The next row shifts by one step, away from the wrap-around
+        let not_last = E::F::one() - is_last;
+        eval.add_constraint(not_last.clone() * (next_a - b.clone()));
+        eval.add_constraint(not_last * (next_b - c));
 
-        eval.add_constraint(c - (a + b));
+        // f(0) = 0, f(1) = 1
+        eval.add_constraint(is_first.clone() * a.clone());
+        eval.add_constraint(is_first * (b - E::F::one()));
+
+        eval.add_constraint(is_target * (a - E::F::from(self.target_value)));
 
         eval
     }
@@ -35,17 +70,36 @@ impl FrameworkEval for FibonacciEval {
 
 pub type FibonacciComponent = FrameworkComponent<FibonacciEval>;
 
-/// Calculate the minimum log_size needed to compute f(target_n)
+fn is_first_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("fibonacci_is_first_{}", log_size),
+    }
+}
+
+fn is_last_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("fibonacci_is_last_{}", log_size),
+    }
+}
+
+fn is_target_id(row: usize, log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("fibonacci_is_target_{}_{}", row, log_size),
+    }
+}
+
+/// Calculate the minimum log_size needed to compute f(target_n), i.e. to
+/// hold rows `0..=target_n`, never below one SIMD vector of rows
 pub fn calculate_log_size(target_n: usize) -> u32 {
-    let min_rows = target_n.saturating_sub(1).max(1);
-    let log_size = (min_rows as f64).log2().ceil() as u32;
-    log_size.max(2)
+    (target_n + 1).next_power_of_two().ilog2().max(LOG_N_LANES)
 }
 
-/// Generate trace for fibonacci sequence
+/// Generate the preprocessed selectors and the trace for f(target_n),
+/// returning f(target_n) and the log size
 pub fn gen_fibonacci_trace(
     target_n: usize,
 ) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
     BaseField,
     u32,
@@ -57,35 +111,119 @@ pub fn gen_fibonacci_trace(
     let mut col_b = Col::<SimdBackend, BaseField>::zeros(n_rows);
     let mut col_c = Col::<SimdBackend, BaseField>::zeros(n_rows);
 
-    let mut a = BaseField::from_u32_unchecked(0);
-    let mut b = BaseField::from_u32_unchecked(1);
-    let mut target_value = BaseField::from_u32_unchecked(0);
+    let mut a = BaseField::zero();
+    let mut b = BaseField::one();
+    let mut target_value = BaseField::zero();
 
-    let compute_rows = (target_n - 1).min(n_rows);
-
-    for row in 0..compute_rows {
+    for row in 0..n_rows {
         let c = a + b;
+        let position = row_position(row, log_size);
+        col_a.set(position, a);
+        col_b.set(position, b);
+        col_c.set(position, c);
 
-        col_a.set(row, a);
-        col_b.set(row, b);
-        col_c.set(row, c);
-
-        let current_index = row + 2;
-        if current_index == target_n {
-            target_value = c;
+        if row == target_n {
+            target_value = a;
         }
 
         a = b;
         b = c;
     }
 
-    let domain = CanonicCoset::new(log_size).circle_domain();
+    // Same order as the get_preprocessed_column calls in evaluate
+    let preprocessed = vec![
+        gen_selector(0, log_size),
+        gen_selector(n_rows - 1, log_size),
+        gen_selector(target_n, log_size),
+    ];
 
+    let domain = CanonicCoset::new(log_size).circle_domain();
     let trace = vec![
         CircleEvaluation::new(domain, col_a),
         CircleEvaluation::new(domain, col_b),
         CircleEvaluation::new(domain, col_c),
     ];
 
-    (trace, target_value, log_size)
+    (preprocessed, trace, target_value, log_size)
+}
+
+/// Proves f(n) for the `n` given as input
+pub struct FibonacciCircuit;
+
+pub struct FibonacciMetadata {
+    pub log_size: u32,
+    pub target_n: usize,
+    pub target_value: BaseField,
+}
+
+impl SolidityCircuit for FibonacciCircuit {
+    type Eval = FibonacciEval;
+    type Metadata = FibonacciMetadata;
+    type Input = usize;
+
+    fn name(&self) -> &'static str {
+        "fibonacci"
+    }
+
+    fn default_input(&self) -> usize {
+        10 // f(10) = 55
+    }
+
+    fn components(&self, metadata: &FibonacciMetadata) -> Vec<FibonacciComponent> {
+        vec![FibonacciComponent::new(
+            &mut TraceLocationAllocator::default(),
+            FibonacciEval {
+                log_n_rows: metadata.log_size,
+                target_row: metadata.target_n,
+                target_value: metadata.target_value,
+            },
+            SecureField::zero(),
+        )]
+    }
+
+    fn generate_trace(&self, target_n: &usize) -> CircuitTrace<FibonacciMetadata> {
+        let (preprocessed, trace, target_value, log_size) = gen_fibonacci_trace(*target_n);
+        CircuitTrace {
+            metadata: FibonacciMetadata {
+                log_size,
+                target_n: *target_n,
+                target_value,
+            },
+            preprocessed,
+            trace,
+            interaction: vec![],
+        }
+    }
+
+    /// f(n), pinned by the boundary constraint on the `is_target` row
+    fn public_inputs(&self, _target_n: &usize, metadata: &FibonacciMetadata) -> Vec<u32> {
+        vec![metadata.target_value.0]
+    }
+
+    fn binds_public_inputs(&self) -> bool {
+        true
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_fibonacci_trace_target() {
+        for (target_n, expected) in [(0, 0), (1, 1), (10, 55), (16, 987)] {
+            let (preprocessed, trace, target_value, log_size) = gen_fibonacci_trace(target_n);
+            assert_eq!(target_value, BaseField::from_u32_unchecked(expected));
+            assert!(target_n < 1 << log_size);
+            assert_eq!(preprocessed.len(), 3);
+            assert_eq!(trace.len(), 3);
+        }
+    }
 }
@@ -0,0 +1,2 @@
+pub mod fibonacci_circuit;
+pub mod single_column_circuit;
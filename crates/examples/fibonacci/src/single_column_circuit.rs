@@ -0,0 +1,211 @@
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use num_traits::{One, Zero};
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::utils::{bit_reverse_index, coset_index_to_circle_domain_index};
+use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator, ORIGINAL_TRACE_IDX,
+};
+
+/// Fibonacci in one column: row `r` holds F(r + 1), so every transition reads
+/// the previous, current and next row through mask offsets `[-1, 0, 1]`.
+///
+/// Rows are cyclic. The transition is disabled on the first and last row, where
+/// the -1 / +1 offsets wrap around, and the output is read at the first row
+/// through the -1 offset, i.e. from the last row.
+#[derive(Clone)]
+pub struct SingleColumnFibonacciEval {
+    pub log_n_rows: u32,
+    /// F(2^log_n_rows), the value in the last row
+    pub output: BaseField,
+}
+
+impl FrameworkEval for SingleColumnFibonacciEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_rows + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let is_first = eval.get_preprocessed_column(is_first_id(self.log_n_rows));
+        let is_last = eval.get_preprocessed_column(is_last_id(self.log_n_rows));
+        let [prev, curr, next] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [-1, 0, 1]);
+
+        // F(r + 2) = F(r + 1) + F(r) away from the wrap-around rows
+        eval.add_constraint(
+            (E::F::one() - is_first.clone() - is_last)
+                * (next.clone() - curr.clone() - prev.clone()),
+        );
+
+        // F(1) = F(2) = 1
+        eval.add_constraint(is_first.clone() * (curr - E::F::one()));
+        eval.add_constraint(is_first.clone() * (next - E::F::one()));
+
+        // Row -1 of the first row is the last row
+        eval.add_constraint(is_first * (prev - E::F::from(self.output)));
+
+        eval
+    }
+}
+
+pub type SingleColumnFibonacciComponent = FrameworkComponent<SingleColumnFibonacciEval>;
+
+fn is_first_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("is_first_{}", log_size),
+    }
+}
+
+fn is_last_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("is_last_{}", log_size),
+    }
+}
+
+/// Bit-reversed circle domain position of the `row`-th point of the trace coset
+pub(crate) fn row_position(row: usize, log_size: u32) -> usize {
+    bit_reverse_index(coset_index_to_circle_domain_index(row, log_size), log_size)
+}
+
+/// Column that is one at `row` and zero elsewhere
+pub(crate) fn gen_selector(
+    row: usize,
+    log_size: u32,
+) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+    let mut col = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+    col.set(row_position(row, log_size), BaseField::one());
+    CircleEvaluation::new(CanonicCoset::new(log_size).circle_domain(), col)
+}
+
+/// Rows needed for a sequence of `sequence_length` terms: a power of two,
+/// never below one SIMD vector
+pub fn calculate_log_size(sequence_length: usize) -> u32 {
+    sequence_length
+        .max(1)
+        .next_power_of_two()
+        .ilog2()
+        .max(LOG_N_LANES)
+}
+
+/// Generate the preprocessed selectors and the sequence column
+pub fn gen_trace(
+    log_size: u32,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    BaseField,
+) {
+    let n_rows = 1 << log_size;
+    let mut col = Col::<SimdBackend, BaseField>::zeros(n_rows);
+
+    let (mut a, mut b) = (BaseField::one(), BaseField::one());
+    for row in 0..n_rows {
+        col.set(row_position(row, log_size), a);
+        (a, b) = (b, a + b);
+    }
+    let output = col.at(row_position(n_rows - 1, log_size));
+
+    // Same order as the get_preprocessed_column calls in evaluate
+    let preprocessed = vec![
+        gen_selector(0, log_size),
+        gen_selector(n_rows - 1, log_size),
+    ];
+    let trace = vec![CircleEvaluation::new(
+        CanonicCoset::new(log_size).circle_domain(),
+        col,
+    )];
+
+    (preprocessed, trace, output)
+}
+
+/// Proves F(N) for N the sequence length rounded up to a power of two
+pub struct SingleColumnFibonacciCircuit;
+
+pub struct SingleColumnFibonacciMetadata {
+    pub log_size: u32,
+    pub output: BaseField,
+}
+
+impl SolidityCircuit for SingleColumnFibonacciCircuit {
+    type Eval = SingleColumnFibonacciEval;
+    type Metadata = SingleColumnFibonacciMetadata;
+    type Input = usize;
+
+    fn name(&self) -> &'static str {
+        "fibonacci-column"
+    }
+
+    fn default_input(&self) -> usize {
+        16
+    }
+
+    fn components(
+        &self,
+        metadata: &SingleColumnFibonacciMetadata,
+    ) -> Vec<SingleColumnFibonacciComponent> {
+        vec![SingleColumnFibonacciComponent::new(
+            &mut TraceLocationAllocator::default(),
+            SingleColumnFibonacciEval {
+                log_n_rows: metadata.log_size,
+                output: metadata.output,
+            },
+            SecureField::zero(),
+        )]
+    }
+
+    fn generate_trace(
+        &self,
+        sequence_length: &usize,
+    ) -> CircuitTrace<SingleColumnFibonacciMetadata> {
+        let log_size = calculate_log_size(*sequence_length);
+        let (preprocessed, trace, output) = gen_trace(log_size);
+        CircuitTrace {
+            metadata: SingleColumnFibonacciMetadata { log_size, output },
+            preprocessed,
+            trace,
+            interaction: vec![],
+        }
+    }
+
+    fn public_inputs(
+        &self,
+        _sequence_length: &usize,
+        metadata: &SingleColumnFibonacciMetadata,
+    ) -> Vec<u32> {
+        vec![metadata.output.0]
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_trace_output() {
+        // F(16) = 987
+        let (preprocessed, trace, output) = gen_trace(4);
+        assert_eq!(preprocessed.len(), 2);
+        assert_eq!(trace.len(), 1);
+        assert_eq!(output, BaseField::from_u32_unchecked(987));
+    }
+}
@@ -0,0 +1 @@
+pub mod merkle_circuit;
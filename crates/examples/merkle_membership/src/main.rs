@@ -0,0 +1,302 @@
+use alloy::{
+    network::EthereumWallet,
+    providers::ProviderBuilder,
+    sol,
+    sol_types::{SolType, SolValue},
+};
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::circuit::DynCircuit;
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use contracts::verifying_key::VerifyingKey;
+use serde_json::json;
+use std::time::Instant;
+use tracing::info;
+use verifier::deploy::{AnvilConfig, DeploymentResult, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+
+use merkle_membership::merkle_circuit::{MerkleMembershipCircuit, DIGEST_SIZE};
+
+sol!(
+    #[sol(rpc)]
+    MerkleRootConsumer,
+    "../../../out/MerkleRootConsumer.sol/MerkleRootConsumer.json"
+);
+
+/// Merkle membership STARK proof verified through a root-checking consumer contract
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Merkle Membership STARK Verifier")
+        .version("1.0")
+        .about(
+            "Example proving Merkle membership with a Poseidon2-M31 hash and verifying it against a stored root",
+        )
+        .arg(
+            Arg::new("depth")
+                .long("depth")
+                .short('d')
+                .help("Tree depth (1 to 20)")
+                .value_name("DEPTH")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("8"),
+        )
+        .arg(
+            Arg::new("leaf-index")
+                .long("leaf-index")
+                .short('i')
+                .help("Index of the leaf to prove")
+                .value_name("INDEX")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if !json {
+        println!("🌳 Merkle Membership STARK Verifier Example");
+        println!("===========================================");
+    }
+
+    let depth = *matches.get_one::<u32>("depth").unwrap();
+    let leaf_index = *matches.get_one::<usize>("leaf-index").unwrap();
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+
+    let (deployment_result, deployer) =
+        deploy_verifier(matches.get_one::<String>("network")).await?;
+
+    if !json {
+        println!("\n📊 Proving leaf {} of a depth-{} tree", leaf_index, depth);
+        println!("   Hash function: {:?}", hash_function);
+    }
+
+    let circuit = MerkleMembershipCircuit;
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+
+    let proving_start = Instant::now();
+    let proved = circuit.prove(
+        Some(&format!("{}:{}", depth, leaf_index)),
+        hash_function,
+        config,
+    )?;
+    let proving_time = proving_start.elapsed();
+
+    let leaf: [u32; DIGEST_SIZE] = proved.public_inputs[..DIGEST_SIZE].try_into()?;
+    let root: [u32; DIGEST_SIZE] = proved.public_inputs[DIGEST_SIZE..].try_into()?;
+    if !json {
+        println!("  ✅ STARK proof generated and verified off-chain");
+        println!("   Leaf: {:?}", leaf);
+        println!("   Root: {:?}", root);
+    }
+
+    let rpc_url = deployer.get_info().rpc_url.parse()?;
+    let wallet = EthereumWallet::from(deployer.get_signer());
+    let provider = ProviderBuilder::new().wallet(wallet).connect_http(rpc_url);
+
+    let key: PinnedVerifyingKey::VerifyingKey =
+        reencode(&VerifyingKey::of(&proved.verifier_input))?;
+    let consumer =
+        MerkleRootConsumer::deploy(&provider, deployment_result.verifier_address, root, key)
+            .await?;
+    info!(address = %consumer.address(), "MerkleRootConsumer deployed");
+
+    let input = proved.verifier_input;
+    let proof: ProofParser::Proof = reencode(&input.proof)?;
+    let params: STWOVerifier::VerificationParams = reencode(&input.verificationParams)?;
+
+    // A proof only resumes from the digest of the public inputs it was made for
+    let mut other_leaf = leaf;
+    other_leaf[0] ^= 1;
+    let rejected = consumer
+        .proveMembership(
+            other_leaf,
+            root,
+            proof.clone(),
+            params.clone(),
+            input.treeRoots.clone(),
+            input.treeColumnLogSizes.clone(),
+            input.digest,
+        )
+        .call()
+        .await
+        .is_err();
+    if !json {
+        println!("\n🔒 Proof rejected for a different leaf: {}", rejected);
+    }
+
+    let receipt = consumer
+        .proveMembership(
+            leaf,
+            root,
+            proof,
+            params,
+            input.treeRoots,
+            input.treeColumnLogSizes,
+            input.digest,
+        )
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+    let is_member = consumer.isMember(leaf).call().await?;
+    let passed = receipt.status() && is_member;
+
+    if json {
+        print_json(&example_result(
+            &deployment_result,
+            json!({
+                "consumer_address": consumer.address(),
+                "depth": depth,
+                "leaf_index": leaf_index,
+                "leaf": leaf,
+                "root": root,
+                "other_leaf_rejected": rejected,
+                "proving_ms": proving_time.as_millis() as u64,
+            }),
+            &json!({
+                "gas_used": receipt.gas_used,
+                "tx_hash": receipt.transaction_hash,
+                "passed": passed,
+            }),
+        )?)?;
+    }
+    if !passed {
+        return Err("Membership proof was rejected".into());
+    }
+    if json {
+        return Ok(());
+    }
+
+    println!("🎯 Membership PROVEN against the stored root!");
+    println!("\n📏 Benchmark");
+    println!("   Depth: {}", depth);
+    println!("   Proving time: {:.2?}", proving_time);
+    println!("   Gas used: {}", receipt.gas_used);
+
+    println!("\n🎉 Merkle membership example completed!");
+    Ok(())
+}
+
+/// Convert between the `contracts` bindings and this crate's bindings of the
+/// same Solidity type
+fn reencode<T, U>(value: &T) -> Result<U>
+where
+    T: SolValue,
+    U: SolValue + From<<U::SolType as SolType>::RustType>,
+{
+    Ok(U::abi_decode(&value.abi_encode())?)
+}
+
+/// Deploy the STWOVerifier contract on `network`, or on a local Anvil with a
+/// block gas limit
+/// high enough for hash-heavy proofs
+async fn deploy_verifier(
+    network: Option<&String>,
+) -> Result<(DeploymentResult, STWOVerifierDeployer)> {
+    let anvil_config = AnvilConfig {
+        gas_limit: "1000000000".to_string(),
+        fork_url: None,
+        ..AnvilConfig::default()
+    };
+
+    let deployer = STWOVerifierDeployer::for_network(network.map(String::as_str), anvil_config)?;
+    let result = deployer.deploy_or_attach().await?;
+
+    info!(
+        address = %result.verifier_address,
+        chain_id = result.chain_id,
+        "STWOVerifier ready"
+    );
+
+    Ok((result, deployer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts::circuit::SolidityCircuit;
+    use contracts::hash::Keccak;
+    use contracts::prover::prove_for_solidity_with_public_inputs;
+    use contracts::verifying_key::verification_params_hash;
+    use contracts::STWOVerifier as Verifier;
+    use poseidon2::poseidon2_circuit::Poseidon2Circuit;
+
+    #[tokio::test]
+    async fn test_other_circuit_rejected() -> Result<(), Box<dyn std::error::Error>> {
+        let deployer = STWOVerifierDeployer::with_anvil()?;
+        let deployment = deployer.deploy().await?;
+        let wallet = EthereumWallet::from(deployer.get_signer());
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .connect_http(deployer.get_info().rpc_url.parse()?);
+
+        let circuit = MerkleMembershipCircuit;
+        let config = SolidityProverConfig::from(circuit.default_pcs_config());
+        let proved = circuit.prove(Some("2:1"), HashFunction::Keccak, config)?;
+        let leaf: [u32; DIGEST_SIZE] = proved.public_inputs[..DIGEST_SIZE].try_into()?;
+        let root: [u32; DIGEST_SIZE] = proved.public_inputs[DIGEST_SIZE..].try_into()?;
+        let input = proved.verifier_input;
+
+        let key: PinnedVerifyingKey::VerifyingKey = reencode(&VerifyingKey::of(&input))?;
+        let consumer =
+            MerkleRootConsumer::deploy(&provider, deployment.verifier_address, root, key).await?;
+        let params: STWOVerifier::VerificationParams = reencode(&input.verificationParams)?;
+        assert_eq!(
+            consumer.verificationParamsHash(params).call().await?,
+            verification_params_hash(&input.verificationParams)
+        );
+
+        // A valid proof of another AIR that mixes the same leaf and root
+        let other = Poseidon2Circuit;
+        let trace = other.generate_trace(&other.default_input());
+        let forged = prove_for_solidity_with_public_inputs::<Keccak, _>(
+            other.components(&trace.metadata),
+            &proved.public_inputs,
+            trace.preprocessed,
+            trace.trace,
+            trace.interaction,
+            SolidityProverConfig::from(other.default_pcs_config()),
+        )?;
+        let verifier = Verifier::new(deployment.verifier_address, &provider);
+        let accepted = verifier
+            .verify(
+                forged.proof.clone(),
+                forged.verificationParams.clone(),
+                forged.treeRoots.clone(),
+                forged.treeColumnLogSizes.clone(),
+                forged.digest,
+                forged.nDraws,
+            )
+            .call()
+            .await?;
+        assert!(accepted);
+
+        for (candidate, expected) in [(forged, false), (input, true)] {
+            let result = consumer
+                .proveMembership(
+                    leaf,
+                    root,
+                    reencode(&candidate.proof)?,
+                    reencode(&candidate.verificationParams)?,
+                    candidate.treeRoots,
+                    candidate.treeColumnLogSizes,
+                    candidate.digest,
+                )
+                .call()
+                .await;
+            assert_eq!(result.is_ok(), expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,448 @@
+use std::str::FromStr;
+
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use num_traits::{One, Zero};
+use poseidon2::poseidon2_circuit::{
+    eval_permutation, gen_round_constant_columns, permute, permute_recording,
+    round_constant_columns, LOG_EXPAND, N_COLUMNS as N_PERMUTATION_COLUMNS, N_STATE,
+};
+use stwo::core::fields::m31::{BaseField, P};
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::utils::{bit_reverse_index, coset_index_to_circle_domain_index};
+use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator, ORIGINAL_TRACE_IDX,
+};
+
+/// A node is the first half of a Poseidon2 state: 8 M31 elements, ~248 bits
+pub const DIGEST_SIZE: usize = N_STATE / 2;
+
+/// Direction bit, node, sibling, then the permutation of the ordered pair
+pub const N_COLUMNS: usize = 1 + 2 * DIGEST_SIZE + N_PERMUTATION_COLUMNS;
+
+/// Deepest tree the example builds in full
+pub const MAX_DEPTH: u32 = 20;
+
+pub type Digest = [BaseField; DIGEST_SIZE];
+
+/// Parent of `left` and `right`: the Poseidon2-M31 permutation in compression mode, truncated to
+/// the first half of the state
+pub fn compress(left: &Digest, right: &Digest) -> Digest {
+    let output = permute(permutation_input(left, right));
+    std::array::from_fn(|i| output[i])
+}
+
+fn permutation_input(left: &Digest, right: &Digest) -> [BaseField; N_STATE] {
+    std::array::from_fn(|i| {
+        if i < DIGEST_SIZE {
+            left[i]
+        } else {
+            right[i - DIGEST_SIZE]
+        }
+    })
+}
+
+/// Binary Merkle tree over a power-of-two number of leaves
+pub struct MerkleTree {
+    /// `layers[0]` holds the leaves, the last layer the root
+    layers: Vec<Vec<Digest>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<Digest>) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "Leaf count must be a power of two"
+        );
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| compress(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn depth(&self) -> u32 {
+        (self.layers.len() - 1) as u32
+    }
+
+    pub fn root(&self) -> Digest {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// Authentication path of leaf `index`, from the leaf level up
+    pub fn path(&self, index: usize) -> MerklePath {
+        let siblings = self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(level, layer)| layer[(index >> level) ^ 1])
+            .collect();
+        MerklePath {
+            leaf: self.layers[0][index],
+            index,
+            siblings,
+        }
+    }
+}
+
+/// Leaf plus the siblings needed to recompute the root
+#[derive(Debug, Clone)]
+pub struct MerklePath {
+    pub leaf: Digest,
+    pub index: usize,
+    pub siblings: Vec<Digest>,
+}
+
+impl MerklePath {
+    /// Whether the node at `level` is a right child
+    fn is_right(&self, level: usize) -> bool {
+        (self.index >> level) & 1 == 1
+    }
+
+    pub fn root(&self) -> Digest {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(self.leaf, |node, (level, sibling)| {
+                if self.is_right(level) {
+                    compress(sibling, &node)
+                } else {
+                    compress(&node, sibling)
+                }
+            })
+    }
+}
+
+/// Deterministic leaf `index` of the example tree
+pub fn example_leaf(index: usize) -> Digest {
+    std::array::from_fn(|i| {
+        BaseField::from_u32_unchecked(((index * DIGEST_SIZE + i + 1) as u64 % P as u64) as u32)
+    })
+}
+
+/// One row per tree level. Row `r` orders the level-`r` node and its sibling
+/// by the direction bit and hashes them; the output must be the node of row
+/// `r + 1`, read through mask offset 1.
+///
+/// Rows past the depth hash zeros and are only checked as permutations.
+#[derive(Clone)]
+pub struct MerkleMembershipEval {
+    pub log_n_rows: u32,
+    pub depth: u32,
+    pub leaf: Digest,
+    pub root: Digest,
+}
+
+impl FrameworkEval for MerkleMembershipEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_rows + LOG_EXPAND
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        // Same allocation order as gen_trace's preprocessed columns
+        let constants = round_constant_columns(&mut eval);
+        let is_first = eval.get_preprocessed_column(is_first_id(self.log_n_rows));
+        let is_chain = eval.get_preprocessed_column(is_chain_id(self.depth, self.log_n_rows));
+        let is_root = eval.get_preprocessed_column(is_root_id(self.depth, self.log_n_rows));
+
+        let direction = eval.next_trace_mask();
+        let [node, next_node]: [[E::F; DIGEST_SIZE]; 2] = {
+            let masks: [[E::F; 2]; DIGEST_SIZE] =
+                std::array::from_fn(|_| eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1]));
+            [
+                std::array::from_fn(|i| masks[i][0].clone()),
+                std::array::from_fn(|i| masks[i][1].clone()),
+            ]
+        };
+        let sibling: [E::F; DIGEST_SIZE] = std::array::from_fn(|_| eval.next_trace_mask());
+        let input: [E::F; N_STATE] = std::array::from_fn(|_| eval.next_trace_mask());
+
+        eval.add_constraint(direction.clone() * (direction.clone() - E::F::one()));
+
+        // (left, right) = direction ? (sibling, node) : (node, sibling)
+        for i in 0..DIGEST_SIZE {
+            let swap = direction.clone() * (sibling[i].clone() - node[i].clone());
+            eval.add_constraint(input[i].clone() - node[i].clone() - swap.clone());
+            eval.add_constraint(input[DIGEST_SIZE + i].clone() - sibling[i].clone() + swap);
+        }
+
+        let output = eval_permutation(&mut eval, constants, input);
+
+        for i in 0..DIGEST_SIZE {
+            eval.add_constraint(is_chain.clone() * (next_node[i].clone() - output[i].clone()));
+            eval.add_constraint(is_first.clone() * (node[i].clone() - E::F::from(self.leaf[i])));
+            eval.add_constraint(is_root.clone() * (output[i].clone() - E::F::from(self.root[i])));
+        }
+
+        eval
+    }
+}
+
+pub type MerkleMembershipComponent = FrameworkComponent<MerkleMembershipEval>;
+
+fn is_first_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("is_first_{}", log_size),
+    }
+}
+
+/// Rows whose output feeds the next row: `0..depth - 1`
+fn is_chain_id(depth: u32, log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("merkle_is_chain_{}_{}", depth, log_size),
+    }
+}
+
+/// The row producing the root: `depth - 1`
+fn is_root_id(depth: u32, log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("merkle_is_root_{}_{}", depth, log_size),
+    }
+}
+
+/// Bit-reversed circle domain position of the `row`-th point of the trace coset
+fn row_position(row: usize, log_size: u32) -> usize {
+    bit_reverse_index(coset_index_to_circle_domain_index(row, log_size), log_size)
+}
+
+/// Column that is one on `rows` and zero elsewhere
+fn gen_selector(
+    rows: std::ops::Range<usize>,
+    log_size: u32,
+) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+    let mut col = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+    for row in rows {
+        col.set(row_position(row, log_size), BaseField::one());
+    }
+    CircleEvaluation::new(CanonicCoset::new(log_size).circle_domain(), col)
+}
+
+/// Rows for a path of `depth` levels: a power of two, never below one SIMD vector
+pub fn calculate_log_size(depth: u32) -> u32 {
+    (depth.max(1) as usize)
+        .next_power_of_two()
+        .ilog2()
+        .max(LOG_N_LANES)
+}
+
+/// Generate the preprocessed columns and the path trace
+pub fn gen_trace(
+    path: &MerklePath,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) {
+    let depth = path.siblings.len();
+    let log_size = calculate_log_size(depth as u32);
+    let n_rows = 1 << log_size;
+
+    let mut preprocessed = gen_round_constant_columns(log_size);
+    preprocessed.extend([
+        gen_selector(0..1, log_size),
+        gen_selector(0..depth - 1, log_size),
+        gen_selector(depth - 1..depth, log_size),
+    ]);
+
+    let mut cols: Vec<Col<SimdBackend, BaseField>> = (0..N_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(n_rows))
+        .collect();
+    let mut node = path.leaf;
+    for row in 0..n_rows {
+        let position = row_position(row, log_size);
+        let (direction, sibling) = match path.siblings.get(row) {
+            Some(sibling) => (path.is_right(row), *sibling),
+            None => (false, [BaseField::zero(); DIGEST_SIZE]),
+        };
+        if row >= depth {
+            node = [BaseField::zero(); DIGEST_SIZE];
+        }
+        let (left, right) = if direction {
+            (&sibling, &node)
+        } else {
+            (&node, &sibling)
+        };
+
+        let mut values = vec![BaseField::from(direction as u32)];
+        values.extend(node);
+        values.extend(sibling);
+        let output = permute_recording(permutation_input(left, right), |value| values.push(value));
+        for (col, value) in cols.iter_mut().zip(values) {
+            col.set(position, value);
+        }
+        node = std::array::from_fn(|i| output[i]);
+    }
+    let trace = cols
+        .into_iter()
+        .map(|col| CircleEvaluation::new(CanonicCoset::new(log_size).circle_domain(), col))
+        .collect();
+
+    (preprocessed, trace)
+}
+
+/// Which leaf of the example tree to prove, parsed from `DEPTH[:INDEX]`
+#[derive(Debug, Clone, Copy)]
+pub struct MembershipInput {
+    pub depth: u32,
+    pub leaf_index: usize,
+}
+
+impl FromStr for MembershipInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (depth, leaf_index) = s.split_once(':').unwrap_or((s, "0"));
+        let depth: u32 = depth
+            .parse()
+            .map_err(|e| format!("Invalid depth {}: {}", depth, e))?;
+        let leaf_index: usize = leaf_index
+            .parse()
+            .map_err(|e| format!("Invalid leaf index {}: {}", leaf_index, e))?;
+        if depth == 0 || depth > MAX_DEPTH {
+            return Err(format!("Depth must be between 1 and {}", MAX_DEPTH));
+        }
+        if leaf_index >= 1 << depth {
+            return Err(format!(
+                "Leaf index {} out of range for depth {}",
+                leaf_index, depth
+            ));
+        }
+        Ok(Self { depth, leaf_index })
+    }
+}
+
+/// Proves that a leaf belongs to the example tree of the given depth. Leaf and
+/// root are public inputs, mixed into the transcript.
+pub struct MerkleMembershipCircuit;
+
+pub struct MerkleMembershipMetadata {
+    pub log_n_rows: u32,
+    pub depth: u32,
+    pub leaf: Digest,
+    pub root: Digest,
+}
+
+impl MembershipInput {
+    /// Build the example tree and the authentication path of the chosen leaf
+    pub fn path(&self) -> MerklePath {
+        let tree = MerkleTree::new((0..1 << self.depth).map(example_leaf).collect());
+        tree.path(self.leaf_index)
+    }
+}
+
+impl SolidityCircuit for MerkleMembershipCircuit {
+    type Eval = MerkleMembershipEval;
+    type Metadata = MerkleMembershipMetadata;
+    type Input = MembershipInput;
+
+    fn name(&self) -> &'static str {
+        "merkle-membership"
+    }
+
+    fn default_input(&self) -> MembershipInput {
+        MembershipInput {
+            depth: 8,
+            leaf_index: 0,
+        }
+    }
+
+    fn components(&self, metadata: &MerkleMembershipMetadata) -> Vec<MerkleMembershipComponent> {
+        vec![MerkleMembershipComponent::new(
+            &mut TraceLocationAllocator::default(),
+            MerkleMembershipEval {
+                log_n_rows: metadata.log_n_rows,
+                depth: metadata.depth,
+                leaf: metadata.leaf,
+                root: metadata.root,
+            },
+            SecureField::zero(),
+        )]
+    }
+
+    fn generate_trace(&self, input: &MembershipInput) -> CircuitTrace<MerkleMembershipMetadata> {
+        let path = input.path();
+        let (preprocessed, trace) = gen_trace(&path);
+        CircuitTrace {
+            metadata: MerkleMembershipMetadata {
+                log_n_rows: calculate_log_size(input.depth),
+                depth: input.depth,
+                leaf: path.leaf,
+                root: path.root(),
+            },
+            preprocessed,
+            trace,
+            interaction: vec![],
+        }
+    }
+
+    /// Leaf followed by root, as `MerkleRootConsumer` expects them
+    fn public_inputs(
+        &self,
+        _input: &MembershipInput,
+        metadata: &MerkleMembershipMetadata,
+    ) -> Vec<u32> {
+        metadata
+            .leaf
+            .iter()
+            .chain(&metadata.root)
+            .map(|value| value.0)
+            .collect()
+    }
+
+    fn binds_public_inputs(&self) -> bool {
+        true
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poseidon2::poseidon2_circuit::N_ROUND_CONSTANTS;
+
+    #[test]
+    fn test_trace_follows_path() {
+        let input: MembershipInput = "5:19".parse().unwrap();
+        let path = input.path();
+        assert_eq!(
+            path.root(),
+            MerkleTree::new((0..32).map(example_leaf).collect()).root()
+        );
+
+        let (preprocessed, trace) = gen_trace(&path);
+        let log_size = calculate_log_size(5);
+        assert_eq!(preprocessed.len(), N_ROUND_CONSTANTS + 3);
+        assert_eq!(trace.len(), N_COLUMNS);
+
+        // The permutation output of the last level is the root
+        let output_start = N_COLUMNS - N_STATE;
+        let root: Vec<BaseField> = trace[output_start..output_start + DIGEST_SIZE]
+            .iter()
+            .map(|col| col.values.at(row_position(4, log_size)))
+            .collect();
+        assert_eq!(root, path.root());
+    }
+}
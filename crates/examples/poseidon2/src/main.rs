@@ -0,0 +1,139 @@
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::circuit::DynCircuit;
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use poseidon2::poseidon2_circuit::Poseidon2Circuit;
+use serde_json::json;
+use std::time::Instant;
+use tracing::info;
+use verifier::deploy::{AnvilConfig, DeploymentResult, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+use verifier::submit::submit_verification;
+
+/// Poseidon2 permutation STARK proof verification example
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Poseidon2 STARK Verifier")
+        .version("1.0")
+        .about("Example proving Poseidon2-M31 permutations and verifying them with STWO verifier")
+        .arg(
+            Arg::new("log-instances")
+                .long("log-instances")
+                .short('n')
+                .help("Log2 of the number of permutations (one per trace row, at least 4)")
+                .value_name("LOG")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("4"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(
+            Arg::new("packed")
+                .long("packed")
+                .help("Submit the proof through verifyPacked using the compact calldata encoding")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if !json {
+        println!("🔐 Poseidon2 STARK Verifier Example");
+        println!("========================================");
+    }
+
+    let (deployment_result, deployer) =
+        deploy_verifier(matches.get_one::<String>("network")).await?;
+
+    let log_instances = *matches.get_one::<u32>("log-instances").unwrap();
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+    if !json {
+        println!(
+            "\n📊 Proving {} Poseidon2 permutations",
+            1u64 << log_instances
+        );
+        println!("   Hash function: {:?}", hash_function);
+    }
+
+    let circuit = Poseidon2Circuit;
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+
+    let proving_start = Instant::now();
+    let proved = circuit.prove(Some(&log_instances.to_string()), hash_function, config)?;
+    let proving_time = proving_start.elapsed();
+    let public_inputs = proved.public_inputs.clone();
+    if !json {
+        println!("  ✅ STARK proof generated and verified off-chain");
+        println!("   First permutation output: {:?}", public_inputs);
+    }
+
+    let report = submit_verification(
+        deployment_result.verifier_address,
+        proved.verifier_input,
+        &deployer,
+        matches.get_flag("packed"),
+    )
+    .await?;
+
+    if json {
+        print_json(&example_result(
+            &deployment_result,
+            json!({
+                "circuit": circuit.name(),
+                "hash_function": format!("{:?}", hash_function),
+                "permutations": 1u64 << log_instances,
+                "public_inputs": public_inputs,
+                "proving_ms": proving_time.as_millis() as u64,
+            }),
+            &report,
+        )?)?;
+        report.ensure_passed()?;
+        return Ok(());
+    }
+
+    println!("\n📏 Benchmark");
+    println!("   Permutations: {}", 1u64 << log_instances);
+    println!("   Proving time: {:.2?}", proving_time);
+    println!(
+        "   Calldata: {} bytes, {} gas",
+        report.calldata_size, report.calldata_gas
+    );
+    println!("   Gas used: {}", report.gas_used);
+    report.ensure_passed()?;
+
+    println!("\n🎉 Poseidon2 verification example completed!");
+    Ok(())
+}
+
+/// Deploy the STWOVerifier contract on `network`, or on a local Anvil with a
+/// block gas limit
+/// high enough for hash-heavy proofs
+async fn deploy_verifier(
+    network: Option<&String>,
+) -> Result<(DeploymentResult, STWOVerifierDeployer)> {
+    let anvil_config = AnvilConfig {
+        gas_limit: "1000000000".to_string(),
+        fork_url: None,
+        ..AnvilConfig::default()
+    };
+
+    let deployer = STWOVerifierDeployer::for_network(network.map(String::as_str), anvil_config)?;
+    let result = deployer.deploy_or_attach().await?;
+
+    info!(
+        address = %result.verifier_address,
+        chain_id = result.chain_id,
+        "STWOVerifier ready"
+    );
+
+    Ok((result, deployer))
+}
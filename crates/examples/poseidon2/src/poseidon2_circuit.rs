@@ -0,0 +1,429 @@
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use num_traits::Zero;
+use stwo::core::fields::m31::{BaseField, P};
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+};
+
+pub const N_STATE: usize = 16;
+pub const N_HALF_FULL_ROUNDS: usize = 4;
+pub const N_FULL_ROUNDS: usize = 2 * N_HALF_FULL_ROUNDS;
+pub const N_PARTIAL_ROUNDS: usize = 14;
+
+/// Input state, the state after every full round and the S-box output of every partial round
+pub const N_COLUMNS: usize = N_STATE * (1 + N_FULL_ROUNDS) + N_PARTIAL_ROUNDS;
+/// One preprocessed column per round constant
+pub const N_ROUND_CONSTANTS: usize = N_STATE * N_FULL_ROUNDS + N_PARTIAL_ROUNDS;
+
+/// The x^5 S-box makes every constraint degree 5, so the composition
+/// polynomial needs 4x the trace size
+pub const LOG_EXPAND: u32 = 2;
+
+/// Full-round constants of Plonky3's `Poseidon2Mersenne31<16>` as built by
+/// `new_from_rng_128(&mut Xoroshiro128Plus::seed_from_u64(1))`, the instance
+/// its known-answer test runs: the first four rows before the partial rounds,
+/// the last four after
+pub const EXTERNAL_ROUND_CONSTANTS: [[u32; N_STATE]; N_FULL_ROUNDS] = [
+    [
+        670752198, 2052960689, 867595173, 1121120522, 1732216065, 1777538858, 974826695, 857651441,
+        1509218160, 933669702, 308743513, 1606546523, 1395707998, 1248974626, 733565087,
+        1614794869,
+    ],
+    [
+        1457687568, 311580733, 2055660101, 1735187654, 1563765150, 358422393, 615368408,
+        1022914986, 1745808542, 1451694789, 1010294888, 478426997, 974777474, 836569592, 553962986,
+        354722588,
+    ],
+    [
+        1099724285, 957403621, 1171073730, 1314307614, 1575313895, 511348931, 1777322674,
+        743793854, 821769216, 365270850, 2100202195, 1610545562, 1781773041, 1642480066, 968153742,
+        107763776,
+    ],
+    [
+        304102504, 1048805912, 670079580, 1825005418, 699322108, 372969254, 1347088819, 1017368981,
+        695522824, 1491107118, 1656304581, 934311777, 1538050768, 1121275927, 1281424936,
+        1609172128,
+    ],
+    [
+        302658704, 2055094098, 16103019, 802016690, 359041126, 1491417545, 151742200, 122792040,
+        802809388, 2143547951, 2020259742, 437172020, 1610027373, 1217130568, 1833171446,
+        2135403312,
+    ],
+    [
+        60728125, 173288461, 1580136315, 2058149815, 1766051075, 458819359, 1495214374, 696367131,
+        367271168, 4549961, 718747682, 1943893587, 1536582683, 1574838747, 1735444335, 848039704,
+    ],
+    [
+        1689611743, 173154748, 427470023, 1004172913, 2077368442, 782638163, 1744615017,
+        1082619536, 297763826, 1160504957, 618979668, 1687696498, 37211066, 2117379525, 1790329919,
+        1183379851,
+    ],
+    [
+        545339302, 1229207547, 723170958, 1927785244, 1080767281, 1903150401, 1929310598, 95801870,
+        637696247, 1214340530, 1722126248, 1823128363, 926128391, 210718841, 1667233644, 688337540,
+    ],
+];
+
+/// Partial-round constants of the same instance
+pub const INTERNAL_ROUND_CONSTANTS: [u32; N_PARTIAL_ROUNDS] = [
+    129024239, 1282387121, 2004475442, 535738304, 1985680653, 895998816, 1108547306, 776893336,
+    1108245527, 574331301, 1825109420, 1194870642, 1497066195, 1664793266,
+];
+
+/// Log2 of the internal layer's diagonal entries `D[1..]`; `D[0] = -2`
+const INTERNAL_DIAGONAL_SHIFTS: [u32; N_STATE - 1] =
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 12, 13, 14, 15, 16];
+
+/// Round constant `index`, in the order the rounds consume them
+pub fn round_constant(index: usize) -> BaseField {
+    let n_first = N_STATE * N_HALF_FULL_ROUNDS;
+    let value = if index < n_first {
+        EXTERNAL_ROUND_CONSTANTS[index / N_STATE][index % N_STATE]
+    } else if index < n_first + N_PARTIAL_ROUNDS {
+        INTERNAL_ROUND_CONSTANTS[index - n_first]
+    } else {
+        let index = index - N_PARTIAL_ROUNDS;
+        EXTERNAL_ROUND_CONSTANTS[index / N_STATE][index % N_STATE]
+    };
+    BaseField::from_u32_unchecked(value)
+}
+
+pub fn round_constant_id(index: usize) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("poseidon2_round_constant_{}", index),
+    }
+}
+
+fn pow5<F: Clone + Mul<Output = F>>(x: F) -> F {
+    let x2 = x.clone() * x.clone();
+    let x4 = x2.clone() * x2;
+    x4 * x
+}
+
+/// The 4x4 matrix `circ(2, 3, 1, 1)` of the external layer
+fn apply_m4<F: Clone + Add<Output = F>>(x: [F; 4]) -> [F; 4] {
+    let [a, b, c, d] = x;
+    let double = |x: F| x.clone() + x;
+    let t01 = a.clone() + b.clone();
+    let t23 = c.clone() + d.clone();
+    let t0123 = t01.clone() + t23.clone();
+    let t01123 = t0123.clone() + b;
+    let t01233 = t0123 + d;
+    [
+        t01123.clone() + t01,
+        t01123 + double(c),
+        t01233.clone() + t23,
+        t01233 + double(a),
+    ]
+}
+
+/// External layer: `circ(2 * M4, M4, M4, M4)`
+fn apply_external_round_matrix<F: Clone + Add<Output = F> + AddAssign>(state: &mut [F; N_STATE]) {
+    for chunk in state.chunks_exact_mut(4) {
+        let mixed = apply_m4([
+            chunk[0].clone(),
+            chunk[1].clone(),
+            chunk[2].clone(),
+            chunk[3].clone(),
+        ]);
+        chunk.clone_from_slice(&mixed);
+    }
+    for j in 0..4 {
+        let sum =
+            state[j].clone() + state[j + 4].clone() + state[j + 8].clone() + state[j + 12].clone();
+        for i in 0..4 {
+            state[4 * i + j] += sum.clone();
+        }
+    }
+}
+
+/// Internal layer: `1 + diag(-2, 2^0, 2^1, ..., 2^8, 2^10, 2^12, ..., 2^16)`
+fn apply_internal_round_matrix<F>(state: &mut [F; N_STATE])
+where
+    F: Clone + Add<Output = F> + Sub<Output = F> + Mul<BaseField, Output = F>,
+{
+    let part_sum = state[2..]
+        .iter()
+        .cloned()
+        .fold(state[1].clone(), |acc, s| acc + s);
+    let full_sum = part_sum.clone() + state[0].clone();
+    state[0] = part_sum - state[0].clone();
+    for (s, shift) in state[1..].iter_mut().zip(INTERNAL_DIAGONAL_SHIFTS) {
+        *s = full_sum.clone() + s.clone() * BaseField::from_u32_unchecked(1 << shift);
+    }
+}
+
+/// Poseidon2-M31 permutation, calling `record` with every value the trace stores,
+/// in the column order of [`eval_permutation`].
+///
+/// Rounds follow the reference: the external layer first, then 4 full rounds,
+/// 14 partial and 4 full, each adding its constants, applying the S-box and
+/// then the round's linear layer.
+pub fn permute_recording(
+    mut state: [BaseField; N_STATE],
+    mut record: impl FnMut(BaseField),
+) -> [BaseField; N_STATE] {
+    let mut constants = (0..N_ROUND_CONSTANTS).map(round_constant);
+    state.iter().for_each(|&s| record(s));
+    apply_external_round_matrix(&mut state);
+
+    for round in 0..N_FULL_ROUNDS + N_PARTIAL_ROUNDS {
+        if is_full_round(round) {
+            for s in state.iter_mut() {
+                *s = pow5(*s + constants.next().unwrap());
+            }
+            apply_external_round_matrix(&mut state);
+            state.iter().for_each(|&s| record(s));
+        } else {
+            state[0] = pow5(state[0] + constants.next().unwrap());
+            record(state[0]);
+            apply_internal_round_matrix(&mut state);
+        }
+    }
+    state
+}
+
+/// Poseidon2-M31 permutation
+pub fn permute(state: [BaseField; N_STATE]) -> [BaseField; N_STATE] {
+    permute_recording(state, |_| {})
+}
+
+/// Rounds run 4 full, 14 partial, 4 full
+fn is_full_round(round: usize) -> bool {
+    round < N_HALF_FULL_ROUNDS || round >= N_HALF_FULL_ROUNDS + N_PARTIAL_ROUNDS
+}
+
+/// One Poseidon2-M31 permutation per row. Every S-box output is a trace column, so
+/// each constraint checks a single round.
+#[derive(Clone)]
+pub struct Poseidon2Eval {
+    pub log_n_rows: u32,
+}
+
+impl FrameworkEval for Poseidon2Eval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_rows + LOG_EXPAND
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let constants = round_constant_columns(&mut eval);
+        let input = std::array::from_fn(|_| eval.next_trace_mask());
+        eval_permutation(&mut eval, constants, input);
+        eval
+    }
+}
+
+/// Request the round-constant preprocessed columns, in index order
+pub fn round_constant_columns<E: EvalAtRow>(eval: &mut E) -> Vec<E::F> {
+    (0..N_ROUND_CONSTANTS)
+        .map(|index| eval.get_preprocessed_column(round_constant_id(index)))
+        .collect()
+}
+
+/// Constrain one permutation of `state`, reading every full-round output and
+/// partial-round S-box output from the next trace columns. Returns the output
+/// state, as trace masks.
+pub fn eval_permutation<E: EvalAtRow>(
+    eval: &mut E,
+    mut constants: Vec<E::F>,
+    mut state: [E::F; N_STATE],
+) -> [E::F; N_STATE] {
+    constants.reverse();
+    apply_external_round_matrix(&mut state);
+
+    for round in 0..N_FULL_ROUNDS + N_PARTIAL_ROUNDS {
+        if is_full_round(round) {
+            for s in state.iter_mut() {
+                *s = pow5(s.clone() + constants.pop().unwrap());
+            }
+            apply_external_round_matrix(&mut state);
+            for s in state.iter_mut() {
+                let m = eval.next_trace_mask();
+                eval.add_constraint(s.clone() - m.clone());
+                *s = m;
+            }
+        } else {
+            let sbox = pow5(state[0].clone() + constants.pop().unwrap());
+            let m = eval.next_trace_mask();
+            eval.add_constraint(sbox - m.clone());
+            state[0] = m;
+            apply_internal_round_matrix(&mut state);
+        }
+    }
+
+    state
+}
+
+pub type Poseidon2Component = FrameworkComponent<Poseidon2Eval>;
+
+/// Permutation input of `row`
+fn row_input(row: usize) -> [BaseField; N_STATE] {
+    std::array::from_fn(|i| {
+        BaseField::from_u32_unchecked(((row * N_STATE + i) as u64 % P as u64) as u32)
+    })
+}
+
+/// One constant column per round constant, matching [`round_constant_columns`]
+pub fn gen_round_constant_columns(
+    log_n_rows: u32,
+) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+    let domain = CanonicCoset::new(log_n_rows).circle_domain();
+    (0..N_ROUND_CONSTANTS)
+        .map(|index| {
+            let constant = round_constant(index);
+            CircleEvaluation::new(domain, (0..1 << log_n_rows).map(|_| constant).collect())
+        })
+        .collect()
+}
+
+/// Generate the round-constant columns and one permutation per row
+pub fn gen_trace(
+    log_n_rows: u32,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    [BaseField; N_STATE],
+) {
+    let n_rows = 1 << log_n_rows;
+    let domain = CanonicCoset::new(log_n_rows).circle_domain();
+
+    let preprocessed = gen_round_constant_columns(log_n_rows);
+
+    // Rows are independent, so the bit-reversed order does not matter here
+    let mut cols: Vec<Col<SimdBackend, BaseField>> = (0..N_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(n_rows))
+        .collect();
+    let mut first_output = [BaseField::zero(); N_STATE];
+    for row in 0..n_rows {
+        let mut col_idx = 0;
+        let output = permute_recording(row_input(row), |value| {
+            cols[col_idx].set(row, value);
+            col_idx += 1;
+        });
+        if row == 0 {
+            first_output = output;
+        }
+    }
+    let trace = cols
+        .into_iter()
+        .map(|col| CircleEvaluation::new(domain, col))
+        .collect();
+
+    (preprocessed, trace, first_output)
+}
+
+/// Proves 2^log_n_rows Poseidon2-M31 permutations
+pub struct Poseidon2Circuit;
+
+pub struct Poseidon2Metadata {
+    pub log_n_rows: u32,
+    pub first_output: [BaseField; N_STATE],
+}
+
+impl SolidityCircuit for Poseidon2Circuit {
+    type Eval = Poseidon2Eval;
+    type Metadata = Poseidon2Metadata;
+    /// Log2 of the number of permutations
+    type Input = u32;
+
+    fn name(&self) -> &'static str {
+        "poseidon2"
+    }
+
+    fn default_input(&self) -> u32 {
+        LOG_N_LANES
+    }
+
+    fn components(&self, metadata: &Poseidon2Metadata) -> Vec<Poseidon2Component> {
+        vec![Poseidon2Component::new(
+            &mut TraceLocationAllocator::default(),
+            Poseidon2Eval {
+                log_n_rows: metadata.log_n_rows,
+            },
+            SecureField::zero(),
+        )]
+    }
+
+    fn generate_trace(&self, log_n_rows: &u32) -> CircuitTrace<Poseidon2Metadata> {
+        let log_n_rows = (*log_n_rows).max(LOG_N_LANES);
+        let (preprocessed, trace, first_output) = gen_trace(log_n_rows);
+        CircuitTrace {
+            metadata: Poseidon2Metadata {
+                log_n_rows,
+                first_output,
+            },
+            preprocessed,
+            trace,
+            interaction: vec![],
+        }
+    }
+
+    /// Output of the permutation in the first row
+    fn public_inputs(&self, _log_n_rows: &u32, metadata: &Poseidon2Metadata) -> Vec<u32> {
+        metadata.first_output.iter().map(|value| value.0).collect()
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plonky3's `test_poseidon2_width_16_random` vector for the same instance
+    #[test]
+    fn test_permutation_known_answer() {
+        let input = [
+            894848333, 1437655012, 1200606629, 1690012884, 71131202, 1749206695, 1717947831,
+            120589055, 19776022, 42382981, 1831865506, 724844064, 171220207, 1299207443, 227047920,
+            1783754913,
+        ]
+        .map(BaseField::from_u32_unchecked);
+        let expected = [
+            1124552602, 2127602268, 1834113265, 1207687593, 1891161485, 245915620, 981277919,
+            627265710, 1534924153, 1580826924, 887997842, 1526280482, 547791593, 1028672510,
+            1803086471, 323071277,
+        ]
+        .map(BaseField::from_u32_unchecked);
+        assert_eq!(permute(input), expected);
+    }
+
+    #[test]
+    fn test_trace_matches_permutation() {
+        let (preprocessed, trace, first_output) = gen_trace(LOG_N_LANES);
+        assert_eq!(preprocessed.len(), N_ROUND_CONSTANTS);
+        assert_eq!(trace.len(), N_COLUMNS);
+        assert_eq!(first_output, permute(row_input(0)));
+
+        // The last N_STATE columns hold the output of every row
+        for row in 0..1 << LOG_N_LANES {
+            let output: Vec<BaseField> = trace[N_COLUMNS - N_STATE..]
+                .iter()
+                .map(|col| col.values.at(row))
+                .collect();
+            assert_eq!(output, permute(row_input(row)));
+        }
+    }
+}
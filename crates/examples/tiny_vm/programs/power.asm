@@ -0,0 +1,9 @@
+; 3^10 by repeated multiplication over the frame (acc, counter)
+    set 0 1         ; acc = 1
+    set 0 10        ; counter = 10
+loop:
+    set 0 3         ; base
+    mul 0 -3 -1     ; acc' = acc * base
+    addi 0 -3 -1    ; counter' = counter - 1
+    jnz -1 loop
+    halt -2         ; output acc = 59049
@@ -0,0 +1,636 @@
+//! Three components linked by LogUp lookups:
+//!
+//! - CPU: one row per executed step. Decodes the fetched instruction into
+//!   opcode flags, checks its semantics and the `pc` / `ap` transition to the
+//!   next row, and looks up the instruction in the program table and every
+//!   accessed cell in the memory table.
+//! - Program: the instructions as preprocessed columns, so the program is fixed
+//!   by the preprocessed commitment, with a multiplicity column.
+//! - Memory: one `(address, value)` row per cell of the write-once memory with
+//!   a multiplicity column.
+
+use num_traits::{One, Zero};
+use stwo::core::channel::Channel;
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::utils::{bit_reverse_index, coset_index_to_circle_domain_index};
+use stwo::prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use stwo::prover::backend::simd::qm31::PackedSecureField;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    relation, EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation,
+    RelationEntry, TraceLocationAllocator, ORIGINAL_TRACE_IDX,
+};
+
+use contracts::prover::TraceEvals;
+
+use crate::executor::Execution;
+use crate::isa::{Instruction, Opcode, N_OPCODES};
+
+relation!(ProgramRelation, 5);
+relation!(MemoryRelation, 2);
+
+/// Lookup elements, drawn after the main trace is committed
+#[derive(Clone)]
+pub struct VmRelations {
+    pub program: ProgramRelation,
+    pub memory: MemoryRelation,
+}
+
+impl VmRelations {
+    pub fn draw(channel: &mut impl Channel) -> Self {
+        Self {
+            program: ProgramRelation::draw(channel),
+            memory: MemoryRelation::draw(channel),
+        }
+    }
+
+    /// Placeholder elements for the components built before the main trace is
+    /// committed
+    pub fn dummy() -> Self {
+        Self {
+            program: ProgramRelation::dummy(),
+            memory: MemoryRelation::dummy(),
+        }
+    }
+}
+
+/// CPU columns, in trace order
+pub mod cpu_column {
+    use super::N_OPCODES;
+
+    pub const PC: usize = 0;
+    pub const AP: usize = 1;
+    pub const OPCODE: usize = 2;
+    pub const DST_OFF: usize = 3;
+    pub const OP0_OFF: usize = 4;
+    pub const OP1_OFF: usize = 5;
+    /// One-hot opcode flags, in `Opcode` order
+    pub const FLAGS: usize = 6;
+    pub const DST: usize = FLAGS + N_OPCODES;
+    pub const OP0: usize = DST + 1;
+    pub const OP1: usize = DST + 2;
+    pub const TAKEN: usize = DST + 3;
+    pub const INV: usize = DST + 4;
+    pub const N_COLUMNS: usize = DST + 5;
+}
+
+/// The jnz constraints and the paired LogUp columns are degree 3, which a 2x
+/// composition domain already holds
+const CPU_LOG_EXPAND: u32 = 1;
+/// Table components only carry a single LogUp fraction per row
+const TABLE_LOG_EXPAND: u32 = 1;
+
+#[derive(Clone)]
+pub struct CpuEval {
+    pub log_n_rows: u32,
+    pub output: BaseField,
+    pub relations: VmRelations,
+}
+
+impl CpuEval {
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let is_first = eval.get_preprocessed_column(is_first_id(self.log_n_rows));
+        let is_last = eval.get_preprocessed_column(is_last_id(self.log_n_rows));
+
+        let [pc, next_pc] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1]);
+        let [ap, next_ap] = eval.next_interaction_mask(ORIGINAL_TRACE_IDX, [0, 1]);
+        let opcode = eval.next_trace_mask();
+        let dst_off = eval.next_trace_mask();
+        let op0_off = eval.next_trace_mask();
+        let op1_off = eval.next_trace_mask();
+        let flags: [E::F; N_OPCODES] = std::array::from_fn(|_| eval.next_trace_mask());
+        let dst = eval.next_trace_mask();
+        let op0 = eval.next_trace_mask();
+        let op1 = eval.next_trace_mask();
+        let taken = eval.next_trace_mask();
+        let inv = eval.next_trace_mask();
+
+        let flag = |opcode: Opcode| flags[opcode as usize].clone();
+        let one = || E::F::one();
+
+        // Decoding: one flag set, matching the opcode
+        let mut flag_sum = E::F::zero();
+        let mut decoded = E::F::zero();
+        for (i, f) in flags.iter().enumerate() {
+            eval.add_constraint(f.clone() * (f.clone() - one()));
+            flag_sum += f.clone();
+            decoded += f.clone() * BaseField::from(i as u32);
+        }
+        eval.add_constraint(flag_sum - one());
+        eval.add_constraint(opcode.clone() - decoded);
+
+        // Semantics; `op1_off` is the immediate of addi, set and jnz
+        eval.add_constraint(flag(Opcode::Add) * (dst.clone() - op0.clone() - op1.clone()));
+        eval.add_constraint(flag(Opcode::Mul) * (dst.clone() - op0.clone() * op1.clone()));
+        eval.add_constraint(flag(Opcode::AddImm) * (dst.clone() - op0.clone() - op1_off.clone()));
+        eval.add_constraint(flag(Opcode::Set) * (dst.clone() - op1_off.clone()));
+
+        // jnz: taken iff the condition is non-zero, proven by its inverse
+        eval.add_constraint(taken.clone() * (taken.clone() - one()));
+        eval.add_constraint(taken.clone() * (one() - flag(Opcode::Jnz)));
+        eval.add_constraint(flag(Opcode::Jnz) * op0.clone() * (one() - taken.clone()));
+        eval.add_constraint(taken.clone() * (one() - op0.clone() * inv));
+
+        // Transition to the next row
+        let not_last = one() - is_last.clone();
+        let writes = one() - flag(Opcode::Jnz) - flag(Opcode::Halt);
+        eval.add_constraint(not_last.clone() * (next_ap - ap.clone() - writes.clone()));
+        eval.add_constraint(
+            not_last
+                * (next_pc - pc.clone() - one() + flag(Opcode::Halt)
+                    - taken * (op1_off.clone() - pc.clone() - one())),
+        );
+
+        // Start at pc = ap = 0, end halted with the claimed output
+        eval.add_constraint(is_first.clone() * pc.clone());
+        eval.add_constraint(is_first * ap.clone());
+        eval.add_constraint(is_last.clone() * (one() - flag(Opcode::Halt)));
+        eval.add_constraint(is_last * (op0.clone() - E::F::from(self.output)));
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.program,
+            E::EF::one(),
+            &[
+                pc,
+                opcode,
+                dst_off.clone(),
+                op0_off.clone(),
+                op1_off.clone(),
+            ],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(writes),
+            &[ap.clone() + dst_off, dst],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(one() - flag(Opcode::Set)),
+            &[ap.clone() + op0_off, op0],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(flag(Opcode::Add) + flag(Opcode::Mul)),
+            &[ap + op1_off, op1],
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+#[derive(Clone)]
+pub struct ProgramEval {
+    pub log_n_rows: u32,
+    pub relations: VmRelations,
+}
+
+impl ProgramEval {
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let address = eval.get_preprocessed_column(program_address_id(self.log_n_rows));
+        let fields: [E::F; 4] = std::array::from_fn(|i| {
+            eval.get_preprocessed_column(program_field_id(i, self.log_n_rows))
+        });
+        let multiplicity = eval.next_trace_mask();
+
+        let [opcode, dst, op0, op1] = fields;
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.program,
+            -E::EF::from(multiplicity),
+            &[address, opcode, dst, op0, op1],
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+#[derive(Clone)]
+pub struct MemoryEval {
+    pub log_n_rows: u32,
+    pub relations: VmRelations,
+}
+
+impl MemoryEval {
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let address = eval.get_preprocessed_column(memory_address_id(self.log_n_rows));
+        let value = eval.next_trace_mask();
+        let multiplicity = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            -E::EF::from(multiplicity),
+            &[address, value],
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// The components share one proof, so they share one eval type
+#[derive(Clone)]
+pub enum VmEval {
+    Cpu(CpuEval),
+    Program(ProgramEval),
+    Memory(MemoryEval),
+}
+
+impl FrameworkEval for VmEval {
+    fn log_size(&self) -> u32 {
+        match self {
+            VmEval::Cpu(eval) => eval.log_n_rows,
+            VmEval::Program(eval) => eval.log_n_rows,
+            VmEval::Memory(eval) => eval.log_n_rows,
+        }
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        match self {
+            VmEval::Cpu(eval) => eval.log_n_rows + CPU_LOG_EXPAND,
+            VmEval::Program(eval) => eval.log_n_rows + TABLE_LOG_EXPAND,
+            VmEval::Memory(eval) => eval.log_n_rows + TABLE_LOG_EXPAND,
+        }
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, eval: E) -> E {
+        match self {
+            VmEval::Cpu(cpu) => cpu.evaluate(eval),
+            VmEval::Program(program) => program.evaluate(eval),
+            VmEval::Memory(memory) => memory.evaluate(eval),
+        }
+    }
+}
+
+pub type VmComponent = FrameworkComponent<VmEval>;
+
+fn is_first_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("is_first_{}", log_size),
+    }
+}
+
+fn is_last_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("is_last_{}", log_size),
+    }
+}
+
+fn program_address_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("vm_program_address_{}", log_size),
+    }
+}
+
+/// Field `i` of the instruction encoding: opcode, dst, op0, op1
+fn program_field_id(i: usize, log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("vm_program_field_{}_{}", i, log_size),
+    }
+}
+
+fn memory_address_id(log_size: u32) -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("vm_memory_address_{}", log_size),
+    }
+}
+
+/// Bit-reversed circle domain position of the `row`-th point of the trace coset
+fn row_position(row: usize, log_size: u32) -> usize {
+    bit_reverse_index(coset_index_to_circle_domain_index(row, log_size), log_size)
+}
+
+/// Rows for `n` entries: a power of two, never below one SIMD vector
+fn log_size_for(n: usize) -> u32 {
+    n.max(1).next_power_of_two().ilog2().max(LOG_N_LANES)
+}
+
+/// Column with `value(row)` at every row of the trace coset
+fn gen_column(log_size: u32, value: impl Fn(usize) -> BaseField) -> Col<SimdBackend, BaseField> {
+    let mut col = Col::<SimdBackend, BaseField>::zeros(1 << log_size);
+    for row in 0..1 << log_size {
+        col.set(row_position(row, log_size), value(row));
+    }
+    col
+}
+
+fn to_evals(log_size: u32, cols: Vec<Col<SimdBackend, BaseField>>) -> TraceEvals {
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    cols.into_iter()
+        .map(|col| CircleEvaluation::new(domain, col))
+        .collect()
+}
+
+/// Log sizes of the CPU, program and memory components
+#[derive(Debug, Clone, Copy)]
+pub struct VmLogSizes {
+    pub cpu: u32,
+    pub program: u32,
+    pub memory: u32,
+}
+
+impl VmLogSizes {
+    /// Rows needed to prove `execution` of `program`
+    pub fn of(program: &[Instruction], execution: &Execution) -> Self {
+        Self {
+            cpu: log_size_for(execution.steps.len()),
+            program: log_size_for(program.len()),
+            memory: log_size_for(execution.memory.len()),
+        }
+    }
+}
+
+/// Main trace columns per component, kept for the interaction trace
+pub struct VmTrace {
+    pub log_sizes: VmLogSizes,
+    pub preprocessed: TraceEvals,
+    pub cpu: Vec<Col<SimdBackend, BaseField>>,
+    pub program: Vec<Col<SimdBackend, BaseField>>,
+    pub memory: Vec<Col<SimdBackend, BaseField>>,
+    /// Preprocessed program columns: address and the 4 encoding fields
+    program_table: Vec<Col<SimdBackend, BaseField>>,
+    memory_addresses: Col<SimdBackend, BaseField>,
+}
+
+impl VmTrace {
+    /// Main trace, in component order
+    pub fn trace(&self) -> TraceEvals {
+        let mut trace = to_evals(self.log_sizes.cpu, self.cpu.clone());
+        trace.extend(to_evals(self.log_sizes.program, self.program.clone()));
+        trace.extend(to_evals(self.log_sizes.memory, self.memory.clone()));
+        trace
+    }
+}
+
+/// Build every trace of `execution`. The CPU trace is padded by repeating the
+/// final `halt`, which keeps `pc` and `ap` and reads the output again.
+pub fn gen_trace(program: &[Instruction], execution: &Execution) -> VmTrace {
+    let log_sizes = VmLogSizes::of(program, execution);
+    let halt = *execution.steps.last().unwrap();
+    let step = |row: usize| execution.steps.get(row).copied().unwrap_or(halt);
+
+    let mut program_multiplicities = vec![0u32; 1 << log_sizes.program];
+    let mut memory_multiplicities = vec![0u32; 1 << log_sizes.memory];
+    for row in 0..1 << log_sizes.cpu {
+        let step = step(row);
+        let instruction = step.instruction;
+        program_multiplicities[step.pc as usize] += 1;
+        if instruction.opcode.writes() {
+            memory_multiplicities[step.address(instruction.dst) as usize] += 1;
+        }
+        if instruction.opcode.reads_op0() {
+            memory_multiplicities[step.address(instruction.op0) as usize] += 1;
+        }
+        if instruction.opcode.reads_op1() {
+            memory_multiplicities[step.address(instruction.op1) as usize] += 1;
+        }
+    }
+
+    let cpu = (0..cpu_column::N_COLUMNS)
+        .map(|column| {
+            gen_column(log_sizes.cpu, |row| {
+                let step = step(row);
+                let [opcode, dst_off, op0_off, op1_off] = step.instruction.encode();
+                match column {
+                    cpu_column::PC => BaseField::from(step.pc),
+                    cpu_column::AP => BaseField::from(step.ap),
+                    cpu_column::OPCODE => opcode,
+                    cpu_column::DST_OFF => dst_off,
+                    cpu_column::OP0_OFF => op0_off,
+                    cpu_column::OP1_OFF => op1_off,
+                    cpu_column::DST => step.dst,
+                    cpu_column::OP0 => step.op0,
+                    cpu_column::OP1 => step.op1,
+                    cpu_column::TAKEN => BaseField::from(step.taken as u32),
+                    cpu_column::INV => step.inv,
+                    flag => BaseField::from(
+                        (step.instruction.opcode as usize == flag - cpu_column::FLAGS) as u32,
+                    ),
+                }
+            })
+        })
+        .collect();
+
+    // Rows past the program hold halts that are never fetched
+    let instruction = |row: usize| program.get(row).copied().unwrap_or(Instruction::HALT);
+    let mut program_table = vec![gen_column(log_sizes.program, |row| {
+        BaseField::from(row as u32)
+    })];
+    program_table.extend(
+        (0..4).map(|field| gen_column(log_sizes.program, |row| instruction(row).encode()[field])),
+    );
+    let program_trace = vec![gen_column(log_sizes.program, |row| {
+        BaseField::from(program_multiplicities[row])
+    })];
+
+    let memory_addresses = gen_column(log_sizes.memory, |row| BaseField::from(row as u32));
+    let memory = vec![
+        gen_column(log_sizes.memory, |row| {
+            execution
+                .memory
+                .get(row)
+                .copied()
+                .unwrap_or_else(BaseField::zero)
+        }),
+        gen_column(log_sizes.memory, |row| {
+            BaseField::from(memory_multiplicities[row])
+        }),
+    ];
+
+    // Same order as the get_preprocessed_column calls of the components
+    let mut preprocessed = to_evals(
+        log_sizes.cpu,
+        vec![
+            gen_column(log_sizes.cpu, |row| BaseField::from((row == 0) as u32)),
+            gen_column(log_sizes.cpu, |row| {
+                BaseField::from((row == (1 << log_sizes.cpu) - 1) as u32)
+            }),
+        ],
+    );
+    preprocessed.extend(to_evals(log_sizes.program, program_table.clone()));
+    preprocessed.extend(to_evals(log_sizes.memory, vec![memory_addresses.clone()]));
+
+    VmTrace {
+        log_sizes,
+        preprocessed,
+        cpu,
+        program: program_trace,
+        memory,
+        program_table,
+        memory_addresses,
+    }
+}
+
+/// LogUp fraction as `(numerator, denominator)` per packed row
+type Fraction<'a> = (
+    Box<dyn Fn(usize) -> PackedSecureField + 'a>,
+    Box<dyn Fn(usize) -> PackedSecureField + 'a>,
+);
+
+/// One LogUp column per pair of fractions, matching `finalize_logup_in_pairs`
+fn gen_logup_columns(log_size: u32, fractions: &[Fraction<'_>]) -> (TraceEvals, SecureField) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    for pair in fractions.chunks(2) {
+        let mut col_gen = logup_gen.new_col();
+        for vec_row in 0..1 << (log_size - LOG_N_LANES) {
+            let (n0, d0) = (pair[0].0(vec_row), pair[0].1(vec_row));
+            let (numerator, denominator) = match pair.get(1) {
+                Some((n1, d1)) => {
+                    let (n1, d1) = (n1(vec_row), d1(vec_row));
+                    (n0 * d1 + n1 * d0, d0 * d1)
+                }
+                None => (n0, d0),
+            };
+            col_gen.write_frac(vec_row, numerator, denominator);
+        }
+        col_gen.finalize_col();
+    }
+    logup_gen.finalize_last()
+}
+
+/// Interaction trace in component order, and the claimed sum of each component
+pub fn gen_interaction_trace(
+    trace: &VmTrace,
+    relations: &VmRelations,
+) -> (TraceEvals, [SecureField; 3]) {
+    use cpu_column::*;
+
+    let cpu = |column: usize| move |vec_row: usize| trace.cpu[column].data[vec_row];
+    let flag = |opcode: Opcode| cpu(FLAGS + opcode as usize);
+    let one = PackedBaseField::broadcast(BaseField::one());
+    let secure = |value: PackedBaseField| PackedSecureField::from(value);
+
+    let cpu_fractions: Vec<Fraction<'_>> = vec![
+        (
+            Box::new(|_| PackedSecureField::one()),
+            Box::new(|r| {
+                relations.program.combine(&[
+                    cpu(PC)(r),
+                    cpu(OPCODE)(r),
+                    cpu(DST_OFF)(r),
+                    cpu(OP0_OFF)(r),
+                    cpu(OP1_OFF)(r),
+                ])
+            }),
+        ),
+        (
+            Box::new(|r| secure(one - flag(Opcode::Jnz)(r) - flag(Opcode::Halt)(r))),
+            Box::new(|r| {
+                relations
+                    .memory
+                    .combine(&[cpu(AP)(r) + cpu(DST_OFF)(r), cpu(DST)(r)])
+            }),
+        ),
+        (
+            Box::new(|r| secure(one - flag(Opcode::Set)(r))),
+            Box::new(|r| {
+                relations
+                    .memory
+                    .combine(&[cpu(AP)(r) + cpu(OP0_OFF)(r), cpu(OP0)(r)])
+            }),
+        ),
+        (
+            Box::new(|r| secure(flag(Opcode::Add)(r) + flag(Opcode::Mul)(r))),
+            Box::new(|r| {
+                relations
+                    .memory
+                    .combine(&[cpu(AP)(r) + cpu(OP1_OFF)(r), cpu(OP1)(r)])
+            }),
+        ),
+    ];
+    let (mut interaction, cpu_sum) = gen_logup_columns(trace.log_sizes.cpu, &cpu_fractions);
+
+    let program_fractions: Vec<Fraction<'_>> = vec![(
+        Box::new(|r| -secure(trace.program[0].data[r])),
+        Box::new(|r| {
+            let values: Vec<PackedBaseField> =
+                trace.program_table.iter().map(|col| col.data[r]).collect();
+            relations.program.combine(&values)
+        }),
+    )];
+    let (program_interaction, program_sum) =
+        gen_logup_columns(trace.log_sizes.program, &program_fractions);
+    interaction.extend(program_interaction);
+
+    let memory_fractions: Vec<Fraction<'_>> = vec![(
+        Box::new(|r| -secure(trace.memory[1].data[r])),
+        Box::new(|r| {
+            relations
+                .memory
+                .combine(&[trace.memory_addresses.data[r], trace.memory[0].data[r]])
+        }),
+    )];
+    let (memory_interaction, memory_sum) =
+        gen_logup_columns(trace.log_sizes.memory, &memory_fractions);
+    interaction.extend(memory_interaction);
+
+    (interaction, [cpu_sum, program_sum, memory_sum])
+}
+
+/// CPU, program and memory components, in trace order
+pub fn components(
+    log_sizes: VmLogSizes,
+    output: BaseField,
+    relations: &VmRelations,
+    claimed_sums: [SecureField; 3],
+) -> Vec<VmComponent> {
+    let allocator = &mut TraceLocationAllocator::default();
+    let [cpu_sum, program_sum, memory_sum] = claimed_sums;
+    vec![
+        VmComponent::new(
+            allocator,
+            VmEval::Cpu(CpuEval {
+                log_n_rows: log_sizes.cpu,
+                output,
+                relations: relations.clone(),
+            }),
+            cpu_sum,
+        ),
+        VmComponent::new(
+            allocator,
+            VmEval::Program(ProgramEval {
+                log_n_rows: log_sizes.program,
+                relations: relations.clone(),
+            }),
+            program_sum,
+        ),
+        VmComponent::new(
+            allocator,
+            VmEval::Memory(MemoryEval {
+                log_n_rows: log_sizes.memory,
+                relations: relations.clone(),
+            }),
+            memory_sum,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::{assemble, fibonacci_source};
+    use crate::executor::execute;
+
+    #[test]
+    fn test_lookups_balance() {
+        let program = assemble(&fibonacci_source(10)).unwrap();
+        let execution = execute(&program, 1000).unwrap();
+        let trace = gen_trace(&program, &execution);
+        assert_eq!(trace.cpu.len(), cpu_column::N_COLUMNS);
+        assert_eq!(trace.preprocessed.len(), 2 + 5 + 1);
+
+        let (interaction, claimed_sums) = gen_interaction_trace(&trace, &VmRelations::dummy());
+        // Two paired CPU columns plus one column per table, 4 base columns each
+        assert_eq!(interaction.len(), 4 * (2 + 1 + 1));
+        assert_eq!(
+            claimed_sums.iter().copied().sum::<SecureField>(),
+            SecureField::zero()
+        );
+    }
+}
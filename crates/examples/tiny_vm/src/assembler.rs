@@ -0,0 +1,173 @@
+//! Assembler for the tiny VM.
+//!
+//! One instruction per line, `;` starts a comment and `name:` defines a label
+//! for the next instruction. Operands are signed integers separated by spaces
+//! or commas; the `jnz` target may be a label.
+//!
+//! ```text
+//!     set 0 5         ; [ap] = 5
+//! loop:
+//!     addi 0 -1 -1    ; [ap] = [ap-1] - 1
+//!     jnz -1 loop
+//!     halt -1
+//! ```
+
+use std::collections::HashMap;
+
+use crate::isa::{Instruction, Opcode};
+
+pub fn assemble(source: &str) -> Result<Vec<Instruction>, String> {
+    // First pass: label addresses
+    let mut labels = HashMap::new();
+    let mut pc = 0;
+    for (line_no, line) in lines(source) {
+        let (label, rest) = split_label(line);
+        if let Some(label) = label {
+            if labels.insert(label, pc).is_some() {
+                return Err(format!("line {}: duplicate label {}", line_no, label));
+            }
+        }
+        if !rest.is_empty() {
+            pc += 1;
+        }
+    }
+
+    let mut program = Vec::new();
+    for (line_no, line) in lines(source) {
+        let (_, rest) = split_label(line);
+        if rest.is_empty() {
+            continue;
+        }
+        let instruction =
+            parse_instruction(rest, &labels).map_err(|e| format!("line {}: {}", line_no, e))?;
+        program.push(instruction);
+    }
+
+    if program.is_empty() {
+        return Err("Empty program".to_string());
+    }
+    Ok(program)
+}
+
+/// Non-empty lines with comments stripped, numbered from 1
+fn lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split(';').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn split_label(line: &str) -> (Option<&str>, &str) {
+    match line.split_once(':') {
+        Some((label, rest)) => (Some(label.trim()), rest.trim()),
+        None => (None, line),
+    }
+}
+
+fn parse_instruction(line: &str, labels: &HashMap<&str, i32>) -> Result<Instruction, String> {
+    let mut tokens = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty());
+    let mnemonic = tokens.next().unwrap().to_ascii_lowercase();
+    let operands: Vec<&str> = tokens.collect();
+
+    let opcode = Opcode::ALL
+        .into_iter()
+        .find(|opcode| opcode.mnemonic() == mnemonic)
+        .ok_or_else(|| format!("unknown instruction {}", mnemonic))?;
+
+    let expected = match opcode {
+        Opcode::Add | Opcode::Mul | Opcode::AddImm => 3,
+        Opcode::Set | Opcode::Jnz => 2,
+        Opcode::Halt => 1,
+    };
+    if operands.len() != expected {
+        return Err(format!(
+            "{} takes {} operands, got {}",
+            mnemonic,
+            expected,
+            operands.len()
+        ));
+    }
+
+    let int = |token: &str| {
+        token
+            .parse::<i32>()
+            .map_err(|_| format!("invalid operand {}", token))
+    };
+    let target = |token: &str| match labels.get(token) {
+        Some(&pc) => Ok(pc),
+        None => int(token).map_err(|_| format!("unknown label {}", token)),
+    };
+
+    let (dst, op0, op1) = match opcode {
+        Opcode::Add | Opcode::Mul | Opcode::AddImm => {
+            (int(operands[0])?, int(operands[1])?, int(operands[2])?)
+        }
+        Opcode::Set => (int(operands[0])?, 0, int(operands[1])?),
+        Opcode::Jnz => (0, int(operands[0])?, target(operands[1])?),
+        Opcode::Halt => (0, int(operands[0])?, 0),
+    };
+
+    Ok(Instruction {
+        opcode,
+        dst,
+        op0,
+        op1,
+    })
+}
+
+/// F(n) through a loop over the frame `(a, b, counter)`; outputs `a`
+pub fn fibonacci_source(n: u32) -> String {
+    format!(
+        "\
+    set 0 0         ; a = F(0)
+    set 0 1         ; b = F(1)
+    set 0 {}        ; counter
+loop:
+    addi 0 -2 0     ; a' = b
+    add 0 -4 -3     ; b' = a + b
+    addi 0 -3 -1    ; counter' = counter - 1
+    jnz -1 loop
+    halt -3         ; output a
+",
+        n
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_fibonacci() {
+        let program = assemble(&fibonacci_source(10)).unwrap();
+        assert_eq!(program.len(), 8);
+        assert_eq!(
+            program[6],
+            Instruction {
+                opcode: Opcode::Jnz,
+                dst: 0,
+                op0: -1,
+                op1: 3,
+            }
+        );
+        assert_eq!(program[4].to_string(), "add 0 -4 -3");
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        assert!(assemble("").is_err());
+        assert!(assemble("nop").unwrap_err().contains("unknown instruction"));
+        assert!(assemble("add 0 1")
+            .unwrap_err()
+            .contains("takes 3 operands"));
+        assert!(assemble("jnz 0 nowhere")
+            .unwrap_err()
+            .contains("unknown label"));
+        assert!(assemble("a:\na: halt 0")
+            .unwrap_err()
+            .contains("duplicate label"));
+    }
+}
@@ -0,0 +1,175 @@
+use num_traits::Zero;
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::FieldExpOps;
+
+use crate::isa::{m31_from_i32, Instruction, Opcode};
+
+/// VM state before one instruction, with the values it touched
+#[derive(Debug, Clone, Copy)]
+pub struct Step {
+    pub pc: u32,
+    pub ap: u32,
+    pub instruction: Instruction,
+    /// Written value, zero when nothing is written
+    pub dst: BaseField,
+    /// `[ap+op0]`, zero when not read
+    pub op0: BaseField,
+    /// `[ap+op1]`, zero when `op1` is an immediate
+    pub op1: BaseField,
+    /// Whether a `jnz` jumped
+    pub taken: bool,
+    /// Inverse of the `jnz` condition, zero otherwise
+    pub inv: BaseField,
+}
+
+impl Step {
+    pub fn address(&self, offset: i32) -> u32 {
+        (self.ap as i64 + offset as i64) as u32
+    }
+}
+
+/// Run to completion: every step up to and including `halt`, and the final
+/// write-once memory
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub steps: Vec<Step>,
+    pub memory: Vec<BaseField>,
+    pub output: BaseField,
+}
+
+/// Execute `program` from `pc = ap = 0`.
+///
+/// Memory is write-once: writing a cell again with another value, or reading
+/// a cell before it is written, is an error, since the memory lookup could not
+/// be satisfied.
+pub fn execute(program: &[Instruction], max_steps: usize) -> Result<Execution, String> {
+    let mut memory: Vec<Option<BaseField>> = Vec::new();
+    let mut steps = Vec::new();
+    let (mut pc, mut ap) = (0u32, 0u32);
+
+    loop {
+        if steps.len() == max_steps {
+            return Err(format!("No halt within {} steps", max_steps));
+        }
+        let instruction = *program
+            .get(pc as usize)
+            .ok_or_else(|| format!("pc {} outside the program", pc))?;
+        let address = |offset: i32| -> Result<usize, String> {
+            usize::try_from(ap as i64 + offset as i64)
+                .map_err(|_| format!("pc {}: negative address ap{:+}", pc, offset))
+        };
+        let read = |memory: &Vec<Option<BaseField>>, offset: i32| -> Result<BaseField, String> {
+            let address = address(offset)?;
+            memory
+                .get(address)
+                .copied()
+                .flatten()
+                .ok_or_else(|| format!("pc {}: read of unwritten cell {}", pc, address))
+        };
+
+        let mut step = Step {
+            pc,
+            ap,
+            instruction,
+            dst: BaseField::zero(),
+            op0: BaseField::zero(),
+            op1: BaseField::zero(),
+            taken: false,
+            inv: BaseField::zero(),
+        };
+        let opcode = instruction.opcode;
+        if opcode.reads_op0() {
+            step.op0 = read(&memory, instruction.op0)?;
+        }
+        if opcode.reads_op1() {
+            step.op1 = read(&memory, instruction.op1)?;
+        }
+        let imm = m31_from_i32(instruction.op1);
+
+        match opcode {
+            Opcode::Add => step.dst = step.op0 + step.op1,
+            Opcode::Mul => step.dst = step.op0 * step.op1,
+            Opcode::AddImm => step.dst = step.op0 + imm,
+            Opcode::Set => step.dst = imm,
+            Opcode::Jnz => {
+                step.taken = !step.op0.is_zero();
+                if step.taken {
+                    step.inv = step.op0.inverse();
+                }
+            }
+            Opcode::Halt => {}
+        }
+
+        if opcode.writes() {
+            let address = address(instruction.dst)?;
+            if memory.len() <= address {
+                memory.resize(address + 1, None);
+            }
+            match memory[address] {
+                Some(value) if value != step.dst => {
+                    return Err(format!(
+                        "pc {}: cell {} written twice ({} then {})",
+                        pc, address, value, step.dst
+                    ))
+                }
+                _ => memory[address] = Some(step.dst),
+            }
+            ap += 1;
+        }
+        steps.push(step);
+
+        pc = match opcode {
+            Opcode::Halt => break,
+            Opcode::Jnz if step.taken => u32::try_from(instruction.op1)
+                .map_err(|_| format!("pc {}: negative jump target", pc))?,
+            _ => pc + 1,
+        };
+    }
+
+    let output = steps.last().unwrap().op0;
+    Ok(Execution {
+        steps,
+        memory: memory
+            .into_iter()
+            .map(|value| value.unwrap_or_else(BaseField::zero))
+            .collect(),
+        output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::{assemble, fibonacci_source};
+
+    #[test]
+    fn test_execute_fibonacci() {
+        let program = assemble(&fibonacci_source(10)).unwrap();
+        let execution = execute(&program, 1000).unwrap();
+        assert_eq!(execution.output, BaseField::from_u32_unchecked(55));
+        // 3 sets, 10 iterations of 4 instructions and the halt
+        assert_eq!(execution.steps.len(), 3 + 10 * 4 + 1);
+        assert_eq!(execution.memory.len(), 3 + 10 * 3);
+    }
+
+    #[test]
+    fn test_execute_power_program() {
+        let program = assemble(include_str!("../programs/power.asm")).unwrap();
+        let execution = execute(&program, 1000).unwrap();
+        assert_eq!(execution.output, BaseField::from_u32_unchecked(59049));
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let overwrite = assemble("set 0 1\nset -1 2\nhalt -1").unwrap();
+        assert!(execute(&overwrite, 10)
+            .unwrap_err()
+            .contains("written twice"));
+
+        let unwritten = assemble("halt 3").unwrap();
+        assert!(execute(&unwritten, 10).unwrap_err().contains("unwritten"));
+
+        let forever = assemble("set 0 1\nloop: jnz -1 loop").unwrap();
+        assert!(execute(&forever, 10).unwrap_err().contains("No halt"));
+    }
+}
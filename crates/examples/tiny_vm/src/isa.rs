@@ -0,0 +1,120 @@
+use std::fmt;
+
+use stwo::core::fields::m31::{BaseField, P};
+
+/// Instruction set. Operands are offsets from the allocation pointer `ap`;
+/// every instruction that writes a cell advances `ap` by one.
+///
+/// | opcode | effect                             | next pc                |
+/// |--------|------------------------------------|------------------------|
+/// | add    | `[ap+dst] = [ap+op0] + [ap+op1]`   | pc + 1                 |
+/// | mul    | `[ap+dst] = [ap+op0] * [ap+op1]`   | pc + 1                 |
+/// | addi   | `[ap+dst] = [ap+op0] + imm`        | pc + 1                 |
+/// | set    | `[ap+dst] = imm`                   | pc + 1                 |
+/// | jnz    | -                                  | `[ap+op0] != 0 ? imm : pc + 1` |
+/// | halt   | outputs `[ap+op0]`                 | pc                     |
+///
+/// `imm` is the `op1` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Opcode {
+    Add = 0,
+    Mul = 1,
+    AddImm = 2,
+    Set = 3,
+    Jnz = 4,
+    Halt = 5,
+}
+
+pub const N_OPCODES: usize = 6;
+
+impl Opcode {
+    pub const ALL: [Opcode; N_OPCODES] = [
+        Opcode::Add,
+        Opcode::Mul,
+        Opcode::AddImm,
+        Opcode::Set,
+        Opcode::Jnz,
+        Opcode::Halt,
+    ];
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Mul => "mul",
+            Opcode::AddImm => "addi",
+            Opcode::Set => "set",
+            Opcode::Jnz => "jnz",
+            Opcode::Halt => "halt",
+        }
+    }
+
+    /// Whether the instruction writes `[ap+dst]` and advances `ap`
+    pub fn writes(self) -> bool {
+        matches!(
+            self,
+            Opcode::Add | Opcode::Mul | Opcode::AddImm | Opcode::Set
+        )
+    }
+
+    /// Whether `[ap+op0]` is read
+    pub fn reads_op0(self) -> bool {
+        self != Opcode::Set
+    }
+
+    /// Whether `[ap+op1]` is read, as opposed to `op1` being an immediate
+    pub fn reads_op1(self) -> bool {
+        matches!(self, Opcode::Add | Opcode::Mul)
+    }
+}
+
+/// One decoded instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub dst: i32,
+    pub op0: i32,
+    pub op1: i32,
+}
+
+impl Instruction {
+    pub const HALT: Instruction = Instruction {
+        opcode: Opcode::Halt,
+        dst: 0,
+        op0: 0,
+        op1: 0,
+    };
+
+    /// Encoding stored in the program columns: opcode, dst, op0, op1
+    pub fn encode(&self) -> [BaseField; 4] {
+        [
+            BaseField::from_u32_unchecked(self.opcode as u32),
+            m31_from_i32(self.dst),
+            m31_from_i32(self.op0),
+            m31_from_i32(self.op1),
+        ]
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.opcode {
+            Opcode::Add | Opcode::Mul | Opcode::AddImm => write!(
+                f,
+                "{} {} {} {}",
+                self.opcode.mnemonic(),
+                self.dst,
+                self.op0,
+                self.op1
+            ),
+            Opcode::Set => write!(f, "set {} {}", self.dst, self.op1),
+            Opcode::Jnz => write!(f, "jnz {} {}", self.op0, self.op1),
+            Opcode::Halt => write!(f, "halt {}", self.op0),
+        }
+    }
+}
+
+/// Signed value as an M31 element, negatives wrapping to `P - |v|`
+pub fn m31_from_i32(value: i32) -> BaseField {
+    BaseField::from_u32_unchecked((value as i64).rem_euclid(P as i64) as u32)
+}
@@ -0,0 +1,5 @@
+pub mod air;
+pub mod assembler;
+pub mod executor;
+pub mod isa;
+pub mod vm_circuit;
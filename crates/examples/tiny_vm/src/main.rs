@@ -0,0 +1,292 @@
+use alloy::{
+    network::EthereumWallet,
+    providers::ProviderBuilder,
+    sol,
+    sol_types::{SolType, SolValue},
+};
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::circuit::DynCircuit;
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use contracts::verifying_key::VerifyingKey;
+use serde_json::json;
+use std::time::Instant;
+use tracing::info;
+use verifier::deploy::{AnvilConfig, DeploymentResult, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+
+use tiny_vm::air::VmLogSizes;
+use tiny_vm::vm_circuit::{ProgramRun, TinyVmCircuit};
+
+sol!(
+    #[sol(rpc)]
+    TinyVmConsumer,
+    "../../../out/TinyVmConsumer.sol/TinyVmConsumer.json"
+);
+
+/// Tiny VM execution proof verification example
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Tiny VM STARK Verifier")
+        .version("1.0")
+        .about("Example proving a tiny VM program run and verifying it with STWO verifier")
+        .arg(
+            Arg::new("program")
+                .long("program")
+                .short('p')
+                .help("Assembly file to run (defaults to the built-in Fibonacci program)")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("n")
+                .long("n")
+                .short('n')
+                .help("Fibonacci index computed by the built-in program")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .default_value("10"),
+        )
+        .arg(
+            Arg::new("max-steps")
+                .long("max-steps")
+                .help("Abort programs that do not halt within this many steps")
+                .value_name("STEPS")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("65536"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if !json {
+        println!("🖥️  Tiny VM STARK Verifier Example");
+        println!("=================================");
+    }
+
+    let program = match matches.get_one::<String>("program") {
+        Some(path) => path.clone(),
+        None => matches.get_one::<u32>("n").unwrap().to_string(),
+    };
+    let max_steps = *matches.get_one::<usize>("max-steps").unwrap();
+    let spec = format!("{}:{}", program, max_steps);
+    let run: ProgramRun = spec.parse()?;
+    let program = &run.program;
+    if !json {
+        println!("\n📜 Program ({} instructions):", program.len());
+        for (pc, instruction) in program.iter().enumerate() {
+            println!("   {:>3}: {}", pc, instruction);
+        }
+    }
+
+    let (deployment_result, deployer) =
+        deploy_verifier(matches.get_one::<String>("network")).await?;
+
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+    let circuit = TinyVmCircuit;
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+
+    if !json {
+        println!("\n📊 Executing and proving");
+        println!("   Hash function: {:?}", hash_function);
+    }
+    let proving_start = Instant::now();
+    let proved = circuit.prove(Some(&spec), hash_function, config)?;
+    let proving_time = proving_start.elapsed();
+    let output = run.execution.output;
+    let n_steps = run.execution.steps.len();
+    let log_sizes = VmLogSizes::of(program, &run.execution);
+    if !json {
+        println!("  ✅ STARK proof generated and verified off-chain");
+        println!("   Output: {}", output);
+        println!("   Steps: {}", n_steps);
+        println!(
+            "   Log sizes: cpu {}, program {}, memory {}",
+            log_sizes.cpu, log_sizes.program, log_sizes.memory
+        );
+    }
+
+    let rpc_url = deployer.get_info().rpc_url.parse()?;
+    let wallet = EthereumWallet::from(deployer.get_signer());
+    let provider = ProviderBuilder::new().wallet(wallet).connect_http(rpc_url);
+
+    let input = proved.verifier_input;
+    let key: PinnedVerifyingKey::VerifyingKey = reencode(&VerifyingKey::of(&input))?;
+    let consumer =
+        TinyVmConsumer::deploy(&provider, deployment_result.verifier_address, key).await?;
+    info!(address = %consumer.address(), "TinyVmConsumer deployed");
+
+    let proof: ProofParser::Proof = reencode(&input.proof)?;
+    let params: STWOVerifier::VerificationParams = reencode(&input.verificationParams)?;
+
+    // Claimed sums that do not add up to zero mean the lookups do not balance
+    let mut unbalanced = params.clone();
+    let claimed = &mut unbalanced.componentParams[0].claimedSum;
+    claimed.first.real = (claimed.first.real + 1) % ((1 << 31) - 1);
+    let rejected = consumer
+        .proveRun(
+            output.0,
+            proof.clone(),
+            unbalanced,
+            input.treeRoots.clone(),
+            input.treeColumnLogSizes.clone(),
+            input.digest,
+        )
+        .call()
+        .await
+        .is_err();
+    if !json {
+        println!("\n🔒 Proof rejected with unbalanced lookups: {}", rejected);
+    }
+
+    let receipt = consumer
+        .proveRun(
+            output.0,
+            proof,
+            params,
+            input.treeRoots,
+            input.treeColumnLogSizes,
+            input.digest,
+        )
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+    let is_proven = consumer.isProvenOutput(output.0).call().await?;
+    let passed = receipt.status() && is_proven;
+
+    if json {
+        print_json(&example_result(
+            &deployment_result,
+            json!({
+                "consumer_address": consumer.address(),
+                "hash_function": format!("{:?}", hash_function),
+                "instructions": program.len(),
+                "output": output.0,
+                "steps": n_steps,
+                "log_sizes": {
+                    "cpu": log_sizes.cpu,
+                    "program": log_sizes.program,
+                    "memory": log_sizes.memory,
+                },
+                "unbalanced_rejected": rejected,
+                "proving_ms": proving_time.as_millis() as u64,
+            }),
+            &json!({
+                "gas_used": receipt.gas_used,
+                "tx_hash": receipt.transaction_hash,
+                "passed": passed,
+            }),
+        )?)?;
+    }
+    if !passed {
+        return Err("Tiny VM proof was rejected".into());
+    }
+    if json {
+        return Ok(());
+    }
+
+    println!("🎯 Output {} PROVEN for the pinned program!", output);
+    println!("\n📏 Benchmark");
+    println!("   Steps: {}", n_steps);
+    println!("   Proving time: {:.2?}", proving_time);
+    println!("   Gas used: {}", receipt.gas_used);
+
+    println!("\n🎉 Tiny VM verification example completed!");
+    Ok(())
+}
+
+/// Convert between the `contracts` bindings and this crate's bindings of the
+/// same Solidity type
+fn reencode<T, U>(value: &T) -> Result<U>
+where
+    T: SolValue,
+    U: SolValue + From<<U::SolType as SolType>::RustType>,
+{
+    Ok(U::abi_decode(&value.abi_encode())?)
+}
+
+/// Deploy the STWOVerifier contract on `network`, or on a local Anvil with a
+/// block gas limit
+/// high enough for multi-component proofs
+async fn deploy_verifier(
+    network: Option<&String>,
+) -> Result<(DeploymentResult, STWOVerifierDeployer)> {
+    let anvil_config = AnvilConfig {
+        gas_limit: "1000000000".to_string(),
+        fork_url: None,
+        ..AnvilConfig::default()
+    };
+
+    let deployer = STWOVerifierDeployer::for_network(network.map(String::as_str), anvil_config)?;
+    let result = deployer.deploy_or_attach().await?;
+
+    info!(
+        address = %result.verifier_address,
+        chain_id = result.chain_id,
+        "STWOVerifier ready"
+    );
+
+    Ok((result, deployer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unbalanced_lookups_rejected() -> Result<(), Box<dyn std::error::Error>> {
+        let deployer = STWOVerifierDeployer::with_anvil()?;
+        let deployment = deployer.deploy().await?;
+        let wallet = EthereumWallet::from(deployer.get_signer());
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .connect_http(deployer.get_info().rpc_url.parse()?);
+
+        let circuit = TinyVmCircuit;
+        let config = SolidityProverConfig::from(circuit.default_pcs_config());
+        let proved = circuit.prove(Some("10"), HashFunction::Keccak, config)?;
+        let output = proved.public_inputs[0];
+        let input = proved.verifier_input;
+
+        let key: PinnedVerifyingKey::VerifyingKey = reencode(&VerifyingKey::of(&input))?;
+        let consumer = TinyVmConsumer::deploy(&provider, deployment.verifier_address, key).await?;
+
+        // Shifting one claimed sum unbalances the lookups without touching the
+        // transcript
+        let mut unbalanced = input.clone();
+        let claimed = &mut unbalanced.verificationParams.componentParams[0].claimedSum;
+        claimed.first.real = (claimed.first.real + 1) % ((1 << 31) - 1);
+
+        let cases = [
+            (output, unbalanced, false),
+            (output + 1, input.clone(), false),
+            (output, input, true),
+        ];
+        for (output, input, expected) in cases {
+            let result = consumer
+                .proveRun(
+                    output,
+                    reencode(&input.proof)?,
+                    reencode(&input.verificationParams)?,
+                    input.treeRoots,
+                    input.treeColumnLogSizes,
+                    input.digest,
+                )
+                .call()
+                .await;
+            assert_eq!(result.is_ok(), expected);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use std::str::FromStr;
+
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use contracts::prover::TraceEvals;
+use num_traits::Zero;
+use stwo::core::channel::Channel;
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+
+use crate::air::{
+    components, gen_interaction_trace, gen_trace, VmComponent, VmEval, VmRelations, VmTrace,
+};
+use crate::assembler::{assemble, fibonacci_source};
+use crate::executor::{execute, Execution};
+use crate::isa::Instruction;
+
+/// Steps a program may run before it is rejected
+pub const DEFAULT_MAX_STEPS: usize = 65536;
+
+/// A program and its run, executed when parsed so a program that does not
+/// halt is rejected as an invalid input
+#[derive(Debug, Clone)]
+pub struct ProgramRun {
+    pub program: Vec<Instruction>,
+    pub execution: Execution,
+}
+
+impl ProgramRun {
+    pub fn new(program: Vec<Instruction>, max_steps: usize) -> Result<Self, String> {
+        let execution = execute(&program, max_steps)?;
+        Ok(Self { program, execution })
+    }
+}
+
+/// `PROGRAM[:MAX_STEPS]`, where `PROGRAM` is the index computed by the
+/// built-in Fibonacci program or the path of an assembly file
+impl FromStr for ProgramRun {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (program, max_steps) = match s.rsplit_once(':').map(|(p, n)| (p, n.parse())) {
+            Some((program, Ok(max_steps))) => (program, max_steps),
+            _ => (s, DEFAULT_MAX_STEPS),
+        };
+        let source = match program.parse::<u32>() {
+            Ok(0) => return Err("Fibonacci index must be at least 1".to_string()),
+            Ok(n) => fibonacci_source(n),
+            Err(_) => std::fs::read_to_string(program)
+                .map_err(|e| format!("Cannot read {}: {}", program, e))?,
+        };
+        Self::new(assemble(&source)?, max_steps)
+    }
+}
+
+/// Proves a run of a tiny VM program. The program is fixed by the
+/// preprocessed commitment and the output is a public input, mixed into the
+/// transcript.
+pub struct TinyVmCircuit;
+
+pub struct TinyVmMetadata {
+    /// Main trace columns, kept for the interaction trace
+    pub trace: VmTrace,
+    pub output: BaseField,
+    pub relations: VmRelations,
+    pub claimed_sums: [SecureField; 3],
+}
+
+impl SolidityCircuit for TinyVmCircuit {
+    type Eval = VmEval;
+    type Metadata = TinyVmMetadata;
+    type Input = ProgramRun;
+
+    fn name(&self) -> &'static str {
+        "tiny-vm"
+    }
+
+    fn default_input(&self) -> ProgramRun {
+        let program = assemble(&fibonacci_source(10)).expect("built-in program assembles");
+        ProgramRun::new(program, DEFAULT_MAX_STEPS).expect("built-in program halts")
+    }
+
+    fn components(&self, metadata: &TinyVmMetadata) -> Vec<VmComponent> {
+        components(
+            metadata.trace.log_sizes,
+            metadata.output,
+            &metadata.relations,
+            metadata.claimed_sums,
+        )
+    }
+
+    fn generate_trace(&self, input: &ProgramRun) -> CircuitTrace<TinyVmMetadata> {
+        let mut trace = gen_trace(&input.program, &input.execution);
+        let preprocessed = std::mem::take(&mut trace.preprocessed);
+        let main = trace.trace();
+        CircuitTrace {
+            metadata: TinyVmMetadata {
+                trace,
+                output: input.execution.output,
+                relations: VmRelations::dummy(),
+                claimed_sums: [SecureField::zero(); 3],
+            },
+            preprocessed,
+            trace: main,
+            interaction: vec![],
+        }
+    }
+
+    /// The claimed sums of an honest run add up to zero; `TinyVmConsumer`
+    /// checks it on-chain
+    fn draw_interaction(
+        &self,
+        metadata: &mut TinyVmMetadata,
+        channel: &mut impl Channel,
+    ) -> TraceEvals {
+        metadata.relations = VmRelations::draw(channel);
+        let (interaction, claimed_sums) =
+            gen_interaction_trace(&metadata.trace, &metadata.relations);
+        metadata.claimed_sums = claimed_sums;
+        interaction
+    }
+
+    fn public_inputs(&self, _input: &ProgramRun, metadata: &TinyVmMetadata) -> Vec<u32> {
+        vec![metadata.output.0]
+    }
+
+    fn binds_public_inputs(&self) -> bool {
+        true
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contracts::circuit::DynCircuit;
+    use contracts::hash::HashFunction;
+    use contracts::prover::SolidityProverConfig;
+
+    #[test]
+    fn test_parse_program_run() {
+        let run: ProgramRun = "10".parse().unwrap();
+        assert_eq!(run.execution.output, BaseField::from(55));
+        assert!("10:5"
+            .parse::<ProgramRun>()
+            .unwrap_err()
+            .contains("No halt"));
+        assert!("0".parse::<ProgramRun>().is_err());
+        assert!("missing.asm".parse::<ProgramRun>().is_err());
+    }
+
+    #[test]
+    fn test_prove_tiny_vm() {
+        let circuit = TinyVmCircuit;
+        let config = SolidityProverConfig::from(circuit.default_pcs_config());
+        let proved = circuit
+            .prove(Some("12"), HashFunction::Keccak, config)
+            .unwrap();
+        assert_eq!(proved.public_inputs, vec![144]);
+        // Preprocessed, main and interaction trees
+        assert_eq!(proved.verifier_input.treeRoots.len(), 3);
+    }
+}
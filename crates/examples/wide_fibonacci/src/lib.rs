@@ -0,0 +1 @@
+pub mod wide_fibonacci_circuit;
@@ -0,0 +1,268 @@
+use anyhow::Result;
+use clap::{Arg, Command};
+use contracts::circuit::DynCircuit;
+use contracts::hash::HashFunction;
+use contracts::prover::SolidityProverConfig;
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+use tracing::info;
+use verifier::deploy::{AnvilConfig, DeploymentResult, STWOVerifierDeployer};
+use verifier::logging::{self, example_result, json_arg, print_json};
+use verifier::network::network_arg;
+use verifier::submit::submit_verification;
+
+use wide_fibonacci::wide_fibonacci_circuit::{WideFibonacciCircuit, WideFibonacciInput};
+
+/// One point of the sweep
+struct Measurement {
+    input: WideFibonacciInput,
+    proving_time: Duration,
+    calldata_size: usize,
+    calldata_gas: u64,
+    gas_used: u64,
+    passed: bool,
+}
+
+/// Wide Fibonacci stress example: sweeps column counts and component log
+/// sizes and reports how verification gas scales
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Command::new("Wide Fibonacci STARK Verifier")
+        .version("1.0")
+        .about("Stress example measuring STWO verifier gas across trace widths and log sizes")
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .short('c')
+                .help("Comma-separated column counts to sweep (at least 3 each)")
+                .value_name("N,N,...")
+                .default_value("8,32,128"),
+        )
+        .arg(
+            Arg::new("log-sizes")
+                .long("log-sizes")
+                .short('l')
+                .help("Comma-separated component log sizes; every prefix of the list is one run")
+                .value_name("LOG,LOG,...")
+                .default_value("5,7,9"),
+        )
+        .arg(
+            Arg::new("hash")
+                .long("hash")
+                .help("Channel and Merkle hash function (keccak, blake2s)")
+                .value_name("HASH")
+                .default_value("keccak"),
+        )
+        .arg(
+            Arg::new("packed")
+                .long("packed")
+                .help("Submit the proofs through verifyPacked using the compact calldata encoding")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .help("Also write the measurements as CSV to this file")
+                .value_name("FILE"),
+        )
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if !json {
+        println!("📐 Wide Fibonacci STARK Verifier Stress Test");
+        println!("============================================");
+    }
+
+    let columns = matches.get_one::<String>("columns").unwrap();
+    let log_sizes = matches.get_one::<String>("log-sizes").unwrap();
+    let inputs = sweep(columns, log_sizes)?;
+    let hash_function: HashFunction = matches.get_one::<String>("hash").unwrap().parse()?;
+    if !json {
+        println!("\n📊 {} runs", inputs.len());
+        println!("   Hash function: {:?}", hash_function);
+    }
+
+    let (deployment_result, deployer) =
+        deploy_verifier(matches.get_one::<String>("network")).await?;
+
+    let circuit = WideFibonacciCircuit;
+    let config = SolidityProverConfig::from(circuit.default_pcs_config());
+    let mut measurements = Vec::new();
+    for input in inputs {
+        if !json {
+            println!(
+                "\n🔄 {} columns, log sizes {:?}",
+                input.n_columns, input.log_sizes
+            );
+        }
+        let proving_start = Instant::now();
+        let proved = circuit.prove(Some(&input_arg(&input)), hash_function, config)?;
+        let proving_time = proving_start.elapsed();
+
+        let report = submit_verification(
+            deployment_result.verifier_address,
+            proved.verifier_input,
+            &deployer,
+            matches.get_flag("packed"),
+        )
+        .await?;
+        measurements.push(Measurement {
+            input,
+            proving_time,
+            calldata_size: report.calldata_size,
+            calldata_gas: report.calldata_gas,
+            gas_used: report.gas_used,
+            passed: report.passed,
+        });
+    }
+
+    if let Some(path) = matches.get_one::<String>("report") {
+        std::fs::write(path, csv(&measurements))?;
+        info!(path = %path, "Report written");
+    }
+
+    let rejected = measurements.iter().filter(|m| !m.passed).count();
+    if json {
+        print_json(&example_result(
+            &deployment_result,
+            json!({
+                "hash_function": format!("{:?}", hash_function),
+                "runs": runs(&measurements),
+            }),
+            &json!({ "passed": rejected == 0, "rejected": rejected }),
+        )?)?;
+    } else {
+        println!("\n📏 Gas scaling");
+        print!("{}", table(&measurements));
+    }
+
+    if rejected > 0 {
+        return Err("Some proofs were rejected on-chain".into());
+    }
+    if !json {
+        println!("\n🎉 Wide Fibonacci stress test completed!");
+    }
+    Ok(())
+}
+
+/// Every column count paired with every non-empty prefix of the log sizes
+fn sweep(columns: &str, log_sizes: &str) -> Result<Vec<WideFibonacciInput>, String> {
+    let log_sizes: Vec<&str> = log_sizes.split(',').map(str::trim).collect();
+    let mut inputs = Vec::new();
+    for n_columns in columns.split(',').map(str::trim) {
+        for n_components in 1..=log_sizes.len() {
+            let arg = format!("{}:{}", n_columns, log_sizes[..n_components].join(","));
+            inputs.push(arg.parse()?);
+        }
+    }
+    Ok(inputs)
+}
+
+fn input_arg(input: &WideFibonacciInput) -> String {
+    let log_sizes: Vec<String> = input.log_sizes.iter().map(u32::to_string).collect();
+    format!("{}:{}", input.n_columns, log_sizes.join(","))
+}
+
+fn table(measurements: &[Measurement]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "   {:>7} {:>14} {:>9} {:>10} {:>13} {:>12} {:>9} {:>10}",
+        "columns",
+        "log sizes",
+        "cells",
+        "proving",
+        "calldata B",
+        "gas used",
+        "gas/cell",
+        "verified"
+    )
+    .unwrap();
+    for m in measurements {
+        let cells = m.input.n_cells();
+        writeln!(
+            out,
+            "   {:>7} {:>14} {:>9} {:>10.2?} {:>13} {:>12} {:>9.1} {:>10}",
+            m.input.n_columns,
+            format!("{:?}", m.input.log_sizes),
+            cells,
+            m.proving_time,
+            m.calldata_size,
+            m.gas_used,
+            m.gas_used as f64 / cells as f64,
+            if m.passed { "✅" } else { "❌" },
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn csv(measurements: &[Measurement]) -> String {
+    let mut out = String::from(
+        "columns,log_sizes,cells,proving_ms,calldata_bytes,calldata_gas,gas_used,passed\n",
+    );
+    for m in measurements {
+        let log_sizes: Vec<String> = m.input.log_sizes.iter().map(u32::to_string).collect();
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            m.input.n_columns,
+            log_sizes.join(" "),
+            m.input.n_cells(),
+            m.proving_time.as_millis(),
+            m.calldata_size,
+            m.calldata_gas,
+            m.gas_used,
+            m.passed
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Measurements as the `runs` array of the `--json` result
+fn runs(measurements: &[Measurement]) -> Value {
+    measurements
+        .iter()
+        .map(|m| {
+            json!({
+                "columns": m.input.n_columns,
+                "log_sizes": m.input.log_sizes,
+                "cells": m.input.n_cells(),
+                "proving_ms": m.proving_time.as_millis() as u64,
+                "calldata_size": m.calldata_size,
+                "calldata_gas": m.calldata_gas,
+                "gas_used": m.gas_used,
+                "passed": m.passed,
+            })
+        })
+        .collect()
+}
+
+/// Deploy the STWOVerifier contract on `network`, or on a local Anvil with a
+/// block gas limit
+/// high enough for the widest traces
+async fn deploy_verifier(
+    network: Option<&String>,
+) -> Result<(DeploymentResult, STWOVerifierDeployer)> {
+    let anvil_config = AnvilConfig {
+        gas_limit: "1000000000".to_string(),
+        fork_url: None,
+        ..AnvilConfig::default()
+    };
+
+    let deployer = STWOVerifierDeployer::for_network(network.map(String::as_str), anvil_config)?;
+    let result = deployer.deploy_or_attach().await?;
+
+    info!(
+        address = %result.verifier_address,
+        chain_id = result.chain_id,
+        "STWOVerifier ready"
+    );
+
+    Ok((result, deployer))
+}
@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use contracts::circuit::{CircuitTrace, SolidityCircuit};
+use num_traits::Zero;
+use stwo::core::fields::m31::BaseField;
+use stwo::core::fields::qm31::SecureField;
+use stwo::core::fri::FriConfig;
+use stwo::core::pcs::PcsConfig;
+use stwo::core::poly::circle::CanonicCoset;
+use stwo::core::ColumnVec;
+use stwo::prover::backend::simd::m31::LOG_N_LANES;
+use stwo::prover::backend::simd::SimdBackend;
+use stwo::prover::backend::{Col, Column};
+use stwo::prover::poly::circle::CircleEvaluation;
+use stwo::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+};
+
+/// Every row is an independent Fibonacci sequence laid out across
+/// `n_columns` columns: column `i` is the sum of columns `i - 2` and `i - 1`.
+#[derive(Clone)]
+pub struct WideFibonacciEval {
+    pub log_n_rows: u32,
+    pub n_columns: usize,
+}
+
+impl FrameworkEval for WideFibonacciEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_n_rows + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let mut a = eval.next_trace_mask();
+        let mut b = eval.next_trace_mask();
+        for _ in 2..self.n_columns {
+            let c = eval.next_trace_mask();
+            eval.add_constraint(c.clone() - (a + b.clone()));
+            a = b;
+            b = c;
+        }
+        eval
+    }
+}
+
+pub type WideFibonacciComponent = FrameworkComponent<WideFibonacciEval>;
+
+/// Component shapes, parsed from `COLUMNS:LOG_SIZE[,LOG_SIZE...]`, e.g. `64:5,7,9`
+/// for three 64-column components of 2^5, 2^7 and 2^9 rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WideFibonacciInput {
+    pub n_columns: usize,
+    pub log_sizes: Vec<u32>,
+}
+
+impl WideFibonacciInput {
+    /// Trace cells over all components
+    pub fn n_cells(&self) -> usize {
+        self.log_sizes
+            .iter()
+            .map(|&log_size| self.n_columns << log_size)
+            .sum()
+    }
+}
+
+impl FromStr for WideFibonacciInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (n_columns, log_sizes) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Expected COLUMNS:LOG_SIZE[,LOG_SIZE...], got {}", s))?;
+        let n_columns: usize = n_columns
+            .parse()
+            .map_err(|e| format!("Invalid column count {}: {}", n_columns, e))?;
+        if n_columns < 3 {
+            return Err("At least 3 columns are needed for one constraint".to_string());
+        }
+        let log_sizes = log_sizes
+            .split(',')
+            .map(|log_size| {
+                let log_size: u32 = log_size
+                    .trim()
+                    .parse()
+                    .map_err(|e| format!("Invalid log size {}: {}", log_size, e))?;
+                if log_size < LOG_N_LANES {
+                    return Err(format!("Log size must be at least {}", LOG_N_LANES));
+                }
+                Ok(log_size)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            n_columns,
+            log_sizes,
+        })
+    }
+}
+
+/// Columns of one component; row `r` starts the sequence from `(1, r)`
+pub fn gen_trace(
+    log_size: u32,
+    n_columns: usize,
+) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+    let n_rows = 1 << log_size;
+    let mut cols: Vec<Col<SimdBackend, BaseField>> = (0..n_columns)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(n_rows))
+        .collect();
+    for row in 0..n_rows {
+        let mut a = BaseField::from(1u32);
+        let mut b = BaseField::from(row as u32);
+        cols[0].set(row, a);
+        cols[1].set(row, b);
+        for col in cols.iter_mut().skip(2) {
+            (a, b) = (b, a + b);
+            col.set(row, b);
+        }
+    }
+
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    cols.into_iter()
+        .map(|col| CircleEvaluation::new(domain, col))
+        .collect()
+}
+
+/// One wide Fibonacci component per requested log size
+pub struct WideFibonacciCircuit;
+
+impl SolidityCircuit for WideFibonacciCircuit {
+    type Eval = WideFibonacciEval;
+    type Metadata = WideFibonacciInput;
+    type Input = WideFibonacciInput;
+
+    fn name(&self) -> &'static str {
+        "wide-fibonacci"
+    }
+
+    fn default_input(&self) -> WideFibonacciInput {
+        WideFibonacciInput {
+            n_columns: 32,
+            log_sizes: vec![5, 7],
+        }
+    }
+
+    fn components(&self, input: &WideFibonacciInput) -> Vec<WideFibonacciComponent> {
+        let allocator = &mut TraceLocationAllocator::default();
+        input
+            .log_sizes
+            .iter()
+            .map(|&log_n_rows| {
+                WideFibonacciComponent::new(
+                    allocator,
+                    WideFibonacciEval {
+                        log_n_rows,
+                        n_columns: input.n_columns,
+                    },
+                    SecureField::zero(),
+                )
+            })
+            .collect()
+    }
+
+    fn generate_trace(&self, input: &WideFibonacciInput) -> CircuitTrace<WideFibonacciInput> {
+        let trace = input
+            .log_sizes
+            .iter()
+            .flat_map(|&log_size| gen_trace(log_size, input.n_columns))
+            .collect();
+        CircuitTrace {
+            metadata: input.clone(),
+            preprocessed: vec![],
+            trace,
+            interaction: vec![],
+        }
+    }
+
+    /// The stress circuit attests to no particular value
+    fn public_inputs(
+        &self,
+        _input: &WideFibonacciInput,
+        _metadata: &WideFibonacciInput,
+    ) -> Vec<u32> {
+        vec![]
+    }
+
+    fn default_pcs_config(&self) -> PcsConfig {
+        PcsConfig {
+            pow_bits: 10,
+            fri_config: FriConfig::new(1, 1, 3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        let input: WideFibonacciInput = "64:5,7,9".parse().unwrap();
+        assert_eq!(input.n_columns, 64);
+        assert_eq!(input.log_sizes, vec![5, 7, 9]);
+        assert_eq!(input.n_cells(), 64 * (32 + 128 + 512));
+
+        assert!("64".parse::<WideFibonacciInput>().is_err());
+        assert!("2:5".parse::<WideFibonacciInput>().is_err());
+        assert!("8:3".parse::<WideFibonacciInput>().is_err());
+    }
+
+    #[test]
+    fn test_gen_trace() {
+        let trace = gen_trace(LOG_N_LANES, 10);
+        assert_eq!(trace.len(), 10);
+        // Row 1 starts from (1, 1): the last column holds F(10) = 55
+        assert_eq!(trace[9].values.at(1), BaseField::from(55u32));
+    }
+}
@@ -0,0 +1,299 @@
+//! Forge artifacts of the verifier contracts and library linking.
+//!
+//! STWOVerifier is too large for the EIP-170 code size limit on its own, so the
+//! FRI, Merkle and OODS code lives in public libraries under
+//! `contracts/verifier/modules`. Their functions are reached with DELEGATECALL,
+//! and the compiler leaves a `__$<hash>$__` placeholder in STWOVerifier's
+//! bytecode for each library address. [`Artifact::linked_bytecode`] fills them
+//! in from the artifact's `linkReferences`.
+
+use std::collections::BTreeMap;
+
+use alloy::primitives::{Address, Bytes};
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+
+/// Maximum runtime code size on mainnet and most L2s (EIP-170)
+pub const EIP170_CODE_SIZE_LIMIT: usize = 24576;
+
+/// A contract compiled by forge into `out/`
+#[derive(Debug, Clone, Copy)]
+pub struct Artifact {
+    pub name: &'static str,
+    json: &'static str,
+}
+
+/// Libraries STWOVerifier links against, in deployment order
+pub const VERIFIER_LIBRARIES: [Artifact; 4] = [
+    Artifact {
+        name: "FriAnswersModule",
+        json: include_str!("../../../out/FriAnswersModule.sol/FriAnswersModule.json"),
+    },
+    Artifact {
+        name: "FriDecommitModule",
+        json: include_str!("../../../out/FriDecommitModule.sol/FriDecommitModule.json"),
+    },
+    Artifact {
+        name: "MerkleModule",
+        json: include_str!("../../../out/MerkleModule.sol/MerkleModule.json"),
+    },
+    Artifact {
+        name: "OodsModule",
+        json: include_str!("../../../out/OodsModule.sol/OodsModule.json"),
+    },
+];
+
+/// The verifier itself, deployed after [`VERIFIER_LIBRARIES`]
+pub const STWO_VERIFIER: Artifact = Artifact {
+    name: "STWOVerifier",
+    json: include_str!("../../../out/StwoVerifier.sol/STWOVerifier.json"),
+};
+
+/// STWOVerifier with the UUPS upgrade entry point, the implementation behind
+/// [`UUPS_VERIFIER_PROXY`]
+pub const UPGRADEABLE_STWO_VERIFIER: Artifact = Artifact {
+    name: "UpgradeableSTWOVerifier",
+    json: include_str!("../../../out/UpgradeableSTWOVerifier.sol/UpgradeableSTWOVerifier.json"),
+};
+
+/// Proxy whose upgrades are authorized by the implementation
+pub const UUPS_VERIFIER_PROXY: Artifact = Artifact {
+    name: "UUPSVerifierProxy",
+    json: include_str!("../../../out/UUPSVerifierProxy.sol/UUPSVerifierProxy.json"),
+};
+
+/// Proxy whose upgrades are authorized by its admin
+pub const TRANSPARENT_VERIFIER_PROXY: Artifact = Artifact {
+    name: "TransparentVerifierProxy",
+    json: include_str!("../../../out/TransparentVerifierProxy.sol/TransparentVerifierProxy.json"),
+};
+
+/// Example consumer proving Merkle membership against a stored root
+pub const MERKLE_ROOT_CONSUMER: Artifact = Artifact {
+    name: "MerkleRootConsumer",
+    json: include_str!("../../../out/MerkleRootConsumer.sol/MerkleRootConsumer.json"),
+};
+
+/// Example consumer recording the results of batched proofs
+pub const BATCH_RESULT_REGISTRY: Artifact = Artifact {
+    name: "BatchResultRegistry",
+    json: include_str!("../../../out/BatchResultRegistry.sol/BatchResultRegistry.json"),
+};
+
+/// Example consumer recording the outputs of tiny VM runs
+pub const TINY_VM_CONSUMER: Artifact = Artifact {
+    name: "TinyVmConsumer",
+    json: include_str!("../../../out/TinyVmConsumer.sol/TinyVmConsumer.json"),
+};
+
+/// Location of one library address inside a bytecode, in bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkReference {
+    pub library: String,
+    pub start: usize,
+    pub length: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactJson {
+    bytecode: BytecodeJson,
+    deployed_bytecode: BytecodeJson,
+    /// Solc metadata: compiler version, settings and source hashes
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BytecodeJson {
+    object: String,
+    /// Source file => library name => offsets
+    #[serde(default)]
+    link_references: BTreeMap<String, BTreeMap<String, Vec<OffsetJson>>>,
+    /// AST id => offsets, only present in `deployedBytecode`
+    #[serde(default)]
+    immutable_references: BTreeMap<String, Vec<OffsetJson>>,
+}
+
+#[derive(Deserialize)]
+struct OffsetJson {
+    start: usize,
+    length: usize,
+}
+
+impl BytecodeJson {
+    fn hex(&self) -> &str {
+        self.object.trim_start_matches("0x")
+    }
+
+    fn link_references(&self) -> Vec<LinkReference> {
+        self.link_references
+            .values()
+            .flatten()
+            .flat_map(|(library, offsets)| {
+                offsets.iter().map(|offset| LinkReference {
+                    library: library.clone(),
+                    start: offset.start,
+                    length: offset.length,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Artifact {
+    fn parse(&self) -> Result<ArtifactJson> {
+        serde_json::from_str(self.json)
+            .map_err(|e| anyhow!("Invalid artifact for {}: {}", self.name, e))
+    }
+
+    /// Creation bytecode as hex, with placeholders for unlinked libraries
+    pub fn bytecode_hex(&self) -> Result<String> {
+        Ok(self.parse()?.bytecode.hex().to_string())
+    }
+
+    /// Runtime bytecode as hex, with placeholders for unlinked libraries
+    pub fn deployed_bytecode_hex(&self) -> Result<String> {
+        Ok(self.parse()?.deployed_bytecode.hex().to_string())
+    }
+
+    /// Library addresses the creation bytecode needs
+    pub fn link_references(&self) -> Result<Vec<LinkReference>> {
+        Ok(self.parse()?.bytecode.link_references())
+    }
+
+    /// Library addresses the runtime bytecode embeds
+    pub fn deployed_link_references(&self) -> Result<Vec<LinkReference>> {
+        Ok(self.parse()?.deployed_bytecode.link_references())
+    }
+
+    /// `(start, length)` in bytes of every immutable the constructor writes
+    /// into the runtime bytecode
+    pub fn immutable_references(&self) -> Result<Vec<(usize, usize)>> {
+        Ok(self
+            .parse()?
+            .deployed_bytecode
+            .immutable_references
+            .values()
+            .flatten()
+            .map(|offset| (offset.start, offset.length))
+            .collect())
+    }
+
+    /// Whether the artifact is a library with public functions. Their runtime
+    /// code starts with `PUSH20 <address>`, zero in the artifact and set to the
+    /// library's own address on deployment, to reject direct calls.
+    pub fn is_library(&self) -> Result<bool> {
+        let prefix = format!("73{}3014", "00".repeat(Address::len_bytes()));
+        Ok(self.deployed_bytecode_hex()?.starts_with(&prefix))
+    }
+
+    /// Runtime code size in bytes, the quantity EIP-170 limits
+    pub fn deployed_size(&self) -> Result<usize> {
+        Ok(self.deployed_bytecode_hex()?.len() / 2)
+    }
+
+    /// Solc metadata of the compilation that produced the artifact
+    pub fn metadata(&self) -> Result<serde_json::Value> {
+        self.parse()?
+            .metadata
+            .ok_or_else(|| anyhow!("Artifact for {} has no metadata", self.name))
+    }
+
+    /// Source file the contract is defined in, e.g. `contracts/verifier/StwoVerifier.sol`
+    pub fn source_path(&self) -> Result<String> {
+        self.metadata()?["settings"]["compilationTarget"]
+            .as_object()
+            .and_then(|target| target.keys().next().cloned())
+            .ok_or_else(|| anyhow!("Artifact for {} has no compilation target", self.name))
+    }
+
+    /// `path:Name`, the name explorers and solc use for the contract
+    pub fn qualified_name(&self) -> Result<String> {
+        Ok(format!("{}:{}", self.source_path()?, self.name))
+    }
+
+    /// Creation bytecode with every library address filled in
+    pub fn linked_bytecode(&self, libraries: &BTreeMap<String, Address>) -> Result<Bytes> {
+        link(&self.bytecode_hex()?, &self.link_references()?, libraries)
+            .map_err(|e| anyhow!("Cannot link {}: {}", self.name, e))
+    }
+}
+
+/// Write the address of each referenced library into `bytecode_hex`
+pub fn link(
+    bytecode_hex: &str,
+    references: &[LinkReference],
+    libraries: &BTreeMap<String, Address>,
+) -> Result<Bytes> {
+    let mut linked = bytecode_hex.to_string();
+    for reference in references {
+        let address = libraries
+            .get(&reference.library)
+            .ok_or_else(|| anyhow!("No address for library {}", reference.library))?;
+        if reference.length != Address::len_bytes() {
+            bail!(
+                "Link reference to {} spans {} bytes",
+                reference.library,
+                reference.length
+            );
+        }
+        let range = 2 * reference.start..2 * (reference.start + reference.length);
+        if range.end > linked.len() {
+            bail!("Link reference to {} is out of bounds", reference.library);
+        }
+        linked.replace_range(range, &hex::encode(address));
+    }
+    if linked.contains("__$") {
+        bail!("Bytecode has placeholders without a link reference");
+    }
+    Ok(hex::decode(linked)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_placeholders() {
+        let placeholder = format!("__${}$__", "0".repeat(34));
+        let bytecode = format!("60{}60{}", placeholder, placeholder);
+        let references = vec![
+            LinkReference {
+                library: "Lib".to_string(),
+                start: 1,
+                length: 20,
+            },
+            LinkReference {
+                library: "Lib".to_string(),
+                start: 22,
+                length: 20,
+            },
+        ];
+        let address = Address::repeat_byte(0xab);
+        let libraries = BTreeMap::from([("Lib".to_string(), address)]);
+
+        let linked = link(&bytecode, &references, &libraries).unwrap();
+        assert_eq!(linked.len(), 42);
+        assert_eq!(&linked[1..21], address.as_slice());
+        assert_eq!(&linked[22..42], address.as_slice());
+
+        assert!(link(&bytecode, &references, &BTreeMap::new()).is_err());
+        assert!(link(&bytecode, &references[..1], &libraries).is_err());
+    }
+
+    #[test]
+    fn test_verifier_links_against_every_library() {
+        let referenced: Vec<String> = STWO_VERIFIER
+            .link_references()
+            .unwrap()
+            .into_iter()
+            .map(|reference| reference.library)
+            .collect();
+        for library in VERIFIER_LIBRARIES {
+            assert!(library.link_references().unwrap().is_empty());
+            assert!(referenced.contains(&library.name.to_string()));
+        }
+    }
+}
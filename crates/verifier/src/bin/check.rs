@@ -0,0 +1,134 @@
+use alloy::{
+    primitives::Address,
+    providers::{Provider, ProviderBuilder},
+};
+use anyhow::{anyhow, Result};
+use clap::{Arg, Command};
+use serde_json::json;
+use std::path::Path;
+use tracing::info;
+use verifier::check::{check_deployment, CodeStatus};
+use verifier::explorer::{find_solc, self_check};
+use verifier::logging::{self, json_arg, print_json};
+use verifier::network::{load_network, network_arg, NetworkProfile};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = Command::new("STWO Verifier Check")
+        .version("1.0")
+        .about("Compares a deployed STWO Verifier and its libraries with the compiled artifacts")
+        .arg(
+            Arg::new("address")
+                .long("address")
+                .help("Address of the deployed STWOVerifier (default: the network's verifier_address)")
+                .value_name("ADDRESS"),
+        )
+        .arg(
+            Arg::new("rpc-url")
+                .long("rpc-url")
+                .help("RPC endpoint, defaults to ETH_RPC_URL or a local node")
+                .value_name("URL"),
+        )
+        .arg(network_arg())
+        .arg(
+            Arg::new("self-check")
+                .long("self-check")
+                .help("Recompile the explorer payloads in DIR with solc instead, without a node")
+                .value_name("DIR"),
+        )
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    if let Some(dir) = matches.get_one::<String>("self-check") {
+        let solc = find_solc();
+        info!(solc = %solc.display(), "Recompiling explorer payloads");
+        let checks = self_check(Path::new(dir), &solc)?;
+        let reproduced = checks.iter().all(|check| check.matches());
+        if json {
+            print_json(&json!({ "checks": checks, "reproduced": reproduced }))?;
+        } else {
+            for check in &checks {
+                if check.matches() {
+                    println!("   ✅ {}: {:?}", check.contract, check.recompiled);
+                } else {
+                    println!(
+                        "   ❌ {}: expected {:?}, recompiled {:?}",
+                        check.contract, check.expected, check.recompiled
+                    );
+                }
+            }
+        }
+        if !reproduced {
+            anyhow::bail!("Recompiled code differs from the payloads");
+        }
+        if !json {
+            println!(
+                "\n🎉 {} payloads reproduce the deployed code!",
+                checks.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let profile = match matches.get_one::<String>("network") {
+        Some(name) => load_network(name)?,
+        None => NetworkProfile::default(),
+    };
+    let verifier_address: Address = match matches.get_one::<String>("address") {
+        Some(address) => address.parse()?,
+        None => profile
+            .verifier_address
+            .ok_or_else(|| anyhow!("Pass --address or a --network with verifier_address"))?,
+    };
+    let rpc_url = matches
+        .get_one::<String>("rpc-url")
+        .cloned()
+        .or(profile.rpc_url)
+        .or_else(|| std::env::var("ETH_RPC_URL").ok())
+        .unwrap_or_else(|| "http://127.0.0.1:8545".to_string());
+
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+    let chain_id = provider.get_chain_id().await?;
+    info!(rpc_url = %rpc_url, chain_id, "Checking STWOVerifier deployment");
+    if let Some(expected) = profile.chain_id.filter(|&expected| expected != chain_id) {
+        return Err(anyhow!(
+            "Network expects chain {}, connected to chain {}",
+            expected,
+            chain_id
+        ));
+    }
+
+    let report = check_deployment(&provider, verifier_address).await?;
+
+    if json {
+        print_json(&json!({
+            "verifier_address": verifier_address,
+            "chain_id": chain_id,
+            "contracts": report.contracts,
+            "intact": report.is_intact(),
+        }))?;
+    } else {
+        println!("\n📋 Results:");
+        for contract in &report.contracts {
+            let icon = match contract.status {
+                CodeStatus::Match => "✅",
+                CodeStatus::Missing => "⚠️ ",
+                CodeStatus::Mismatch { .. } => "❌",
+            };
+            println!(
+                "   {} {} at {:?}: {}",
+                icon, contract.name, contract.address, contract.status
+            );
+        }
+    }
+
+    if !report.is_intact() {
+        anyhow::bail!("Deployment does not match the compiled artifacts");
+    }
+    if !json {
+        println!("\n🎉 Deployment matches the compiled artifacts!");
+    }
+    Ok(())
+}
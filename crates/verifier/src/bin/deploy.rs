@@ -1,6 +1,16 @@
 use anyhow::Result;
 use clap::{Arg, Command};
+use serde_json::json;
+use std::path::Path;
+use tracing::info;
 use verifier::deploy::{STWOVerifierDeployer, AnvilConfig};
+use verifier::manifest::DeploymentManifest;
+use verifier::proxy::ProxyKind;
+use verifier::explorer::write_payloads;
+use verifier::gas::{parse_gwei, GasConfig};
+use verifier::logging::{self, json_arg, print_json};
+use verifier::network::{load_network, network_arg, NetworkProfile};
+use verifier::signer::{SignerConfig, SignerSource};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,7 +29,7 @@ async fn main() -> Result<()> {
                 .long("code-size-limit")
                 .help("Code size limit for contracts")
                 .value_name("BYTES")
-                .default_value("51000"),
+                .default_value("24576"),
         )
         .arg(
             Arg::new("gas-limit")
@@ -28,13 +38,61 @@ async fn main() -> Result<()> {
                 .value_name("GAS")
                 .default_value("99999999999999"),
         )
+        .arg(
+            Arg::new("existing")
+                .long("existing")
+                .help("Reuse the verifier at this address if its code matches the artifacts")
+                .value_name("ADDRESS"),
+        )
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .help("Deploy behind an upgradeable proxy (uups, transparent)")
+                .value_name("KIND"),
+        )
+        .arg(
+            Arg::new("owner")
+                .long("owner")
+                .help("Account allowed to upgrade the proxy (default: deployer)")
+                .value_name("ADDRESS"),
+        )
+        .arg(
+            Arg::new("manifest")
+                .long("manifest")
+                .help("Write the deployment manifest to this JSON file")
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::new("rpc-url")
+                .long("rpc-url")
+                .help("Deploy to this node instead of a new Anvil instance; needs a signer option")
+                .value_name("URL"),
+        )
+        .args(SignerSource::args())
+        .args(GasConfig::args())
+        .arg(network_arg())
+        .arg(
+            Arg::new("explorer-dir")
+                .long("explorer-dir")
+                .help("Write Standard-JSON inputs and constructor args for explorer verification here")
+                .value_name("DIR"),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .help("Simulate the deployment on a fork and report gas, cost, code sizes and addresses")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("keep-running")
                 .long("keep-running")
                 .help("Keep Anvil running after deployment")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(json_arg())
         .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
 
     // Create Anvil configuration from command line args
     let anvil_config = AnvilConfig {
@@ -45,35 +103,130 @@ async fn main() -> Result<()> {
         code_size_limit: matches.get_one::<String>("code-size-limit")
             .unwrap()
             .parse()
-            .unwrap_or(51000),
+            .unwrap_or(24576),
         gas_limit: matches.get_one::<String>("gas-limit")
             .unwrap()
             .clone(),
         fork_url: std::env::var("ETH_RPC_URL").ok(),
     };
 
-    println!("🔧 Starting deployment with configuration:");
-    println!("   Block time: {} seconds", anvil_config.block_time);
-    println!("   Code size limit: {} bytes", anvil_config.code_size_limit);
-    println!("   Gas limit: {}", anvil_config.gas_limit);
-    if let Some(ref fork_url) = anvil_config.fork_url {
-        println!("   Fork URL: {}", fork_url);
+    info!(
+        block_time = anvil_config.block_time,
+        code_size_limit = anvil_config.code_size_limit,
+        gas_limit = %anvil_config.gas_limit,
+        fork_url = anvil_config.fork_url.as_deref(),
+        "Starting deployment"
+    );
+
+    // Create deployer and deploy; command line options override the profile
+    let mut profile = match matches.get_one::<String>("network") {
+        Some(name) => load_network(name)?,
+        None => NetworkProfile::default(),
+    };
+    if let Some(rpc_url) = matches.get_one::<String>("rpc-url") {
+        profile.rpc_url = Some(rpc_url.clone());
+    }
+    let signer = SignerConfig::from_matches(&matches);
+    if signer.source()?.is_some() {
+        profile.signer = Some(signer);
+    }
+    profile.gas = Some(
+        profile
+            .gas
+            .unwrap_or_default()
+            .merge(GasConfig::from_matches(&matches)),
+    );
+    let deployer = STWOVerifierDeployer::from_profile(&profile, anvil_config)?;
+
+    if matches.get_flag("dry-run") {
+        let proxy = matches
+            .get_one::<String>("proxy")
+            .map(|kind| kind.parse::<ProxyKind>())
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let owner = matches
+            .get_one::<String>("owner")
+            .map(|owner| owner.parse())
+            .transpose()?;
+        let gas_price = matches
+            .get_one::<String>("gas-price")
+            .map(|gwei| parse_gwei(gwei))
+            .transpose()?;
+        let report = deployer.dry_run(proxy, owner, gas_price).await?;
+        if json {
+            print_json(&json!({
+                "dry_run": report,
+                "total_gas": report.total_gas(),
+                "total_cost": report.total_cost(),
+                "within_eip170": report.within_eip170(),
+                "funded": report.is_funded(),
+            }))?;
+        } else {
+            println!("\n{}", report);
+        }
+        deployer.stop_anvil();
+        if !report.within_eip170() {
+            anyhow::bail!("A contract exceeds the EIP-170 limit of 24576 bytes");
+        }
+        return Ok(());
+    }
+
+    let result = match (
+        matches.get_one::<String>("proxy"),
+        matches.get_one::<String>("existing"),
+    ) {
+        (Some(kind), _) => {
+            let kind: ProxyKind = kind.parse().map_err(anyhow::Error::msg)?;
+            let owner = matches
+                .get_one::<String>("owner")
+                .map(|owner| owner.parse())
+                .transpose()?;
+            deployer.deploy_proxied(kind, owner).await?
+        }
+        (None, Some(existing)) => deployer.deploy_unless_present(existing.parse()?).await?,
+        (None, None) => deployer.deploy_or_attach().await?,
+    };
+
+    if let Some(path) = matches.get_one::<String>("manifest") {
+        DeploymentManifest::from_result(&result).save(Path::new(path))?;
+        info!(path = %path, "Manifest written");
     }
 
-    // Create deployer and deploy
-    let deployer = STWOVerifierDeployer::with_anvil_config(anvil_config)?;
-    let result = deployer.deploy().await?;
-    
-    println!("\n🎉 Deployment completed successfully!");
-    println!("📋 Results:");
-    println!("   Contract Address: {:?}", result.verifier_address);
-    if let Some(chain_id) = result.chain_id {
-        println!("   Chain ID: {}", chain_id);
+    if let Some(dir) = matches.get_one::<String>("explorer-dir") {
+        write_payloads(Path::new(dir), &result)?;
     }
-    if let Some(block_number) = result.block_number {
-        println!("   Block Number: {}", block_number);
+
+    if json {
+        print_json(&result)?;
+    } else {
+        println!("\n🎉 Deployment completed successfully!");
+        println!("📋 Results:");
+        println!("   Contract Address: {:?}", result.verifier_address);
+        if let Some(ref proxy) = result.proxy {
+            println!("   Proxy kind: {}", proxy.kind);
+            println!("   Implementation: {:?}", proxy.implementation);
+            println!("   Owner: {:?}", proxy.owner);
+        }
+        for (library, address) in &result.libraries {
+            println!("   {}: {:?}", library, address);
+        }
+        for tx in &result.transactions {
+            println!("   {} tx: {:?} ({} gas)", tx.name, tx.tx_hash, tx.gas_used);
+        }
+        if let Some(chain_id) = result.chain_id {
+            println!("   Chain ID: {}", chain_id);
+        }
+        if let Some(block_number) = result.block_number {
+            println!("   Block Number: {}", block_number);
+        }
+        if let Some(path) = matches.get_one::<String>("manifest") {
+            println!("   Manifest: {}", path);
+        }
+        if let Some(dir) = matches.get_one::<String>("explorer-dir") {
+            println!("   Explorer payloads: {}", dir);
+        }
     }
-    
+
     if matches.get_flag("keep-running") {
         // Keep Anvil running until user stops it
         deployer.wait_for_shutdown().await?;
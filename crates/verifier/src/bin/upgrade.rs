@@ -0,0 +1,157 @@
+use alloy::{
+    network::EthereumWallet,
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{anyhow, Result};
+use clap::{Arg, Command};
+use serde_json::json;
+use std::path::Path;
+use tracing::info;
+use verifier::check::check_implementation;
+use verifier::fixture::ProofFixture;
+use verifier::gas::GasConfig;
+use verifier::logging::{self, json_arg, print_json};
+use verifier::manifest::DeploymentManifest;
+use verifier::network::{load_network, network_arg, NetworkProfile};
+use verifier::proxy::upgrade_proxy;
+use verifier::signer::{SignerConfig, SignerSource};
+use verifier::tx::TxOptions;
+
+/// Anvil's default account #0, as in script/DeploySTWOVerifier.s.sol
+const DEFAULT_PRIVATE_KEY: &str =
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = Command::new("STWO Verifier Upgrade")
+        .version("1.0")
+        .about(
+            "Upgrades a proxied STWO Verifier once the new implementation accepts a proof fixture",
+        )
+        .arg(
+            Arg::new("manifest")
+                .long("manifest")
+                .help("Deployment manifest written by deploy --proxy; updated in place")
+                .value_name("PATH")
+                .required(true),
+        )
+        .arg(
+            Arg::new("fixture")
+                .long("fixture")
+                .help("Proof fixture the new implementation must accept")
+                .value_name("PATH")
+                .required(true),
+        )
+        .arg(
+            Arg::new("rpc-url")
+                .long("rpc-url")
+                .help("RPC endpoint, defaults to ETH_RPC_URL or a local node")
+                .value_name("URL"),
+        )
+        .args(SignerSource::args())
+        .args(GasConfig::args())
+        .arg(network_arg())
+        .arg(json_arg())
+        .get_matches();
+    let json = matches.get_flag("json");
+    logging::init(json)?;
+
+    let manifest_path = Path::new(matches.get_one::<String>("manifest").unwrap());
+    let mut manifest = DeploymentManifest::load(manifest_path)?;
+    let fixture = ProofFixture::load(Path::new(matches.get_one::<String>("fixture").unwrap()))?;
+    let proxy = manifest
+        .proxy
+        .clone()
+        .ok_or_else(|| anyhow!("Manifest {} has no proxy", manifest_path.display()))?;
+
+    // Command line options first, then the network profile
+    let profile = match matches.get_one::<String>("network") {
+        Some(name) => load_network(name)?,
+        None => NetworkProfile::default(),
+    };
+    let rpc_url = matches
+        .get_one::<String>("rpc-url")
+        .cloned()
+        .or(profile.rpc_url)
+        .or_else(|| std::env::var("ETH_RPC_URL").ok())
+        .unwrap_or_else(|| "http://127.0.0.1:8545".to_string());
+    // Signer options, then the profile's signer, then PRIVATE_KEY like the forge script
+    let source = match SignerConfig::from_matches(&matches).source()? {
+        Some(source) => Some(source),
+        None => match &profile.signer {
+            Some(signer) => signer.source()?,
+            None => None,
+        },
+    };
+    let signer: PrivateKeySigner = match source {
+        Some(source) => source.load()?,
+        None => std::env::var("PRIVATE_KEY")
+            .unwrap_or_else(|_| DEFAULT_PRIVATE_KEY.to_string())
+            .parse()?,
+    };
+
+    info!(
+        rpc_url = %rpc_url,
+        proxy = %proxy.address,
+        kind = %proxy.kind,
+        current = %proxy.implementation,
+        signer = %signer.address(),
+        "Upgrading STWO Verifier proxy"
+    );
+
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(rpc_url.parse()?);
+    if let Some(chain_id) = manifest.chain_id.or(profile.chain_id) {
+        let connected = provider.get_chain_id().await?;
+        if connected != chain_id {
+            return Err(anyhow!(
+                "Manifest is for chain {}, connected to chain {}",
+                chain_id,
+                connected
+            ));
+        }
+    }
+
+    let gas = profile
+        .gas
+        .unwrap_or_default()
+        .merge(GasConfig::from_matches(&matches));
+    let options = TxOptions {
+        confirmations: profile.confirmations.unwrap_or(1),
+        gas: gas.options()?,
+    };
+    let record = upgrade_proxy(&provider, &proxy, &fixture, &options).await?;
+    let report = check_implementation(
+        &provider,
+        &proxy.kind.implementation(),
+        record.implementation,
+    )
+    .await?;
+    if !report.is_intact() {
+        return Err(anyhow!(
+            "New implementation does not match the compiled artifacts"
+        ));
+    }
+
+    manifest.record_upgrade(record.clone())?;
+    manifest.save(manifest_path)?;
+
+    if json {
+        print_json(&json!({
+            "proxy": proxy.address,
+            "upgrade": record,
+            "implementations": manifest.history.len(),
+        }))?;
+    } else {
+        println!("\n🎉 Upgrade completed successfully!");
+        println!("   Implementation: {:?}", record.implementation);
+        if let Some(tx_hash) = record.tx_hash {
+            println!("   Upgrade tx: {:?}", tx_hash);
+        }
+        println!("   Implementations so far: {}", manifest.history.len());
+        println!("   Manifest: {}", manifest_path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! On-chain bytecode integrity of a verifier deployment.
+//!
+//! The runtime code returned by `eth_getCode` is compared with the artifact's
+//! `deployedBytecode`. Bytes the artifact cannot know are masked on both sides
+//! before comparing: immutables written by the constructor, library addresses
+//! linked into STWOVerifier, and the self-address libraries push to reject
+//! direct calls. The library addresses are read back from the verifier's code,
+//! so a single verifier address is enough to audit the whole deployment.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::artifact::{Artifact, STWO_VERIFIER, VERIFIER_LIBRARIES};
+
+/// Outcome of comparing one contract's on-chain code with its artifact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeStatus {
+    /// Same code up to immutables and linked addresses
+    Match,
+    /// No code at the address
+    Missing,
+    /// Different code; offset of the first differing byte
+    Mismatch { first_difference: usize },
+}
+
+impl fmt::Display for CodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeStatus::Match => write!(f, "matches the artifact"),
+            CodeStatus::Missing => write!(f, "no code"),
+            CodeStatus::Mismatch { first_difference } => {
+                write!(f, "differs from the artifact at byte {}", first_difference)
+            }
+        }
+    }
+}
+
+/// Integrity of one deployed contract
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractCheck {
+    pub name: &'static str,
+    pub address: Address,
+    pub status: CodeStatus,
+}
+
+/// Integrity of a verifier implementation and every library it links to
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub contracts: Vec<ContractCheck>,
+}
+
+impl IntegrityReport {
+    /// Whether every contract matches its artifact
+    pub fn is_intact(&self) -> bool {
+        self.contracts
+            .iter()
+            .all(|contract| contract.status == CodeStatus::Match)
+    }
+
+    /// Addresses of the libraries the verifier links to, by name
+    pub fn libraries(&self) -> BTreeMap<String, Address> {
+        self.contracts
+            .iter()
+            .skip(1)
+            .map(|contract| (contract.name.to_string(), contract.address))
+            .collect()
+    }
+}
+
+/// Byte ranges of the runtime code that depend on the deployment
+fn masked_ranges(artifact: &Artifact) -> Result<Vec<(usize, usize)>> {
+    let mut ranges = artifact.immutable_references()?;
+    ranges.extend(
+        artifact
+            .deployed_link_references()?
+            .into_iter()
+            .map(|reference| (reference.start, reference.length)),
+    );
+    if artifact.is_library()? {
+        ranges.push((1, Address::len_bytes()));
+    }
+    Ok(ranges)
+}
+
+/// Compare `code` with the runtime bytecode of `artifact`
+pub fn compare_code(artifact: &Artifact, code: &[u8]) -> Result<CodeStatus> {
+    if code.is_empty() {
+        return Ok(CodeStatus::Missing);
+    }
+
+    let mut expected_hex = artifact.deployed_bytecode_hex()?;
+    let ranges = masked_ranges(artifact)?;
+    for &(start, length) in &ranges {
+        let range = 2 * start..2 * (start + length);
+        if range.end > expected_hex.len() {
+            return Err(anyhow!(
+                "Masked range of {} is out of bounds",
+                artifact.name
+            ));
+        }
+        expected_hex.replace_range(range, &"00".repeat(length));
+    }
+    let expected = hex::decode(expected_hex)?;
+
+    let mut actual = code.to_vec();
+    for &(start, length) in &ranges {
+        if let Some(bytes) = actual.get_mut(start..start + length) {
+            bytes.fill(0);
+        }
+    }
+
+    let first_difference = expected
+        .iter()
+        .zip(&actual)
+        .position(|(a, b)| a != b)
+        .or_else(|| (expected.len() != actual.len()).then_some(expected.len().min(actual.len())));
+    Ok(match first_difference {
+        None => CodeStatus::Match,
+        Some(first_difference) => CodeStatus::Mismatch { first_difference },
+    })
+}
+
+/// Library addresses linked into the runtime `code` of `artifact`
+pub fn linked_libraries(artifact: &Artifact, code: &[u8]) -> Result<BTreeMap<String, Address>> {
+    let mut libraries = BTreeMap::new();
+    for reference in artifact.deployed_link_references()? {
+        let bytes = code
+            .get(reference.start..reference.start + reference.length)
+            .ok_or_else(|| anyhow!("Code is too short to link {}", reference.library))?;
+        let address = Address::from_slice(bytes);
+        if let Some(previous) = libraries.insert(reference.library.clone(), address) {
+            if previous != address {
+                return Err(anyhow!(
+                    "{} is linked at both {:?} and {:?}",
+                    reference.library,
+                    previous,
+                    address
+                ));
+            }
+        }
+    }
+    Ok(libraries)
+}
+
+/// Check the STWOVerifier at `verifier_address` and the libraries it links to
+pub async fn check_deployment(
+    provider: &impl Provider,
+    verifier_address: Address,
+) -> Result<IntegrityReport> {
+    check_implementation(provider, &STWO_VERIFIER, verifier_address).await
+}
+
+/// Check the `artifact` contract at `address` and the libraries it links to
+pub async fn check_implementation(
+    provider: &impl Provider,
+    artifact: &Artifact,
+    address: Address,
+) -> Result<IntegrityReport> {
+    let code = provider.get_code_at(address).await?;
+    let status = compare_code(artifact, &code)?;
+    let mut contracts = vec![ContractCheck {
+        name: artifact.name,
+        address,
+        status: status.clone(),
+    }];
+
+    // Library addresses can only be trusted from the expected verifier code
+    if status == CodeStatus::Match {
+        let libraries = linked_libraries(artifact, &code)?;
+        for library in VERIFIER_LIBRARIES {
+            let address = *libraries
+                .get(library.name)
+                .ok_or_else(|| anyhow!("{} does not link {}", artifact.name, library.name))?;
+            let code = provider.get_code_at(address).await?;
+            contracts.push(ContractCheck {
+                name: library.name,
+                address,
+                status: compare_code(&library, &code)?,
+            });
+        }
+    }
+
+    Ok(IntegrityReport { contracts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::link;
+
+    #[test]
+    fn test_compare_code_masks_links() {
+        let references = STWO_VERIFIER.deployed_link_references().unwrap();
+        let libraries: BTreeMap<String, Address> = VERIFIER_LIBRARIES
+            .iter()
+            .enumerate()
+            .map(|(i, library)| (library.name.to_string(), Address::repeat_byte(i as u8 + 1)))
+            .collect();
+        let code = link(
+            &STWO_VERIFIER.deployed_bytecode_hex().unwrap(),
+            &references,
+            &libraries,
+        )
+        .unwrap();
+
+        assert_eq!(
+            compare_code(&STWO_VERIFIER, &code).unwrap(),
+            CodeStatus::Match
+        );
+        assert_eq!(linked_libraries(&STWO_VERIFIER, &code).unwrap(), libraries);
+        assert_eq!(
+            compare_code(&STWO_VERIFIER, &[]).unwrap(),
+            CodeStatus::Missing
+        );
+
+        let mut tampered = code.to_vec();
+        tampered[100] ^= 1;
+        assert_eq!(
+            compare_code(&STWO_VERIFIER, &tampered).unwrap(),
+            CodeStatus::Mismatch {
+                first_difference: 100
+            }
+        );
+        assert!(matches!(
+            compare_code(&STWO_VERIFIER, &code[..code.len() - 1]).unwrap(),
+            CodeStatus::Mismatch { .. }
+        ));
+    }
+}
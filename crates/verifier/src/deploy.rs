@@ -1,13 +1,31 @@
+use std::collections::BTreeMap;
+
 use alloy::{
-    network::EthereumWallet,
+    network::{EthereumWallet, TransactionBuilder},
     node_bindings::{Anvil, AnvilInstance},
-    primitives::Address,
+    primitives::{Address, Bytes, TxHash, U256},
     providers::Provider,
     providers::ProviderBuilder,
+    rpc::types::TransactionRequest,
     signers::local::PrivateKeySigner,
     sol,
 };
 use anyhow::Result;
+use serde::Serialize;
+use tracing::{info, instrument};
+
+use crate::artifact::{
+    Artifact, EIP170_CODE_SIZE_LIMIT, STWO_VERIFIER, TRANSPARENT_VERIFIER_PROXY,
+    UPGRADEABLE_STWO_VERIFIER, UUPS_VERIFIER_PROXY, VERIFIER_LIBRARIES,
+};
+use crate::check::{check_implementation, CodeStatus, IntegrityReport};
+use crate::dry_run::{simulate_deployment, DryRunReport};
+use crate::fixture::ProofFixture;
+use crate::manifest::ImplementationRecord;
+use crate::network::{load_network, NetworkProfile};
+use crate::proxy::{deploy_proxy, upgrade_proxy, ProxyKind, ProxyRecord};
+use crate::signer::SignerSource;
+use crate::tx::{send_transaction, TxOptions};
 
 sol!(
     #[sol(rpc)]
@@ -32,7 +50,7 @@ impl Default for AnvilConfig {
     fn default() -> Self {
         Self {
             block_time: 1,
-            code_size_limit: 51000,
+            code_size_limit: EIP170_CODE_SIZE_LIMIT as u64,
             gas_limit: "99999999999999".to_string(),
             fork_url: std::env::var("ETH_RPC_URL").ok(),
         }
@@ -40,11 +58,36 @@ impl Default for AnvilConfig {
 }
 
 /// Result of a successful deployment
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DeploymentResult {
+    /// Address to verify proofs at: the proxy for proxied deployments
     pub verifier_address: Address,
+    /// Linked library name => address
+    pub libraries: BTreeMap<String, Address>,
     pub chain_id: Option<u64>,
     pub block_number: Option<u64>,
+    pub proxy: Option<ProxyRecord>,
+    /// Contract creations in sending order, empty when an existing
+    /// deployment was reused
+    pub transactions: Vec<DeployedContract>,
+}
+
+/// One contract creation sent by the deployer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeployedContract {
+    pub name: String,
+    pub address: Address,
+    pub tx_hash: TxHash,
+    pub gas_used: u64,
+}
+
+/// A contract linked against freshly deployed libraries
+pub(crate) struct LinkedDeployment {
+    pub address: Address,
+    /// Linked library name => address
+    pub libraries: BTreeMap<String, Address>,
+    /// The libraries, then the contract
+    pub transactions: Vec<DeployedContract>,
 }
 
 /// Configuration for deployment
@@ -52,15 +95,21 @@ pub struct DeploymentResult {
 pub struct DeployConfig {
     private_key: PrivateKeySigner,
     rpc_url: Option<String>,
-    anvil_instance: AnvilInstance,
+    /// Local node started by the deployer, `None` for persistent networks
+    anvil_instance: Option<AnvilInstance>,
     anvil_config: AnvilConfig,
+    /// Chain the node must be on, when known
+    chain_id: Option<u64>,
+    /// Verifier to reuse, see [`STWOVerifierDeployer::deploy_or_attach`]
+    verifier_address: Option<Address>,
+    tx_options: TxOptions,
 }
 
 impl DeployConfig {
     pub fn new(
         private_key: PrivateKeySigner,
         rpc_url: Option<String>,
-        anvil_instance: AnvilInstance,
+        anvil_instance: Option<AnvilInstance>,
         anvil_config: AnvilConfig,
     ) -> Self {
         Self {
@@ -68,8 +117,24 @@ impl DeployConfig {
             rpc_url,
             anvil_instance,
             anvil_config,
+            chain_id: None,
+            verifier_address: None,
+            tx_options: TxOptions::default(),
         }
     }
+
+    /// Apply the chain id, verifier address, confirmations and gas settings of `profile`
+    pub fn with_network(mut self, profile: &NetworkProfile) -> Result<Self> {
+        self.chain_id = profile.chain_id;
+        self.verifier_address = profile.verifier_address;
+        if let Some(confirmations) = profile.confirmations {
+            self.tx_options.confirmations = confirmations;
+        }
+        if let Some(gas) = &profile.gas {
+            self.tx_options.gas = gas.options()?;
+        }
+        Ok(self)
+    }
 }
 
 /// Information about deployment configuration
@@ -95,15 +160,69 @@ impl STWOVerifierDeployer {
 
     /// Create deployer with custom Anvil configuration
     pub fn with_anvil_config(anvil_config: AnvilConfig) -> Result<Self> {
+        Self::with_anvil_signer(anvil_config, None)
+    }
+
+    /// Create deployer on a new Anvil instance signing with `signer`, or with
+    /// Anvil's first account when `None`. Other accounts are funded before deploying.
+    pub fn with_anvil_signer(
+        anvil_config: AnvilConfig,
+        signer: Option<&SignerSource>,
+    ) -> Result<Self> {
         let anvil = Self::setup_anvil(&anvil_config)?;
-        let private_key = anvil.keys()[0].clone().into();
+        let private_key = match signer {
+            Some(source) => source.load()?,
+            None => anvil.keys()[0].clone().into(),
+        };
         let rpc_url = Some(anvil.endpoint());
 
-        let config = DeployConfig::new(private_key, rpc_url, anvil, anvil_config);
+        let config = DeployConfig::new(private_key, rpc_url, Some(anvil), anvil_config);
 
         Ok(Self { config })
     }
 
+    /// Create deployer for an existing node, such as a persistent network
+    pub fn with_rpc(rpc_url: String, signer: &SignerSource) -> Result<Self> {
+        let anvil_config = AnvilConfig {
+            fork_url: None,
+            ..AnvilConfig::default()
+        };
+        let config = DeployConfig::new(signer.load()?, Some(rpc_url), None, anvil_config);
+        Ok(Self { config })
+    }
+
+    /// Create deployer for a network profile. Local profiles start Anvil from
+    /// `defaults` with the profile's Anvil fields applied.
+    pub fn from_profile(profile: &NetworkProfile, defaults: AnvilConfig) -> Result<Self> {
+        let signer = match &profile.signer {
+            Some(signer) => signer.source()?,
+            None => None,
+        };
+        let deployer = match &profile.rpc_url {
+            Some(rpc_url) => {
+                let signer = signer
+                    .ok_or_else(|| anyhow::anyhow!("Network at {} needs a signer", rpc_url))?;
+                Self::with_rpc(rpc_url.clone(), &signer)?
+            }
+            None => Self::with_anvil_signer(profile.anvil_config(defaults), signer.as_ref())?,
+        };
+        Ok(Self {
+            config: deployer.config.with_network(profile)?,
+        })
+    }
+
+    /// Create deployer for the network named `network` in `stwo-sol.toml`, or
+    /// on a new Anvil instance configured by `defaults` when `None`
+    pub fn for_network(network: Option<&str>, defaults: AnvilConfig) -> Result<Self> {
+        match network {
+            Some(name) => {
+                info!(network = name, "Using network profile");
+                Self::from_profile(&load_network(name)?, defaults)
+            }
+            None => Self::with_anvil_config(defaults),
+        }
+    }
+
     /// Setup Anvil instance with given configuration
     fn setup_anvil(config: &AnvilConfig) -> Result<AnvilInstance> {
         let mut anvil_builder = Anvil::new()
@@ -114,56 +233,187 @@ impl STWOVerifierDeployer {
             .arg(&config.gas_limit);
 
         if let Some(ref fork_url) = config.fork_url {
-            println!("🔗 Forking from: {}", fork_url);
+            info!(fork_url = %fork_url, "Forking");
             anvil_builder = anvil_builder.fork(fork_url.clone());
         }
 
         let anvil = anvil_builder.try_spawn()?;
-        println!("⚡ Anvil started on: {}", anvil.endpoint());
+        info!(endpoint = %anvil.endpoint(), "Anvil started");
 
         Ok(anvil)
     }
 
-    /// Deploy STWOVerifier contract
+    /// Deploy the verifier libraries, then STWOVerifier linked against them
+    #[instrument(name = "deploy", skip_all)]
     pub async fn deploy(&self) -> Result<DeploymentResult> {
-        println!("🚀 Starting STWO Verifier deployment...");
-
         let provider = self.create_provider().await?;
 
         // Get network info
         let chain_id = provider.get_chain_id().await.ok();
         let block_number = provider.get_block_number().await.ok();
+        info!(chain_id, block_number, "Starting STWO Verifier deployment");
 
-        println!("📋 Network info:");
-        if let Some(id) = chain_id {
-            println!("   Chain ID: {}", id);
-        }
-        if let Some(block) = block_number {
-            println!("   Block number: {}", block);
-        }
-
-        // Deploy contract
-        let deploy_tx = STWOVerifier::deploy(&provider).await?;
-        let verifier_address = *deploy_tx.address();
-
-        println!("✅ Contract deployed at: {:?}", verifier_address);
+        self.check_code_sizes()?;
+        self.prepare(&provider).await?;
+        let linked = deploy_linked(&provider, &STWO_VERIFIER, &self.config.tx_options).await?;
 
         // Verify deployment
-        self.verify_deployment(verifier_address).await?;
+        self.verify_deployment(&STWO_VERIFIER, linked.address, &linked.libraries)
+            .await?;
 
         Ok(DeploymentResult {
-            verifier_address,
+            verifier_address: linked.address,
+            libraries: linked.libraries,
             chain_id,
             block_number,
+            proxy: None,
+            transactions: linked.transactions,
         })
     }
 
+    /// Deploy a verifier implementation behind a `kind` proxy. `owner` may
+    /// upgrade it and defaults to the deployer account.
+    #[instrument(name = "deploy", skip_all, fields(proxy = %kind))]
+    pub async fn deploy_proxied(
+        &self,
+        kind: ProxyKind,
+        owner: Option<Address>,
+    ) -> Result<DeploymentResult> {
+        let provider = self.create_provider().await?;
+        let chain_id = provider.get_chain_id().await.ok();
+        info!(chain_id, "Starting STWO Verifier deployment behind a proxy");
+        self.check_code_sizes()?;
+        self.prepare(&provider).await?;
+
+        let owner = owner.unwrap_or(self.config.private_key.address());
+        let (proxy, implementation, transactions) =
+            deploy_proxy(&provider, kind, owner, &self.config.tx_options).await?;
+        info!(owner = %proxy.owner, "Proxy owner set");
+
+        self.verify_deployment(
+            &kind.implementation(),
+            implementation.implementation,
+            &implementation.libraries,
+        )
+        .await?;
+
+        Ok(DeploymentResult {
+            verifier_address: proxy.address,
+            libraries: implementation.libraries,
+            chain_id,
+            block_number: implementation.block_number,
+            proxy: Some(proxy),
+            transactions,
+        })
+    }
+
+    /// Simulate [`deploy`](Self::deploy), or [`deploy_proxied`](Self::deploy_proxied)
+    /// with `proxy`, on a fork without sending anything to the network. The
+    /// cost is computed at `gas_price` wei, or at the gas strategy's current
+    /// max fee when `None`.
+    pub async fn dry_run(
+        &self,
+        proxy: Option<ProxyKind>,
+        owner: Option<Address>,
+        gas_price: Option<u128>,
+    ) -> Result<DryRunReport> {
+        let gas_price = match gas_price {
+            Some(gas_price) => gas_price,
+            None => {
+                let provider = self.create_provider().await?;
+                self.config
+                    .tx_options
+                    .gas
+                    .fees(&provider)
+                    .await?
+                    .max_fee_per_gas()
+            }
+        };
+        simulate_deployment(
+            &self.get_info().rpc_url,
+            self.config.private_key.clone(),
+            proxy,
+            owner,
+            gas_price,
+        )
+        .await
+    }
+
+    /// Switch `proxy` to a freshly deployed implementation once it accepts
+    /// `fixture`. The deployer account must own the proxy.
+    pub async fn upgrade(
+        &self,
+        proxy: &ProxyRecord,
+        fixture: &ProofFixture,
+    ) -> Result<ImplementationRecord> {
+        let provider = self.create_provider().await?;
+        let record = upgrade_proxy(&provider, proxy, fixture, &self.config.tx_options).await?;
+        self.verify_deployment(
+            &proxy.kind.implementation(),
+            record.implementation,
+            &record.libraries,
+        )
+        .await?;
+        Ok(record)
+    }
+
+    /// Fail early when a contract would exceed the code size limit of the node
+    fn check_code_sizes(&self) -> Result<()> {
+        let limit = self.config.anvil_config.code_size_limit as usize;
+        let contracts = [
+            STWO_VERIFIER,
+            UPGRADEABLE_STWO_VERIFIER,
+            UUPS_VERIFIER_PROXY,
+            TRANSPARENT_VERIFIER_PROXY,
+        ];
+        for artifact in VERIFIER_LIBRARIES.iter().chain(&contracts) {
+            let size = artifact.deployed_size()?;
+            if size > limit {
+                anyhow::bail!(
+                    "{} is {} bytes, over the {} byte code size limit",
+                    artifact.name,
+                    size,
+                    limit
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the node is on the expected chain, and give the signer a balance
+    /// on the deployer's own Anvil instance
+    async fn prepare(&self, provider: &impl Provider) -> Result<()> {
+        if let Some(expected) = self.config.chain_id {
+            let chain_id = provider.get_chain_id().await?;
+            if chain_id != expected {
+                anyhow::bail!(
+                    "Connected to chain {}, the network profile expects {}",
+                    chain_id,
+                    expected
+                );
+            }
+        }
+        info!(strategy = %self.config.tx_options.gas.strategy, "Gas strategy");
+        if self.config.anvil_instance.is_none() {
+            return Ok(());
+        }
+        let address = self.config.private_key.address();
+        if provider.get_balance(address).await?.is_zero() {
+            let balance = U256::from(10_000u64) * U256::from(10u64).pow(U256::from(18u64));
+            provider
+                .raw_request::<_, ()>("anvil_setBalance".into(), (address, balance))
+                .await?;
+            info!(%address, "Funded signer on Anvil");
+        }
+        Ok(())
+    }
+
     /// Create provider with wallet
-    async fn create_provider(&self) -> Result<impl alloy::providers::Provider> {
+    pub(crate) async fn create_provider(&self) -> Result<impl alloy::providers::Provider> {
         let wallet = EthereumWallet::from(self.config.private_key.clone());
         let provider = ProviderBuilder::new()
             .wallet(wallet)
-            .connect_http(self.config.anvil_instance.endpoint_url().clone());
+            .connect_http(self.get_info().rpc_url.parse()?);
 
         Ok(provider)
     }
@@ -183,40 +433,184 @@ impl STWOVerifierDeployer {
         }
     }
 
-    pub async fn get_signer(&self) -> Result<PrivateKeySigner> {
-        Ok(self.config.private_key.clone())
+    pub fn get_signer(&self) -> PrivateKeySigner {
+        self.config.private_key.clone()
     }
 
-    /// Verify that deployment was successful
-    async fn verify_deployment(&self, verifier_address: Address) -> Result<()> {
-        println!("🔍 Verifying deployment...");
+    /// How the deployer and the proof submitter send transactions
+    pub fn tx_options(&self) -> TxOptions {
+        self.config.tx_options
+    }
+
+    /// Reuse the network's configured verifier if it has one, deploy otherwise
+    pub async fn deploy_or_attach(&self) -> Result<DeploymentResult> {
+        match self.config.verifier_address {
+            Some(address) => self.deploy_unless_present(address).await,
+            None => self.deploy().await,
+        }
+    }
 
+    /// Compare the on-chain code of the verifier and its libraries with the artifacts
+    pub async fn check(&self, verifier_address: Address) -> Result<IntegrityReport> {
+        self.check_implementation(&STWO_VERIFIER, verifier_address)
+            .await
+    }
+
+    /// Compare the on-chain code of an `artifact` implementation and its
+    /// libraries with the artifacts
+    pub async fn check_implementation(
+        &self,
+        artifact: &Artifact,
+        address: Address,
+    ) -> Result<IntegrityReport> {
+        let provider = self.create_provider().await?;
+        check_implementation(&provider, artifact, address).await
+    }
+
+    /// Reuse the verifier at `existing` if its code matches the artifacts, deploy
+    /// a new one if there is no code there, and fail if the code differs
+    pub async fn deploy_unless_present(&self, existing: Address) -> Result<DeploymentResult> {
+        let report = self.check(existing).await?;
+        if report.is_intact() {
+            info!(address = %existing, "STWOVerifier already deployed");
+            let provider = self.create_provider().await?;
+            return Ok(DeploymentResult {
+                verifier_address: existing,
+                libraries: report.libraries(),
+                chain_id: provider.get_chain_id().await.ok(),
+                block_number: provider.get_block_number().await.ok(),
+                proxy: None,
+                transactions: Vec::new(),
+            });
+        }
+        for contract in &report.contracts {
+            if contract.status != CodeStatus::Missing {
+                anyhow::bail!(
+                    "{} at {:?} {}",
+                    contract.name,
+                    contract.address,
+                    contract.status
+                );
+            }
+        }
+        self.deploy().await
+    }
+
+    /// Verify that the deployed code is the code of the artifacts
+    async fn verify_deployment(
+        &self,
+        artifact: &Artifact,
+        verifier_address: Address,
+        libraries: &BTreeMap<String, Address>,
+    ) -> Result<()> {
         if verifier_address == Address::ZERO {
-            anyhow::bail!("❌ Deployment failed - zero address");
+            anyhow::bail!("Deployment failed - zero address");
         }
 
-        println!("✅ Deployment verified successfully");
+        let report = self
+            .check_implementation(artifact, verifier_address)
+            .await?;
+        for contract in &report.contracts {
+            if contract.status != CodeStatus::Match {
+                anyhow::bail!(
+                    "{} at {:?} {}",
+                    contract.name,
+                    contract.address,
+                    contract.status
+                );
+            }
+        }
+        if &report.libraries() != libraries {
+            anyhow::bail!("{} is not linked to the deployed libraries", artifact.name);
+        }
+
+        info!(address = %verifier_address, "Deployment verified");
         Ok(())
     }
 
     /// Stop the Anvil instance
     pub fn stop_anvil(self) {
-        drop(self.config.anvil_instance);
-        println!("🛑 Anvil instance stopped");
+        if let Some(anvil) = self.config.anvil_instance {
+            drop(anvil);
+            info!("Anvil instance stopped");
+        }
     }
 
     /// Wait for user interruption (Ctrl+C)
     pub async fn wait_for_shutdown(self) -> Result<()> {
-        println!("\n⏳ Anvil is running. Press Ctrl+C to stop...");
+        info!("Anvil is running, press Ctrl+C to stop");
         tokio::signal::ctrl_c().await?;
         self.stop_anvil();
         Ok(())
     }
 }
 
+/// Send a contract creation transaction for `artifact`
+pub(crate) async fn deploy_code(
+    provider: &impl Provider,
+    artifact: &Artifact,
+    bytecode: Bytes,
+    options: &TxOptions,
+) -> Result<DeployedContract> {
+    let tx = TransactionRequest::default().with_deploy_code(bytecode);
+    let receipt = send_transaction(provider, tx, options).await?;
+    if !receipt.status() {
+        anyhow::bail!("Deployment of {} reverted", artifact.name);
+    }
+    let address = receipt
+        .contract_address
+        .ok_or_else(|| anyhow::anyhow!("No contract address for {}", artifact.name))?;
+
+    info!(contract = artifact.name, %address, "Contract deployed");
+    Ok(DeployedContract {
+        name: artifact.name.to_string(),
+        address,
+        tx_hash: receipt.transaction_hash,
+        gas_used: receipt.gas_used,
+    })
+}
+
+/// Deploy the verifier libraries, then `artifact` linked against them
+pub(crate) async fn deploy_linked(
+    provider: &impl Provider,
+    artifact: &Artifact,
+    options: &TxOptions,
+) -> Result<LinkedDeployment> {
+    // Libraries first: the verifier's bytecode embeds their addresses
+    let mut libraries = BTreeMap::new();
+    let mut transactions = Vec::new();
+    for library in VERIFIER_LIBRARIES {
+        let deployed = deploy_code(
+            provider,
+            &library,
+            library.linked_bytecode(&libraries)?,
+            options,
+        )
+        .await?;
+        libraries.insert(library.name.to_string(), deployed.address);
+        transactions.push(deployed);
+    }
+
+    let deployed = deploy_code(
+        provider,
+        artifact,
+        artifact.linked_bytecode(&libraries)?,
+        options,
+    )
+    .await?;
+    let address = deployed.address;
+    transactions.push(deployed);
+    Ok(LinkedDeployment {
+        address,
+        libraries,
+        transactions,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::signer::mnemonic_signer;
 
     #[tokio::test]
     async fn test_deploy_with_anvil() -> Result<()> {
@@ -224,8 +618,85 @@ mod tests {
         let result = deployer.deploy().await?;
 
         assert_ne!(result.verifier_address, Address::ZERO);
+        let last = result.transactions.last().unwrap();
+        assert_eq!(last.name, STWO_VERIFIER.name);
+        assert_eq!(last.address, result.verifier_address);
+        assert_eq!(result.transactions.len(), VERIFIER_LIBRARIES.len() + 1);
 
-        println!("Test deployment successful: {:?}", result.verifier_address);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_within_eip170_limit() -> Result<()> {
+        for artifact in VERIFIER_LIBRARIES.iter().chain([&STWO_VERIFIER]) {
+            let size = artifact.deployed_size()?;
+            assert!(
+                size <= EIP170_CODE_SIZE_LIMIT,
+                "{} is {} bytes",
+                artifact.name,
+                size
+            );
+        }
+
+        let config = AnvilConfig {
+            code_size_limit: EIP170_CODE_SIZE_LIMIT as u64,
+            fork_url: None,
+            ..AnvilConfig::default()
+        };
+        let deployer = STWOVerifierDeployer::with_anvil_config(config)?;
+        let result = deployer.deploy().await?;
+
+        assert_eq!(result.libraries.len(), VERIFIER_LIBRARIES.len());
+        let provider = deployer.create_provider().await?;
+        for address in result.libraries.values().chain([&result.verifier_address]) {
+            assert!(!provider.get_code_at(*address).await?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_unless_present() -> Result<()> {
+        let deployer = STWOVerifierDeployer::with_anvil()?;
+        let result = deployer.deploy().await?;
+
+        let reused = deployer
+            .deploy_unless_present(result.verifier_address)
+            .await?;
+        assert_eq!(reused.verifier_address, result.verifier_address);
+        assert_eq!(reused.libraries, result.libraries);
+
+        // A library is not the verifier: its code differs
+        let library = result.libraries["OodsModule"];
+        assert!(!deployer.check(library).await?.is_intact());
+        assert!(deployer.deploy_unless_present(library).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_with_mnemonic_signer() -> Result<()> {
+        // An unfunded account, funded by the deployer before deploying
+        let signer = mnemonic_signer(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "m/44'/60'/0'/0/3",
+        )?;
+        let anvil_config = AnvilConfig {
+            fork_url: None,
+            ..AnvilConfig::default()
+        };
+        let anvil = STWOVerifierDeployer::setup_anvil(&anvil_config)?;
+        let rpc_url = Some(anvil.endpoint());
+        let deployer = STWOVerifierDeployer::new(DeployConfig::new(
+            signer.clone(),
+            rpc_url,
+            Some(anvil),
+            anvil_config,
+        ));
+        let result = deployer.deploy().await?;
+
+        assert_ne!(result.verifier_address, Address::ZERO);
+        assert_eq!(deployer.get_signer().address(), signer.address());
 
         Ok(())
     }
@@ -234,19 +705,15 @@ mod tests {
     async fn test_deploy_with_custom_config() -> Result<()> {
         let custom_config = AnvilConfig {
             block_time: 2,
-            code_size_limit: 100000,
             gas_limit: "50000000".to_string(),
             fork_url: None,
+            ..AnvilConfig::default()
         };
 
         let deployer = STWOVerifierDeployer::with_anvil_config(custom_config)?;
         let result = deployer.deploy().await?;
 
         assert_ne!(result.verifier_address, Address::ZERO);
-        println!(
-            "Custom config deployment successful: {:?}",
-            result.verifier_address
-        );
 
         Ok(())
     }
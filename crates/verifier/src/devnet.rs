@@ -0,0 +1,275 @@
+//! Local development network with the verifier and the example consumers.
+//!
+//! [`Devnet::start`] runs Anvil with automine, deploys STWOVerifier with its
+//! libraries and the given consumer contracts, and writes their addresses to a
+//! JSON file. With a state file, the chain is restored from an
+//! `anvil_dumpState` snapshot on start and saved again on shutdown, so a restart
+//! skips the deployment.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use alloy::{
+    node_bindings::Anvil,
+    primitives::{Address, Bytes},
+    providers::Provider,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::artifact::{Artifact, EIP170_CODE_SIZE_LIMIT};
+use crate::deploy::{deploy_code, AnvilConfig, DeployConfig, STWOVerifierDeployer};
+
+/// Addresses file written when none is given
+pub const DEFAULT_ADDRESSES_PATH: &str = "devnet.json";
+
+#[derive(Debug, Clone)]
+pub struct DevnetConfig {
+    /// Anvil port, a free one when `None`
+    pub port: Option<u16>,
+    /// Where the addresses are written
+    pub addresses: PathBuf,
+    /// Snapshot loaded on start when it exists and written on shutdown
+    pub state: Option<PathBuf>,
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            addresses: PathBuf::from(DEFAULT_ADDRESSES_PATH),
+            state: None,
+        }
+    }
+}
+
+/// A consumer contract deployed next to the verifier
+pub struct DevnetConsumer {
+    pub artifact: Artifact,
+    /// ABI-encoded constructor arguments, given the verifier address
+    pub constructor_args: Box<dyn Fn(Address) -> Vec<u8> + Send + Sync>,
+}
+
+/// Contents of the addresses file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevnetAddresses {
+    pub rpc_url: String,
+    pub chain_id: u64,
+    pub verifier: Address,
+    /// Linked library name => address
+    pub libraries: BTreeMap<String, Address>,
+    /// Example consumer contract name => address
+    pub consumers: BTreeMap<String, Address>,
+}
+
+impl DevnetAddresses {
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// A running devnet; Anvil stops when it is dropped
+pub struct Devnet {
+    deployer: STWOVerifierDeployer,
+    pub addresses: DevnetAddresses,
+    state: Option<PathBuf>,
+}
+
+impl Devnet {
+    /// Start Anvil, restore the snapshot or deploy the verifier and `consumers`,
+    /// and write the addresses
+    pub async fn start(config: &DevnetConfig, consumers: &[DevnetConsumer]) -> Result<Self> {
+        // The default block gas limit, high enough to submit the examples' proofs
+        let anvil_config = AnvilConfig {
+            block_time: 0,
+            code_size_limit: EIP170_CODE_SIZE_LIMIT as u64,
+            fork_url: None,
+            ..AnvilConfig::default()
+        };
+        let mut anvil = Anvil::new()
+            .arg("--code-size-limit")
+            .arg(anvil_config.code_size_limit.to_string())
+            .arg("--gas-limit")
+            .arg(&anvil_config.gas_limit);
+        if let Some(port) = config.port {
+            anvil = anvil.port(port);
+        }
+        let anvil = anvil.try_spawn()?;
+        info!(endpoint = %anvil.endpoint(), "Anvil started with automine");
+
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let rpc_url = anvil.endpoint();
+        let deployer = STWOVerifierDeployer::new(DeployConfig::new(
+            signer,
+            Some(rpc_url.clone()),
+            Some(anvil),
+            anvil_config,
+        ));
+
+        let restored = match &config.state {
+            Some(state) if state.exists() => {
+                let provider = deployer.create_provider().await?;
+                let snapshot = Bytes::from(std::fs::read(state)?);
+                provider
+                    .raw_request::<_, bool>("anvil_loadState".into(), (snapshot,))
+                    .await?;
+                info!(state = %state.display(), "Loaded state");
+                restore_addresses(&deployer, &config.addresses).await?
+            }
+            _ => None,
+        };
+
+        let addresses = match restored {
+            Some(addresses) => DevnetAddresses {
+                rpc_url,
+                ..addresses
+            },
+            None => deploy_contracts(&deployer, rpc_url, consumers).await?,
+        };
+        addresses.save(&config.addresses)?;
+        info!(path = %config.addresses.display(), "Addresses written");
+
+        Ok(Self {
+            deployer,
+            addresses,
+            state: config.state.clone(),
+        })
+    }
+
+    /// Write an `anvil_dumpState` snapshot to the state file, if there is one
+    pub async fn save_state(&self) -> Result<()> {
+        let Some(path) = &self.state else {
+            return Ok(());
+        };
+        let provider = self.deployer.create_provider().await?;
+        let snapshot: Bytes = provider.raw_request("anvil_dumpState".into(), ()).await?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, &snapshot)?;
+        info!(state = %path.display(), "State saved");
+        Ok(())
+    }
+
+    /// Keep the devnet running until Ctrl+C, then save the state and stop Anvil
+    pub async fn run_until_shutdown(self) -> Result<()> {
+        info!("Devnet is running, press Ctrl+C to stop");
+        tokio::signal::ctrl_c().await?;
+        self.save_state().await?;
+        self.deployer.stop_anvil();
+        Ok(())
+    }
+
+    pub fn deployer(&self) -> &STWOVerifierDeployer {
+        &self.deployer
+    }
+}
+
+/// Addresses of the previous run if the restored chain still holds its contracts
+async fn restore_addresses(
+    deployer: &STWOVerifierDeployer,
+    path: &Path,
+) -> Result<Option<DevnetAddresses>> {
+    let Ok(addresses) = DevnetAddresses::load(path) else {
+        warn!(path = %path.display(), "No addresses to restore, deploying again");
+        return Ok(None);
+    };
+    let provider = deployer.create_provider().await?;
+    let mut intact = deployer.check(addresses.verifier).await?.is_intact();
+    for address in addresses.consumers.values() {
+        intact &= !provider.get_code_at(*address).await?.is_empty();
+    }
+    if !intact {
+        warn!(
+            path = %path.display(),
+            "Snapshot does not hold the contracts, deploying again"
+        );
+        return Ok(None);
+    }
+    info!(address = %addresses.verifier, "STWOVerifier restored");
+    Ok(Some(addresses))
+}
+
+/// Deploy the verifier, then each consumer pointing at it
+async fn deploy_contracts(
+    deployer: &STWOVerifierDeployer,
+    rpc_url: String,
+    consumers: &[DevnetConsumer],
+) -> Result<DevnetAddresses> {
+    let result = deployer.deploy().await?;
+    let provider = deployer.create_provider().await?;
+
+    let mut addresses = BTreeMap::new();
+    for DevnetConsumer {
+        artifact,
+        constructor_args,
+    } in consumers
+    {
+        let mut bytecode = artifact.linked_bytecode(&Default::default())?.to_vec();
+        bytecode.extend(constructor_args(result.verifier_address));
+        let deployed =
+            deploy_code(&provider, artifact, bytecode.into(), &deployer.tx_options()).await?;
+        addresses.insert(deployed.name, deployed.address);
+    }
+
+    Ok(DevnetAddresses {
+        rpc_url,
+        chain_id: provider.get_chain_id().await?,
+        verifier: result.verifier_address,
+        libraries: result.libraries,
+        consumers: addresses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::MERKLE_ROOT_CONSUMER;
+    use alloy::sol_types::SolValue;
+    use contracts::verifying_key::VerifyingKey;
+
+    #[tokio::test]
+    async fn test_devnet_restart_from_snapshot() -> Result<()> {
+        let dir = std::env::temp_dir().join("stwo_devnet_restart");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = DevnetConfig {
+            port: None,
+            addresses: dir.join("devnet.json"),
+            state: Some(dir.join("state")),
+        };
+
+        // Restoring only needs the consumer's code, so any key will do
+        let consumers = [DevnetConsumer {
+            artifact: MERKLE_ROOT_CONSUMER,
+            constructor_args: Box::new(|verifier| {
+                (verifier, [0u32; 8], VerifyingKey::default()).abi_encode_params()
+            }),
+        }];
+        let devnet = Devnet::start(&config, &consumers).await?;
+        assert_eq!(devnet.addresses.consumers.len(), 1);
+        devnet.save_state().await?;
+        let first = devnet.addresses.clone();
+        devnet.deployer.stop_anvil();
+
+        let restarted = Devnet::start(&config, &consumers).await?;
+        assert_eq!(restarted.addresses.verifier, first.verifier);
+        assert_eq!(restarted.addresses.consumers, first.consumers);
+        assert_eq!(
+            DevnetAddresses::load(&config.addresses)?,
+            restarted.addresses
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//! Deployment simulation on a throwaway fork of the target chain.
+//!
+//! The full deployment, libraries and proxy included, runs on an Anvil
+//! instance forked from the target node with the same signer, so nonces and
+//! therefore contract addresses are those a real deployment would get as long
+//! as the signer sends nothing else first. Nothing reaches the target node.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use alloy::{
+    eips::BlockId,
+    network::EthereumWallet,
+    node_bindings::Anvil,
+    primitives::{utils::format_ether, Address, U256},
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::Result;
+use serde::Serialize;
+use tracing::info;
+
+use crate::artifact::{EIP170_CODE_SIZE_LIMIT, STWO_VERIFIER};
+use crate::deploy::deploy_linked;
+use crate::proxy::{deploy_proxy, ProxyKind};
+use crate::tx::TxOptions;
+
+/// Code size limit of the fork, high enough for oversized contracts to be
+/// reported instead of failing the simulation
+const FORK_CODE_SIZE_LIMIT: u64 = 1_000_000;
+
+/// One contract creation of the simulated deployment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SimulatedTransaction {
+    pub contract: String,
+    /// Address the contract gets if the deployment is sent now
+    pub address: Address,
+    pub gas_used: u64,
+    /// Runtime code size in bytes
+    pub code_size: usize,
+}
+
+impl SimulatedTransaction {
+    pub fn within_eip170(&self) -> bool {
+        self.code_size <= EIP170_CODE_SIZE_LIMIT
+    }
+}
+
+/// Outcome of a simulated deployment
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub chain_id: u64,
+    pub deployer: Address,
+    /// Nonce of the deployer's first transaction
+    pub nonce: u64,
+    /// Deployer balance on the target chain
+    pub balance: U256,
+    /// Price per gas in wei the cost is computed at
+    pub gas_price: u128,
+    /// Contract creations in sending order
+    pub transactions: Vec<SimulatedTransaction>,
+    /// Address consumers would call: the proxy when there is one
+    pub verifier_address: Address,
+}
+
+impl DryRunReport {
+    pub fn total_gas(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.gas_used).sum()
+    }
+
+    /// Cost in wei of all transactions at `gas_price`
+    pub fn total_cost(&self) -> U256 {
+        U256::from(self.total_gas()) * U256::from(self.gas_price)
+    }
+
+    /// Whether every contract fits the EIP-170 limit
+    pub fn within_eip170(&self) -> bool {
+        self.transactions
+            .iter()
+            .all(SimulatedTransaction::within_eip170)
+    }
+
+    pub fn is_funded(&self) -> bool {
+        self.balance >= self.total_cost()
+    }
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📋 Dry run on chain {}:", self.chain_id)?;
+        writeln!(f, "   Deployer: {:?} (nonce {})", self.deployer, self.nonce)?;
+        for tx in &self.transactions {
+            writeln!(
+                f,
+                "   {} {:<24} {:?}  {:>9} gas  {:>6} bytes",
+                if tx.within_eip170() { "✅" } else { "❌" },
+                tx.contract,
+                tx.address,
+                tx.gas_used,
+                tx.code_size
+            )?;
+        }
+        writeln!(f, "   Verifier address: {:?}", self.verifier_address)?;
+        writeln!(f, "   Total gas: {}", self.total_gas())?;
+        writeln!(
+            f,
+            "   Total cost: {} wei ({} ETH) at {} wei per gas",
+            self.total_cost(),
+            format_ether(self.total_cost()),
+            self.gas_price
+        )?;
+        write!(
+            f,
+            "   Balance: {} wei{}",
+            self.balance,
+            if self.is_funded() {
+                ""
+            } else {
+                " ⚠️  not enough for the deployment"
+            }
+        )
+    }
+}
+
+/// Simulate the deployment `signer` would send to `rpc_url`: the verifier, or
+/// a `proxy` owned by `owner` (the signer when `None`) in front of an implementation.
+/// The cost is reported at `gas_price` wei.
+pub async fn simulate_deployment(
+    rpc_url: &str,
+    signer: PrivateKeySigner,
+    proxy: Option<ProxyKind>,
+    owner: Option<Address>,
+    gas_price: u128,
+) -> Result<DryRunReport> {
+    let deployer = signer.address();
+    let target = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+    let balance = target.get_balance(deployer).await?;
+
+    let fork = Anvil::new()
+        .fork(rpc_url)
+        .arg("--code-size-limit")
+        .arg(FORK_CODE_SIZE_LIMIT.to_string())
+        .try_spawn()?;
+    info!(endpoint = %fork.endpoint(), "Simulating deployment on a fork");
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(fork.endpoint().parse()?);
+
+    // The fork pays for the simulation whatever the real balance is
+    let funds = U256::from(10u64).pow(U256::from(30u64));
+    provider
+        .raw_request::<_, ()>("anvil_setBalance".into(), (deployer, funds))
+        .await?;
+    let chain_id = provider.get_chain_id().await?;
+    let nonce = provider.get_transaction_count(deployer).await?;
+    let first_block = provider.get_block_number().await?;
+
+    let options = TxOptions::default();
+    let mut names: BTreeMap<Address, String> = BTreeMap::new();
+    let verifier_address = match proxy {
+        Some(kind) => {
+            let (record, implementation, _) =
+                deploy_proxy(&provider, kind, owner.unwrap_or(deployer), &options).await?;
+            for (name, address) in implementation.libraries {
+                names.insert(address, name);
+            }
+            names.insert(
+                record.implementation,
+                kind.implementation().name.to_string(),
+            );
+            names.insert(record.address, kind.proxy().name.to_string());
+            record.address
+        }
+        None => {
+            let linked = deploy_linked(&provider, &STWO_VERIFIER, &options).await?;
+            for (name, address) in linked.libraries {
+                names.insert(address, name);
+            }
+            names.insert(linked.address, STWO_VERIFIER.name.to_string());
+            linked.address
+        }
+    };
+
+    // Every transaction the deployer sent on the fork, in order
+    let mut transactions = Vec::new();
+    for block in first_block + 1..=provider.get_block_number().await? {
+        let receipts = provider
+            .get_block_receipts(BlockId::number(block))
+            .await?
+            .unwrap_or_default();
+        for receipt in receipts.into_iter().filter(|r| r.from == deployer) {
+            let Some(address) = receipt.contract_address else {
+                continue;
+            };
+            transactions.push(SimulatedTransaction {
+                contract: names
+                    .get(&address)
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                address,
+                gas_used: receipt.gas_used,
+                code_size: provider.get_code_at(address).await?.len(),
+            });
+        }
+    }
+
+    Ok(DryRunReport {
+        chain_id,
+        deployer,
+        nonce,
+        balance,
+        gas_price,
+        transactions,
+        verifier_address,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deploy::STWOVerifierDeployer;
+
+    #[tokio::test]
+    async fn test_dry_run_predicts_deployment() -> Result<()> {
+        let deployer = STWOVerifierDeployer::with_anvil()?;
+        let report = deployer.dry_run(None, None, Some(1_000_000_000)).await?;
+
+        assert_eq!(report.transactions.len(), 5);
+        assert_eq!(report.transactions[4].contract, "STWOVerifier");
+        assert!(report.within_eip170());
+        assert_eq!(
+            report.total_cost(),
+            U256::from(report.total_gas()) * U256::from(1_000_000_000u64)
+        );
+
+        // Nothing was sent to the deployer's node
+        let provider = deployer.create_provider().await?;
+        assert!(provider
+            .get_code_at(report.verifier_address)
+            .await?
+            .is_empty());
+
+        let result = deployer.deploy().await?;
+        assert_eq!(result.verifier_address, report.verifier_address);
+        Ok(())
+    }
+}
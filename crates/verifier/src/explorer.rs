@@ -0,0 +1,358 @@
+//! Source verification payloads for block explorers.
+//!
+//! For every deployed contract, [`write_payloads`] writes a Standard-JSON
+//! compiler input rebuilt from the artifact's solc metadata (solc 0.8.26,
+//! `via_ir`, 200 optimizer runs, as in `foundry.toml`), the ABI-encoded
+//! constructor arguments, and a summary with the compiler version, linked
+//! libraries and the hash of the runtime code. The sources are read from the
+//! repository and must hash to the values in the metadata, so the payload
+//! describes exactly the compiled code.
+//!
+//! Libraries are linked by the explorer rather than through
+//! `settings.libraries`, which would change the metadata hash appended to the
+//! bytecode. [`self_check`] recompiles each input with a local solc and
+//! compares the linked runtime code hash with the recorded one, without a node.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use alloy::primitives::{keccak256, Address, B256};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::artifact::{link, Artifact, LinkReference, STWO_VERIFIER, VERIFIER_LIBRARIES};
+use crate::deploy::DeploymentResult;
+
+/// Repository root, which the paths in the artifacts' metadata are relative to
+pub const REPO_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+/// Compiler version of `foundry.toml`
+pub const SOLC_VERSION: &str = "0.8.26";
+
+/// What an explorer needs besides the compiler input to verify one contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplorerPayload {
+    /// `path:Name` of the contract
+    pub contract: String,
+    pub address: Address,
+    /// Full compiler version, e.g. `v0.8.26+commit.8a97fa7a`
+    pub compiler_version: String,
+    /// `path:Name` of each linked library => address
+    pub libraries: BTreeMap<String, Address>,
+    /// ABI-encoded constructor arguments as hex, without `0x`
+    pub constructor_args: String,
+    /// keccak256 of the runtime code with the libraries linked and the
+    /// immutables left zero
+    pub runtime_code_hash: B256,
+}
+
+/// Standard-JSON compiler input reproducing the compilation of `artifact`,
+/// with the sources read from `root`
+pub fn standard_json_input(artifact: &Artifact, root: &Path) -> Result<Value> {
+    let metadata = artifact.metadata()?;
+    let mut sources = serde_json::Map::new();
+    let listed = metadata["sources"]
+        .as_object()
+        .ok_or_else(|| anyhow!("Metadata of {} lists no sources", artifact.name))?;
+    for (path, source) in listed {
+        let content = std::fs::read_to_string(root.join(path))
+            .map_err(|e| anyhow!("Cannot read source {}: {}", path, e))?;
+        let expected = source["keccak256"].as_str().unwrap_or_default();
+        if expected.parse::<B256>().ok() != Some(keccak256(content.as_bytes())) {
+            bail!(
+                "{} changed since {} was compiled; run forge build",
+                path,
+                artifact.name
+            );
+        }
+        sources.insert(path.clone(), json!({ "content": content }));
+    }
+
+    let mut settings = metadata["settings"].clone();
+    let settings_map = settings
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Metadata of {} has no settings", artifact.name))?;
+    // Metadata-only field; solc takes the target from outputSelection
+    settings_map.remove("compilationTarget");
+    settings_map.insert(
+        "outputSelection".to_string(),
+        json!({ "*": { "*": ["abi", "evm.bytecode", "evm.deployedBytecode", "metadata"] } }),
+    );
+
+    Ok(json!({
+        "language": metadata["language"].as_str().unwrap_or("Solidity"),
+        "sources": sources,
+        "settings": settings,
+    }))
+}
+
+impl ExplorerPayload {
+    pub fn new(
+        artifact: &Artifact,
+        address: Address,
+        libraries: &BTreeMap<String, Address>,
+        constructor_args: &[u8],
+    ) -> Result<Self> {
+        let version = artifact.metadata()?["compiler"]["version"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Metadata of {} has no compiler version", artifact.name))?
+            .to_string();
+        if !version.starts_with(SOLC_VERSION) {
+            bail!(
+                "{} was compiled with solc {}, expected {}",
+                artifact.name,
+                version,
+                SOLC_VERSION
+            );
+        }
+
+        let references = artifact.deployed_link_references()?;
+        let mut qualified = BTreeMap::new();
+        for reference in &references {
+            let library = VERIFIER_LIBRARIES
+                .iter()
+                .find(|library| library.name == reference.library)
+                .ok_or_else(|| anyhow!("Unknown library {}", reference.library))?;
+            let address = libraries
+                .get(&reference.library)
+                .ok_or_else(|| anyhow!("No address for library {}", reference.library))?;
+            qualified.insert(library.qualified_name()?, *address);
+        }
+        let runtime = link(&artifact.deployed_bytecode_hex()?, &references, libraries)?;
+
+        Ok(Self {
+            contract: artifact.qualified_name()?,
+            address,
+            compiler_version: format!("v{}", version),
+            libraries: qualified,
+            constructor_args: hex::encode(constructor_args),
+            runtime_code_hash: keccak256(&runtime),
+        })
+    }
+
+    /// Library name => address, as [`link`] takes them
+    fn library_addresses(&self) -> BTreeMap<String, Address> {
+        self.libraries
+            .iter()
+            .map(|(name, address)| {
+                let name = name.rsplit(':').next().unwrap_or(name);
+                (name.to_string(), *address)
+            })
+            .collect()
+    }
+}
+
+/// Every contract of a deployment with its constructor arguments, in deployment order
+pub fn deployed_contracts(result: &DeploymentResult) -> Result<Vec<(Artifact, Address, Vec<u8>)>> {
+    let mut contracts = Vec::new();
+    for library in VERIFIER_LIBRARIES {
+        let address = result
+            .libraries
+            .get(library.name)
+            .ok_or_else(|| anyhow!("Deployment has no {}", library.name))?;
+        contracts.push((library, *address, Vec::new()));
+    }
+    match &result.proxy {
+        Some(proxy) => {
+            contracts.push((
+                proxy.kind.implementation(),
+                proxy.implementation,
+                Vec::new(),
+            ));
+            contracts.push((
+                proxy.kind.proxy(),
+                proxy.address,
+                proxy
+                    .kind
+                    .constructor_args(proxy.implementation, proxy.owner),
+            ));
+        }
+        None => contracts.push((STWO_VERIFIER, result.verifier_address, Vec::new())),
+    }
+    Ok(contracts)
+}
+
+/// Write `<Name>.input.json`, `<Name>.args` and `<Name>.json` into `dir` for
+/// each contract of `result`, and return the payloads
+pub fn write_payloads(dir: &Path, result: &DeploymentResult) -> Result<Vec<ExplorerPayload>> {
+    std::fs::create_dir_all(dir)?;
+    let root = Path::new(REPO_ROOT);
+    let mut payloads = Vec::new();
+    for (artifact, address, args) in deployed_contracts(result)? {
+        let payload = ExplorerPayload::new(&artifact, address, &result.libraries, &args)?;
+        let input = standard_json_input(&artifact, root)?;
+        std::fs::write(
+            dir.join(format!("{}.input.json", artifact.name)),
+            serde_json::to_string_pretty(&input)?,
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.args", artifact.name)),
+            &payload.constructor_args,
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.json", artifact.name)),
+            serde_json::to_string_pretty(&payload)?,
+        )?;
+        info!(contract = artifact.name, dir = %dir.display(), "Explorer payload written");
+        payloads.push(payload);
+    }
+    Ok(payloads)
+}
+
+/// solc to recompile with: `SOLC`, foundry's svm install of [`SOLC_VERSION`], or `solc`
+pub fn find_solc() -> PathBuf {
+    if let Ok(solc) = std::env::var("SOLC") {
+        return PathBuf::from(solc);
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        let svm = Path::new(&home)
+            .join(".svm")
+            .join(SOLC_VERSION)
+            .join(format!("solc-{}", SOLC_VERSION));
+        if svm.exists() {
+            return svm;
+        }
+    }
+    PathBuf::from("solc")
+}
+
+/// Run `solc --standard-json` on `input` and return its output
+pub fn compile(solc: &Path, input: &Value) -> Result<Value> {
+    let mut child = Command::new(solc)
+        .arg("--standard-json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Cannot run {}: {}", solc.display(), e))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("No stdin for solc"))?
+        .write_all(serde_json::to_string(input)?.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("solc failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let output: Value = serde_json::from_slice(&output.stdout)?;
+    let errors: Vec<&str> = output["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|error| error["severity"] == "error")
+        .filter_map(|error| error["formattedMessage"].as_str())
+        .collect();
+    if !errors.is_empty() {
+        bail!("solc reported errors:\n{}", errors.join("\n"));
+    }
+    Ok(output)
+}
+
+/// Result of recompiling one payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelfCheck {
+    pub contract: String,
+    pub expected: B256,
+    pub recompiled: B256,
+}
+
+impl SelfCheck {
+    pub fn matches(&self) -> bool {
+        self.expected == self.recompiled
+    }
+}
+
+/// Recompile every payload in `dir` with `solc` and compare the runtime code
+/// hash with the recorded one. Needs no node.
+pub fn self_check(dir: &Path, solc: &Path) -> Result<Vec<SelfCheck>> {
+    let mut checks = Vec::new();
+    let mut summaries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    summaries.retain(|path| {
+        let name = path.to_string_lossy();
+        name.ends_with(".json") && !name.ends_with(".input.json")
+    });
+    summaries.sort();
+
+    for summary in summaries {
+        let payload: ExplorerPayload = serde_json::from_str(&std::fs::read_to_string(&summary)?)?;
+        let (path, name) = payload
+            .contract
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("Invalid contract name {}", payload.contract))?;
+        let input: Value = serde_json::from_str(&std::fs::read_to_string(
+            summary.with_extension("input.json"),
+        )?)?;
+        info!(contract = %payload.contract, "Recompiling");
+
+        let output = compile(solc, &input)?;
+        let deployed = &output["contracts"][path][name]["evm"]["deployedBytecode"];
+        let object = deployed["object"]
+            .as_str()
+            .ok_or_else(|| anyhow!("solc produced no bytecode for {}", payload.contract))?;
+        let references: Vec<LinkReference> = deployed["linkReferences"]
+            .as_object()
+            .into_iter()
+            .flat_map(|files| files.values())
+            .filter_map(Value::as_object)
+            .flat_map(|libraries| {
+                libraries.iter().flat_map(|(library, offsets)| {
+                    offsets
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(|offset| LinkReference {
+                            library: library.clone(),
+                            start: offset["start"].as_u64().unwrap_or_default() as usize,
+                            length: offset["length"].as_u64().unwrap_or_default() as usize,
+                        })
+                })
+            })
+            .collect();
+        let runtime = link(object, &references, &payload.library_addresses())?;
+
+        checks.push(SelfCheck {
+            contract: payload.contract.clone(),
+            expected: payload.runtime_code_hash,
+            recompiled: keccak256(&runtime),
+        });
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_matches_artifact() {
+        let libraries: BTreeMap<String, Address> = VERIFIER_LIBRARIES
+            .iter()
+            .enumerate()
+            .map(|(i, library)| (library.name.to_string(), Address::repeat_byte(i as u8 + 1)))
+            .collect();
+        let payload =
+            ExplorerPayload::new(&STWO_VERIFIER, Address::repeat_byte(9), &libraries, &[]).unwrap();
+        assert_eq!(
+            payload.contract,
+            "contracts/verifier/StwoVerifier.sol:STWOVerifier"
+        );
+        assert!(payload.compiler_version.starts_with("v0.8.26+commit."));
+        assert_eq!(payload.library_addresses(), libraries);
+        assert!(payload.constructor_args.is_empty());
+
+        let input = standard_json_input(&STWO_VERIFIER, Path::new(REPO_ROOT)).unwrap();
+        assert_eq!(input["settings"]["viaIR"], true);
+        assert_eq!(input["settings"]["optimizer"]["runs"], 200);
+        assert!(input["settings"].get("compilationTarget").is_none());
+        assert!(input["sources"]
+            .get("contracts/verifier/StwoVerifier.sol")
+            .is_some());
+    }
+}
@@ -0,0 +1,85 @@
+//! Stored proofs used to check that a verifier implementation still accepts
+//! what the current one accepts.
+
+use std::path::Path;
+
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, Bytes},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    sol_types::SolCall,
+};
+use anyhow::{anyhow, Result};
+use contracts::{STWOVerifier, VerifierInput};
+use serde::{Deserialize, Serialize};
+
+/// `verify` calldata of a proof the verifier must accept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofFixture {
+    /// Circuit and parameters the proof was generated with
+    pub description: String,
+    pub calldata: Bytes,
+}
+
+impl ProofFixture {
+    pub fn from_input(description: impl Into<String>, input: &VerifierInput) -> Self {
+        let calldata = STWOVerifier::verifyCall {
+            proof: input.proof.clone(),
+            params: input.verificationParams.clone(),
+            treeRoots: input.treeRoots.clone(),
+            treeColumnLogSizes: input.treeColumnLogSizes.clone(),
+            digest: input.digest,
+            nDraws: input.nDraws,
+        }
+        .abi_encode();
+        Self {
+            description: description.into(),
+            calldata: calldata.into(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read fixture {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the verifier at `address` accepts the proof, simulated with
+    /// `eth_call` from the zero address so a transparent proxy admin never
+    /// intercepts it
+    pub async fn is_accepted_by(&self, provider: &impl Provider, address: Address) -> Result<bool> {
+        let call = TransactionRequest::default()
+            .with_from(Address::ZERO)
+            .with_to(address)
+            .with_input(self.calldata.clone());
+        match provider.call(call).await {
+            Ok(output) => Ok(STWOVerifier::verifyCall::abi_decode_returns(&output)?),
+            // A rejected proof usually reverts rather than returning false
+            Err(e) if e.as_error_resp().is_some() => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let fixture = ProofFixture {
+            description: "fibonacci n=10".to_string(),
+            calldata: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        };
+        let path = std::env::temp_dir().join("stwo_fixture_round_trip.json");
+        fixture.save(&path).unwrap();
+        assert_eq!(ProofFixture::load(&path).unwrap(), fixture);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,420 @@
+//! Fee selection for deployment and proof submission transactions.
+//!
+//! A [`GasStrategy`] prices each transaction: the provider's EIP-1559 oracle,
+//! fixed fees, a percentile of recent priority fees, or a legacy gas price.
+//! [`GasOptions::max_fee_cap`] bounds whatever the strategy picks, and with
+//! [`GasOptions::replace_after`] a transaction still pending after the timeout
+//! is sent again with the same nonce and bumped fees.
+
+use std::fmt;
+use std::time::Duration;
+
+use alloy::{
+    eips::BlockNumberOrTag, primitives::utils::parse_units, providers::Provider,
+    rpc::types::TransactionRequest,
+};
+use anyhow::{anyhow, bail, Result};
+use clap::{Arg, ArgMatches};
+use serde::Deserialize;
+
+/// How the fees of a transaction are chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasStrategy {
+    /// EIP-1559 fees from the provider's `eth_feeHistory` estimator
+    Oracle,
+    /// EIP-1559 fees given in wei
+    Fixed {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+    /// Priority fee at `percentile` of the rewards paid in the last `blocks`
+    /// blocks, on top of twice the next base fee
+    Percentile { percentile: u8, blocks: u64 },
+    /// Type 0 transaction at `gas_price` wei, `eth_gasPrice` when `None`
+    Legacy { gas_price: Option<u128> },
+}
+
+impl fmt::Display for GasStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GasStrategy::Oracle => write!(f, "oracle"),
+            GasStrategy::Fixed { .. } => write!(f, "fixed"),
+            GasStrategy::Percentile { percentile, blocks } => {
+                write!(f, "percentile (p{} of {} blocks)", percentile, blocks)
+            }
+            GasStrategy::Legacy { .. } => write!(f, "legacy"),
+        }
+    }
+}
+
+/// Fees set on a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+    Legacy {
+        gas_price: u128,
+    },
+}
+
+impl Fees {
+    /// Highest price per gas the transaction may pay
+    pub fn max_fee_per_gas(&self) -> u128 {
+        match *self {
+            Fees::Eip1559 {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas,
+            Fees::Legacy { gas_price } => gas_price,
+        }
+    }
+
+    /// Both fees raised by `percent`, at least by one wei
+    pub fn bumped(&self, percent: u64) -> Self {
+        let bump = |fee: u128| fee + (fee * percent as u128 / 100).max(1);
+        match *self {
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Fees::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: bump(gas_price),
+            },
+        }
+    }
+
+    /// Fees with the price per gas limited to `cap` wei
+    pub fn capped(&self, cap: Option<u128>) -> Self {
+        let Some(cap) = cap else {
+            return *self;
+        };
+        match *self {
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Fees::Eip1559 {
+                max_fee_per_gas: max_fee_per_gas.min(cap),
+                max_priority_fee_per_gas: max_priority_fee_per_gas.min(cap),
+            },
+            Fees::Legacy { gas_price } => Fees::Legacy {
+                gas_price: gas_price.min(cap),
+            },
+        }
+    }
+
+    /// Set the fee fields of `tx`, clearing those of the other transaction type
+    pub fn apply(&self, tx: &mut TransactionRequest) {
+        match *self {
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                tx.gas_price = None;
+                tx.max_fee_per_gas = Some(max_fee_per_gas);
+                tx.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+            }
+            Fees::Legacy { gas_price } => {
+                tx.gas_price = Some(gas_price);
+                tx.max_fee_per_gas = None;
+                tx.max_priority_fee_per_gas = None;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Fees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => write!(
+                f,
+                "max fee {} wei, priority fee {} wei",
+                max_fee_per_gas, max_priority_fee_per_gas
+            ),
+            Fees::Legacy { gas_price } => write!(f, "gas price {} wei", gas_price),
+        }
+    }
+}
+
+/// Fee strategy, cap and replacement policy of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasOptions {
+    pub strategy: GasStrategy,
+    /// Upper bound in wei on the max fee per gas or gas price, replacements included
+    pub max_fee_cap: Option<u128>,
+    /// Replace a transaction still pending after this long; wait forever when `None`
+    pub replace_after: Option<Duration>,
+    /// Fee increase of each replacement; nodes require at least 10
+    pub bump_percent: u64,
+    /// Replacements sent before giving up
+    pub max_replacements: u32,
+}
+
+impl Default for GasOptions {
+    fn default() -> Self {
+        Self {
+            strategy: GasStrategy::Oracle,
+            max_fee_cap: None,
+            replace_after: None,
+            bump_percent: 20,
+            max_replacements: 3,
+        }
+    }
+}
+
+impl GasOptions {
+    /// Fees for the next transaction under the strategy, limited by the cap
+    pub async fn fees(&self, provider: &impl Provider) -> Result<Fees> {
+        let fees = match self.strategy {
+            GasStrategy::Oracle => {
+                let estimate = provider.estimate_eip1559_fees().await?;
+                Fees::Eip1559 {
+                    max_fee_per_gas: estimate.max_fee_per_gas,
+                    max_priority_fee_per_gas: estimate.max_priority_fee_per_gas,
+                }
+            }
+            GasStrategy::Fixed {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            },
+            GasStrategy::Percentile { percentile, blocks } => {
+                let history = provider
+                    .get_fee_history(blocks, BlockNumberOrTag::Latest, &[percentile as f64])
+                    .await?;
+                let base_fee = history
+                    .next_block_base_fee()
+                    .ok_or_else(|| anyhow!("Node returned no base fee history"))?;
+                // Median over the blocks of each block's percentile reward
+                let mut rewards: Vec<u128> = history
+                    .reward
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|block| block.first().copied())
+                    .collect();
+                rewards.sort_unstable();
+                let priority_fee = rewards.get(rewards.len() / 2).copied().unwrap_or(0);
+                Fees::Eip1559 {
+                    max_fee_per_gas: 2 * base_fee + priority_fee,
+                    max_priority_fee_per_gas: priority_fee,
+                }
+            }
+            GasStrategy::Legacy { gas_price } => Fees::Legacy {
+                gas_price: match gas_price {
+                    Some(gas_price) => gas_price,
+                    None => provider.get_gas_price().await?,
+                },
+            },
+        };
+        Ok(fees.capped(self.max_fee_cap))
+    }
+
+    /// Fees of the replacement for a transaction sent with `fees`, `None`
+    /// once the cap leaves no room for the required increase
+    pub fn replacement(&self, fees: &Fees) -> Option<Fees> {
+        let bumped = fees.bumped(self.bump_percent);
+        let capped = bumped.capped(self.max_fee_cap);
+        (capped == bumped).then_some(bumped)
+    }
+}
+
+/// Gas fields of a network profile or of the command line. Amounts are in
+/// gwei and may have decimals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasConfig {
+    /// `oracle`, `fixed`, `percentile` or `legacy`
+    pub strategy: Option<String>,
+    pub max_fee: Option<String>,
+    pub priority_fee: Option<String>,
+    pub gas_price: Option<String>,
+    pub percentile: Option<u8>,
+    /// Blocks of fee history for `percentile`
+    pub blocks: Option<u64>,
+    pub max_fee_cap: Option<String>,
+    /// Seconds before a pending transaction is replaced
+    pub replace_after: Option<u64>,
+    pub bump_percent: Option<u64>,
+    pub max_replacements: Option<u32>,
+}
+
+impl GasConfig {
+    /// Command line options for the fields commonly set per run
+    pub fn args() -> [Arg; 7] {
+        [
+            Arg::new("gas-strategy")
+                .long("gas-strategy")
+                .help("Fee strategy: oracle, fixed, percentile or legacy (default: oracle)")
+                .value_name("STRATEGY"),
+            Arg::new("max-fee")
+                .long("max-fee")
+                .help("Max fee per gas in gwei for the fixed strategy")
+                .value_name("GWEI"),
+            Arg::new("priority-fee")
+                .long("priority-fee")
+                .help("Max priority fee per gas in gwei for the fixed strategy")
+                .value_name("GWEI"),
+            Arg::new("gas-price")
+                .long("gas-price")
+                .help("Gas price in gwei for the legacy strategy and deploy --dry-run costs")
+                .value_name("GWEI"),
+            Arg::new("fee-percentile")
+                .long("fee-percentile")
+                .help("Priority fee percentile of recent blocks for the percentile strategy")
+                .value_name("PERCENT")
+                .value_parser(clap::value_parser!(u8).range(0..=100)),
+            Arg::new("max-fee-cap")
+                .long("max-fee-cap")
+                .help("Never pay more than this many gwei per gas, replacements included")
+                .value_name("GWEI"),
+            Arg::new("replace-after")
+                .long("replace-after")
+                .help("Resend a transaction with bumped fees when pending this many seconds")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64)),
+        ]
+    }
+
+    /// Fields given with [`GasConfig::args`]
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let option = |name: &str| matches.get_one::<String>(name).cloned();
+        Self {
+            strategy: option("gas-strategy"),
+            max_fee: option("max-fee"),
+            priority_fee: option("priority-fee"),
+            gas_price: option("gas-price"),
+            percentile: matches.get_one::<u8>("fee-percentile").copied(),
+            max_fee_cap: option("max-fee-cap"),
+            replace_after: matches.get_one::<u64>("replace-after").copied(),
+            ..Self::default()
+        }
+    }
+
+    /// `self` with the fields set in `other` replaced
+    pub fn merge(self, other: GasConfig) -> Self {
+        Self {
+            strategy: other.strategy.or(self.strategy),
+            max_fee: other.max_fee.or(self.max_fee),
+            priority_fee: other.priority_fee.or(self.priority_fee),
+            gas_price: other.gas_price.or(self.gas_price),
+            percentile: other.percentile.or(self.percentile),
+            blocks: other.blocks.or(self.blocks),
+            max_fee_cap: other.max_fee_cap.or(self.max_fee_cap),
+            replace_after: other.replace_after.or(self.replace_after),
+            bump_percent: other.bump_percent.or(self.bump_percent),
+            max_replacements: other.max_replacements.or(self.max_replacements),
+        }
+    }
+
+    pub fn options(&self) -> Result<GasOptions> {
+        let gwei = |value: &Option<String>| value.as_deref().map(parse_gwei).transpose();
+        let strategy = match self.strategy.as_deref().unwrap_or("oracle") {
+            "oracle" => GasStrategy::Oracle,
+            "fixed" => {
+                let max_fee_per_gas = gwei(&self.max_fee)?
+                    .ok_or_else(|| anyhow!("The fixed gas strategy needs max-fee"))?;
+                let max_priority_fee_per_gas = gwei(&self.priority_fee)?
+                    .ok_or_else(|| anyhow!("The fixed gas strategy needs priority-fee"))?;
+                if max_priority_fee_per_gas > max_fee_per_gas {
+                    bail!("priority-fee is above max-fee");
+                }
+                GasStrategy::Fixed {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                }
+            }
+            "percentile" => GasStrategy::Percentile {
+                percentile: self.percentile.unwrap_or(50),
+                blocks: self.blocks.unwrap_or(20),
+            },
+            "legacy" => GasStrategy::Legacy {
+                gas_price: gwei(&self.gas_price)?,
+            },
+            other => bail!(
+                "Unknown gas strategy: {} (expected oracle, fixed, percentile or legacy)",
+                other
+            ),
+        };
+        if self.percentile.is_some_and(|percentile| percentile > 100) {
+            bail!("percentile must be at most 100");
+        }
+
+        let defaults = GasOptions::default();
+        Ok(GasOptions {
+            strategy,
+            max_fee_cap: gwei(&self.max_fee_cap)?,
+            replace_after: self.replace_after.map(Duration::from_secs),
+            bump_percent: self.bump_percent.unwrap_or(defaults.bump_percent),
+            max_replacements: self.max_replacements.unwrap_or(defaults.max_replacements),
+        })
+    }
+}
+
+/// Wei in `value` gwei
+pub fn parse_gwei(value: &str) -> Result<u128> {
+    let wei = parse_units(value.trim(), "gwei")
+        .map_err(|e| anyhow!("Invalid gwei amount {}: {}", value, e))?
+        .get_absolute();
+    u128::try_from(wei).map_err(|_| anyhow!("Gwei amount {} is too large", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_config() {
+        let config: GasConfig = toml::from_str(
+            r#"
+            strategy = "fixed"
+            max_fee = "30"
+            priority_fee = "1.5"
+            max_fee_cap = "40"
+            replace_after = 60
+            "#,
+        )
+        .unwrap();
+        let options = config.options().unwrap();
+        assert_eq!(
+            options.strategy,
+            GasStrategy::Fixed {
+                max_fee_per_gas: 30_000_000_000,
+                max_priority_fee_per_gas: 1_500_000_000,
+            }
+        );
+        assert_eq!(options.replace_after, Some(Duration::from_secs(60)));
+
+        // 30 -> 36 gwei fits the cap, the next bump to 43.2 does not
+        let first = Fees::Eip1559 {
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_500_000_000,
+        };
+        let second = options.replacement(&first).unwrap();
+        assert_eq!(second.max_fee_per_gas(), 36_000_000_000);
+        assert!(options.replacement(&second).is_none());
+
+        let legacy = config.merge(GasConfig {
+            strategy: Some("legacy".to_string()),
+            ..GasConfig::default()
+        });
+        assert_eq!(
+            legacy.options().unwrap().strategy,
+            GasStrategy::Legacy { gas_price: None }
+        );
+        for strategy in ["fastest", "fixed"] {
+            let config = GasConfig {
+                strategy: Some(strategy.to_string()),
+                ..GasConfig::default()
+            };
+            assert!(config.options().is_err());
+        }
+    }
+}
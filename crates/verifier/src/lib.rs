@@ -1 +1,15 @@
-pub mod deploy;
\ No newline at end of file
+pub mod artifact;
+pub mod check;
+pub mod deploy;
+pub mod devnet;
+pub mod dry_run;
+pub mod explorer;
+pub mod fixture;
+pub mod gas;
+pub mod logging;
+pub mod manifest;
+pub mod network;
+pub mod proxy;
+pub mod signer;
+pub mod submit;
+pub mod tx;
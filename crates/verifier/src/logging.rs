@@ -0,0 +1,120 @@
+//! Log output and machine-readable results for the binaries.
+//!
+//! Library code only emits `tracing` events and spans; binaries install a
+//! subscriber with [`init`], which writes logs to stderr so stdout carries
+//! nothing but the result. With `--json`, logs are JSON lines and the result is
+//! a single object printed by [`print_json`]. `RUST_LOG` overrides the default
+//! `info` level.
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Arg, ArgAction};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing_subscriber::EnvFilter;
+
+use crate::deploy::DeploymentResult;
+
+/// The `--json` flag shared by the binaries
+pub fn json_arg() -> Arg {
+    Arg::new("json")
+        .long("json")
+        .action(ArgAction::SetTrue)
+        .help("Log JSON lines to stderr and print a single JSON result object to stdout")
+}
+
+/// Install the global subscriber, logging to stderr as text or JSON lines
+pub fn init(json: bool) -> Result<()> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    let installed = if json {
+        builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init()
+    } else {
+        builder.with_target(false).try_init()
+    };
+    installed.map_err(|e| anyhow!("Cannot install the log subscriber: {}", e))
+}
+
+/// Print `result` to stdout as one JSON object
+pub fn print_json<T: Serialize>(result: &T) -> Result<()> {
+    println!("{}", serde_json::to_string(result)?);
+    Ok(())
+}
+
+/// Result object of an example run: the verifier deployment, the example's own
+/// `fields` and the `verification` outcome, which holds a `passed` flag
+pub fn example_result(
+    deployment: &DeploymentResult,
+    fields: Value,
+    verification: &impl Serialize,
+) -> Result<Value> {
+    let Value::Object(fields) = fields else {
+        bail!("Example result fields must be a JSON object");
+    };
+    let mut result = json!({
+        "verifier_address": deployment.verifier_address,
+        "chain_id": deployment.chain_id,
+        "deployment": deployment.transactions,
+        "verification": verification,
+    });
+    for (key, value) in fields {
+        if result.get(&key).is_some() {
+            bail!("Example result field {} is reserved", key);
+        }
+        result[key] = value;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    #[test]
+    fn test_example_result_shape() {
+        let deployment = DeploymentResult {
+            verifier_address: Address::repeat_byte(1),
+            libraries: Default::default(),
+            chain_id: Some(31337),
+            block_number: Some(3),
+            proxy: None,
+            transactions: Vec::new(),
+        };
+        let result = example_result(
+            &deployment,
+            json!({ "public_inputs": [1, 2], "proving_ms": 40 }),
+            &json!({ "passed": true, "gas_used": 1000 }),
+        )
+        .unwrap();
+
+        let object = result.as_object().unwrap();
+        let mut keys: Vec<&str> = object.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "chain_id",
+                "deployment",
+                "proving_ms",
+                "public_inputs",
+                "verification",
+                "verifier_address"
+            ]
+        );
+        assert_eq!(result["verification"]["passed"], true);
+        assert_eq!(result["chain_id"], 31337);
+        assert_eq!(
+            serde_json::from_value::<Address>(result["verifier_address"].clone()).unwrap(),
+            deployment.verifier_address
+        );
+
+        assert!(example_result(&deployment, json!([1]), &json!({})).is_err());
+        assert!(example_result(&deployment, json!({ "chain_id": 1 }), &json!({})).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Deployment manifest: the addresses of a verifier deployment and, behind a
+//! proxy, every implementation it has pointed to.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use alloy::primitives::{Address, TxHash};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::deploy::DeploymentResult;
+use crate::proxy::ProxyRecord;
+
+/// One implementation a proxy pointed to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImplementationRecord {
+    pub implementation: Address,
+    /// Linked library name => address
+    pub libraries: BTreeMap<String, Address>,
+    /// Block the proxy switched to this implementation in
+    pub block_number: Option<u64>,
+    /// Description of the proof fixture the implementation accepted before the
+    /// switch, `None` for the first implementation
+    pub fixture: Option<String>,
+    /// Transaction that switched the proxy, `None` for the first implementation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<TxHash>,
+}
+
+/// Addresses of one verifier deployment, stored as JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentManifest {
+    pub chain_id: Option<u64>,
+    /// Address consumers call: the proxy when there is one
+    pub verifier_address: Address,
+    /// Libraries of the current implementation
+    pub libraries: BTreeMap<String, Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyRecord>,
+    /// Implementations behind the proxy, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<ImplementationRecord>,
+}
+
+impl DeploymentManifest {
+    pub fn from_result(result: &DeploymentResult) -> Self {
+        let history = result
+            .proxy
+            .iter()
+            .map(|proxy| ImplementationRecord {
+                implementation: proxy.implementation,
+                libraries: result.libraries.clone(),
+                block_number: result.block_number,
+                fixture: None,
+                tx_hash: None,
+            })
+            .collect();
+        Self {
+            chain_id: result.chain_id,
+            verifier_address: result.verifier_address,
+            libraries: result.libraries.clone(),
+            proxy: result.proxy.clone(),
+            history,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read manifest {}: {}", path.display(), e))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Point the proxy at the implementation of `record` and append it to the history
+    pub fn record_upgrade(&mut self, record: ImplementationRecord) -> Result<()> {
+        let proxy = self
+            .proxy
+            .as_mut()
+            .ok_or_else(|| anyhow!("Deployment at {:?} has no proxy", self.verifier_address))?;
+        proxy.implementation = record.implementation;
+        self.libraries = record.libraries.clone();
+        self.history.push(record);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::ProxyKind;
+
+    #[test]
+    fn test_manifest_records_upgrades() {
+        let libraries = BTreeMap::from([("OodsModule".to_string(), Address::repeat_byte(1))]);
+        let result = DeploymentResult {
+            verifier_address: Address::repeat_byte(2),
+            libraries: libraries.clone(),
+            chain_id: Some(31337),
+            block_number: Some(5),
+            proxy: Some(ProxyRecord {
+                kind: ProxyKind::Uups,
+                address: Address::repeat_byte(2),
+                implementation: Address::repeat_byte(3),
+                owner: Address::repeat_byte(4),
+            }),
+            transactions: Vec::new(),
+        };
+        let mut manifest = DeploymentManifest::from_result(&result);
+        assert_eq!(manifest.history.len(), 1);
+
+        manifest
+            .record_upgrade(ImplementationRecord {
+                implementation: Address::repeat_byte(5),
+                libraries: BTreeMap::new(),
+                block_number: Some(9),
+                fixture: Some("fibonacci n=10".to_string()),
+                tx_hash: Some(TxHash::repeat_byte(6)),
+            })
+            .unwrap();
+        assert_eq!(
+            manifest.proxy.as_ref().unwrap().implementation,
+            Address::repeat_byte(5)
+        );
+        assert_eq!(manifest.history.len(), 2);
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DeploymentManifest>(&json).unwrap(),
+            manifest
+        );
+
+        let mut plain = DeploymentManifest::from_result(&DeploymentResult {
+            proxy: None,
+            ..result
+        });
+        assert!(plain.history.is_empty());
+        assert!(plain.record_upgrade(manifest.history[1].clone()).is_err());
+    }
+}
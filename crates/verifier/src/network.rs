@@ -0,0 +1,182 @@
+//! Named network profiles read from `stwo-sol.toml`.
+//!
+//! ```toml
+//! [networks.local]
+//! gas_limit = "1000000000"
+//!
+//! [networks.sepolia]
+//! rpc_url = "https://sepolia.example.org"
+//! chain_id = 11155111
+//! verifier_address = "0x..."
+//! confirmations = 2
+//! signer = { keystore = "~/.foundry/keystores/deployer", password_env = "KEYSTORE_PASSWORD" }
+//! gas = { strategy = "percentile", percentile = 60, max_fee_cap = "50", replace_after = 120 }
+//! ```
+//!
+//! A profile without `rpc_url` starts a new Anvil instance, configured by the
+//! Anvil fields over the caller's defaults. The file is `stwo-sol.toml` in the
+//! working directory unless `STWO_SOL_CONFIG` names another one.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use alloy::primitives::Address;
+use anyhow::{anyhow, Result};
+use clap::Arg;
+use serde::Deserialize;
+
+use crate::deploy::AnvilConfig;
+use crate::gas::GasConfig;
+use crate::signer::SignerConfig;
+
+/// Config file read when `STWO_SOL_CONFIG` is not set
+pub const DEFAULT_CONFIG_PATH: &str = "stwo-sol.toml";
+/// Environment variable overriding the config file path
+pub const CONFIG_PATH_ENV: &str = "STWO_SOL_CONFIG";
+
+/// One network the tools can deploy to and submit proofs on
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkProfile {
+    /// Node to connect to; a new Anvil instance when absent
+    pub rpc_url: Option<String>,
+    /// Expected chain id, checked before sending anything
+    pub chain_id: Option<u64>,
+    /// Signer; Anvil's first account when absent on a local network
+    pub signer: Option<SignerConfig>,
+    /// Verifier already deployed on the network, reused when its code matches
+    pub verifier_address: Option<Address>,
+    /// Blocks to wait for after a transaction is included
+    pub confirmations: Option<u64>,
+    /// Fee strategy, cap and replacement policy
+    pub gas: Option<GasConfig>,
+    /// Anvil block time in seconds
+    pub block_time: Option<u64>,
+    /// Anvil code size limit in bytes
+    pub code_size_limit: Option<u64>,
+    /// Anvil block gas limit
+    pub gas_limit: Option<String>,
+    /// URL Anvil forks from
+    pub fork_url: Option<String>,
+}
+
+impl NetworkProfile {
+    /// `defaults` with the Anvil fields set in the profile applied
+    pub fn anvil_config(&self, defaults: AnvilConfig) -> AnvilConfig {
+        AnvilConfig {
+            block_time: self.block_time.unwrap_or(defaults.block_time),
+            code_size_limit: self.code_size_limit.unwrap_or(defaults.code_size_limit),
+            gas_limit: self.gas_limit.clone().unwrap_or(defaults.gas_limit),
+            fork_url: self.fork_url.clone().or(defaults.fork_url),
+        }
+    }
+}
+
+/// Contents of `stwo-sol.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkProfile>,
+}
+
+impl NetworkConfig {
+    /// Path of the config file: `STWO_SOL_CONFIG` or `stwo-sol.toml`
+    pub fn default_path() -> PathBuf {
+        std::env::var(CONFIG_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_CONFIG_PATH))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Cannot read {}: {}", path.display(), e))?;
+        toml::from_str(&contents).map_err(|e| anyhow!("Invalid {}: {}", path.display(), e))
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&NetworkProfile> {
+        self.networks.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.networks.keys().map(String::as_str).collect();
+            anyhow!(
+                "Unknown network {}; configured networks: {}",
+                name,
+                known.join(", ")
+            )
+        })
+    }
+}
+
+/// `--network NAME` option shared by the binaries and examples
+pub fn network_arg() -> Arg {
+    Arg::new("network")
+        .long("network")
+        .help("Network profile from stwo-sol.toml (default: a new Anvil instance)")
+        .value_name("NAME")
+}
+
+/// Profile `name` from the default config file
+pub fn load_network(name: &str) -> Result<NetworkProfile> {
+    NetworkConfig::load(&NetworkConfig::default_path())?
+        .profile(name)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::SignerSource;
+
+    #[test]
+    fn test_parse_profiles() {
+        let config: NetworkConfig = toml::from_str(
+            r#"
+            [networks.local]
+            gas_limit = "1000000000"
+
+            [networks.sepolia]
+            rpc_url = "https://sepolia.example.org"
+            chain_id = 11155111
+            verifier_address = "0x00000000000000000000000000000000000000aa"
+            confirmations = 2
+            signer = { mnemonic_env = "MNEMONIC" }
+            gas = { strategy = "legacy", gas_price = "2" }
+            "#,
+        )
+        .unwrap();
+
+        let local = config.profile("local").unwrap();
+        assert!(local.rpc_url.is_none());
+        let anvil = local.anvil_config(AnvilConfig {
+            block_time: 3,
+            code_size_limit: 100000,
+            gas_limit: "30000000".to_string(),
+            fork_url: None,
+        });
+        assert_eq!(anvil.block_time, 3);
+        assert_eq!(anvil.gas_limit, "1000000000");
+
+        let sepolia = config.profile("sepolia").unwrap();
+        assert_eq!(sepolia.chain_id, Some(11155111));
+        assert_eq!(sepolia.confirmations, Some(2));
+        assert_eq!(
+            sepolia.gas.as_ref().unwrap().options().unwrap().strategy,
+            crate::gas::GasStrategy::Legacy {
+                gas_price: Some(2_000_000_000)
+            }
+        );
+        assert_eq!(
+            sepolia.signer.as_ref().unwrap().source().unwrap(),
+            Some(SignerSource::Mnemonic {
+                phrase_env: "MNEMONIC".to_string(),
+                derivation_path: crate::signer::DEFAULT_DERIVATION_PATH.to_string(),
+            })
+        );
+
+        assert!(config
+            .profile("mainnet")
+            .unwrap_err()
+            .to_string()
+            .contains("local, sepolia"));
+        assert!(toml::from_str::<NetworkConfig>("[networks.x]\nrpc = \"typo\"").is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Verifier deployments behind an upgradeable proxy.
+//!
+//! Consumers keep calling the proxy address while the verifier implementation
+//! behind it changes. Two proxies are supported:
+//!
+//! - [`ProxyKind::Uups`]: `UUPSVerifierProxy` in front of an
+//!   `UpgradeableSTWOVerifier`, which checks the owner and performs upgrades.
+//! - [`ProxyKind::Transparent`]: `TransparentVerifierProxy` in front of a plain
+//!   `STWOVerifier`; the proxy admin upgrades and cannot call the verifier.
+//!
+//! Before switching, [`upgrade_proxy`] makes sure the new implementation
+//! accepts a stored [`ProofFixture`], and checks it again through the proxy.
+
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::{
+    primitives::{b256, Address, Bytes, B256},
+    providers::Provider,
+    sol,
+    sol_types::{SolCall, SolValue},
+};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::artifact::{
+    Artifact, STWO_VERIFIER, TRANSPARENT_VERIFIER_PROXY, UPGRADEABLE_STWO_VERIFIER,
+    UUPS_VERIFIER_PROXY,
+};
+use crate::deploy::{deploy_code, deploy_linked, DeployedContract};
+use crate::fixture::ProofFixture;
+use crate::manifest::ImplementationRecord;
+use crate::tx::{send_transaction, TxOptions};
+
+sol! {
+    /// Upgrade entry point of both proxies: the implementation's for UUPS,
+    /// the proxy's own for transparent
+    #[sol(rpc)]
+    interface IUpgradeableVerifier {
+        function initialize(address owner) external;
+        function upgradeTo(address newImplementation) external;
+    }
+}
+
+/// EIP-1967 slot both proxies keep the implementation address in
+pub const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// Which proxy a deployment uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    Uups,
+    Transparent,
+}
+
+impl ProxyKind {
+    /// Implementation contract the proxy delegates to
+    pub fn implementation(&self) -> Artifact {
+        match self {
+            ProxyKind::Uups => UPGRADEABLE_STWO_VERIFIER,
+            ProxyKind::Transparent => STWO_VERIFIER,
+        }
+    }
+
+    pub fn proxy(&self) -> Artifact {
+        match self {
+            ProxyKind::Uups => UUPS_VERIFIER_PROXY,
+            ProxyKind::Transparent => TRANSPARENT_VERIFIER_PROXY,
+        }
+    }
+
+    /// ABI-encoded constructor arguments of the proxy
+    pub fn constructor_args(&self, implementation: Address, owner: Address) -> Vec<u8> {
+        match self {
+            ProxyKind::Uups => {
+                let init = IUpgradeableVerifier::initializeCall { owner }.abi_encode();
+                (implementation, Bytes::from(init)).abi_encode_params()
+            }
+            ProxyKind::Transparent => (implementation, owner).abi_encode_params(),
+        }
+    }
+}
+
+impl FromStr for ProxyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "uups" => Ok(ProxyKind::Uups),
+            "transparent" => Ok(ProxyKind::Transparent),
+            _ => Err(format!(
+                "Unknown proxy kind: {} (expected uups or transparent)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyKind::Uups => write!(f, "uups"),
+            ProxyKind::Transparent => write!(f, "transparent"),
+        }
+    }
+}
+
+/// A deployed proxy and the implementation it currently points to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyRecord {
+    pub kind: ProxyKind,
+    pub address: Address,
+    pub implementation: Address,
+    /// UUPS owner or transparent proxy admin
+    pub owner: Address,
+}
+
+/// Implementation the proxy at `proxy` currently delegates to
+pub async fn current_implementation(provider: &impl Provider, proxy: Address) -> Result<Address> {
+    let slot = provider
+        .get_storage_at(proxy, IMPLEMENTATION_SLOT.into())
+        .await?;
+    Ok(Address::from_word(slot.into()))
+}
+
+/// Deploy an implementation with its libraries, then a `kind` proxy owned by
+/// `owner`. The contract creations are returned in sending order.
+pub async fn deploy_proxy(
+    provider: &impl Provider,
+    kind: ProxyKind,
+    owner: Address,
+    options: &TxOptions,
+) -> Result<(ProxyRecord, ImplementationRecord, Vec<DeployedContract>)> {
+    let linked = deploy_linked(provider, &kind.implementation(), options).await?;
+    let implementation = linked.address;
+    let mut transactions = linked.transactions;
+
+    let proxy = kind.proxy();
+    let mut bytecode = proxy.linked_bytecode(&Default::default())?.to_vec();
+    bytecode.extend(kind.constructor_args(implementation, owner));
+    let deployed = deploy_code(provider, &proxy, bytecode.into(), options).await?;
+    let address = deployed.address;
+    transactions.push(deployed);
+    if current_implementation(provider, address).await? != implementation {
+        bail!(
+            "{} at {:?} does not point to the implementation",
+            proxy.name,
+            address
+        );
+    }
+
+    let record = ImplementationRecord {
+        implementation,
+        libraries: linked.libraries,
+        block_number: provider.get_block_number().await.ok(),
+        fixture: None,
+        tx_hash: None,
+    };
+    Ok((
+        ProxyRecord {
+            kind,
+            address,
+            implementation,
+            owner,
+        },
+        record,
+        transactions,
+    ))
+}
+
+/// Deploy a new implementation, check it accepts `fixture`, and switch `proxy` to it.
+///
+/// `provider` must sign as the proxy owner. The proxy is left untouched when
+/// the new implementation rejects the fixture.
+#[instrument(name = "upgrade", skip_all, fields(proxy = %proxy.address, kind = %proxy.kind))]
+pub async fn upgrade_proxy(
+    provider: &impl Provider,
+    proxy: &ProxyRecord,
+    fixture: &ProofFixture,
+    options: &TxOptions,
+) -> Result<ImplementationRecord> {
+    let linked = deploy_linked(provider, &proxy.kind.implementation(), options).await?;
+    let implementation = linked.address;
+
+    info!(fixture = %fixture.description, "Checking fixture");
+    if !fixture.is_accepted_by(provider, implementation).await? {
+        bail!(
+            "New implementation at {:?} rejects the fixture; proxy not upgraded",
+            implementation
+        );
+    }
+
+    let upgrade = IUpgradeableVerifier::new(proxy.address, provider)
+        .upgradeTo(implementation)
+        .into_transaction_request();
+    let receipt = send_transaction(provider, upgrade, options).await?;
+    if !receipt.status() {
+        bail!("Upgrade transaction reverted; is the signer the proxy owner?");
+    }
+
+    if current_implementation(provider, proxy.address).await? != implementation {
+        bail!("Proxy at {:?} did not switch implementation", proxy.address);
+    }
+    if !fixture.is_accepted_by(provider, proxy.address).await? {
+        bail!(
+            "Proxy at {:?} rejects the fixture after the upgrade",
+            proxy.address
+        );
+    }
+    info!(%implementation, "Proxy upgraded");
+
+    Ok(ImplementationRecord {
+        implementation,
+        libraries: linked.libraries,
+        block_number: receipt.block_number,
+        fixture: Some(fixture.description.clone()),
+        tx_hash: Some(receipt.transaction_hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deploy::STWOVerifierDeployer;
+    use crate::manifest::DeploymentManifest;
+    use anyhow::anyhow;
+    use contracts::hash::Keccak;
+    use contracts::prover::prove_for_solidity;
+    use num_traits::Zero;
+    use stwo::core::fields::m31::BaseField;
+    use stwo::core::fields::qm31::SecureField;
+    use stwo::core::pcs::PcsConfig;
+    use stwo::core::poly::circle::CanonicCoset;
+    use stwo::prover::backend::simd::SimdBackend;
+    use stwo::prover::backend::{Col, Column};
+    use stwo::prover::poly::circle::CircleEvaluation;
+    use stwo_constraint_framework::{
+        EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+    };
+
+    const LOG_SIZE: u32 = 5;
+
+    /// `a^2 = b` on every row
+    struct SquareEval;
+
+    impl FrameworkEval for SquareEval {
+        fn log_size(&self) -> u32 {
+            LOG_SIZE
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            LOG_SIZE + 1
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = eval.next_trace_mask();
+            let b = eval.next_trace_mask();
+            eval.add_constraint(a.clone() * a - b);
+            eval
+        }
+    }
+
+    /// Fixture of a real proof every verifier implementation accepts
+    fn square_fixture() -> Result<ProofFixture> {
+        let mut a = Col::<SimdBackend, BaseField>::zeros(1 << LOG_SIZE);
+        let mut b = Col::<SimdBackend, BaseField>::zeros(1 << LOG_SIZE);
+        for row in 0..1 << LOG_SIZE {
+            let value = BaseField::from(row as u32 + 2);
+            a.set(row, value);
+            b.set(row, value * value);
+        }
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let component = FrameworkComponent::new(
+            &mut TraceLocationAllocator::default(),
+            SquareEval,
+            SecureField::zero(),
+        );
+        let input = prove_for_solidity::<Keccak, _>(
+            vec![component],
+            vec![],
+            vec![
+                CircleEvaluation::new(domain, a),
+                CircleEvaluation::new(domain, b),
+            ],
+            vec![],
+            PcsConfig::default(),
+        )
+        .map_err(|e| anyhow!("Proving the fixture failed: {}", e))?;
+        Ok(ProofFixture::from_input("square 2^5 rows", &input))
+    }
+
+    #[test]
+    fn test_proxy_kind_parsing() {
+        assert_eq!("UUPS".parse::<ProxyKind>().unwrap(), ProxyKind::Uups);
+        assert_eq!(
+            "transparent".parse::<ProxyKind>().unwrap(),
+            ProxyKind::Transparent
+        );
+        assert!("beacon".parse::<ProxyKind>().is_err());
+        assert_eq!(
+            serde_json::to_string(&ProxyKind::Transparent).unwrap(),
+            "\"transparent\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_rejects_failing_fixture() -> Result<()> {
+        for kind in [ProxyKind::Uups, ProxyKind::Transparent] {
+            let deployer = STWOVerifierDeployer::with_anvil()?;
+            let result = deployer.deploy_proxied(kind, None).await?;
+            let proxy = result.proxy.clone().unwrap();
+            assert_eq!(result.verifier_address, proxy.address);
+
+            let fixture = ProofFixture {
+                description: "garbage".to_string(),
+                calldata: Bytes::from(vec![0u8; 4]),
+            };
+            assert!(deployer.upgrade(&proxy, &fixture).await.is_err());
+
+            let provider = deployer.create_provider().await?;
+            assert_eq!(
+                current_implementation(&provider, proxy.address).await?,
+                proxy.implementation
+            );
+            assert!(deployer
+                .check_implementation(&kind.implementation(), proxy.implementation)
+                .await?
+                .is_intact());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_accepts_fixture() -> Result<()> {
+        let fixture = square_fixture()?;
+        for kind in [ProxyKind::Uups, ProxyKind::Transparent] {
+            let deployer = STWOVerifierDeployer::with_anvil()?;
+            let result = deployer.deploy_proxied(kind, None).await?;
+            let proxy = result.proxy.clone().unwrap();
+            let provider = deployer.create_provider().await?;
+            assert!(fixture.is_accepted_by(&provider, proxy.address).await?);
+            let mut manifest = DeploymentManifest::from_result(&result);
+
+            let record = deployer.upgrade(&proxy, &fixture).await?;
+            assert_ne!(record.implementation, proxy.implementation);
+            assert_eq!(record.fixture.as_ref(), Some(&fixture.description));
+            assert!(record.tx_hash.is_some());
+            assert_eq!(
+                current_implementation(&provider, proxy.address).await?,
+                record.implementation
+            );
+            assert!(fixture.is_accepted_by(&provider, proxy.address).await?);
+
+            manifest.record_upgrade(record.clone())?;
+            assert_eq!(manifest.verifier_address, proxy.address);
+            assert_eq!(
+                manifest.proxy.as_ref().unwrap().implementation,
+                record.implementation
+            );
+            assert_eq!(manifest.history.len(), 2);
+            assert_eq!(manifest.history[0].implementation, proxy.implementation);
+            assert_eq!(manifest.history[1], record);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Where the deployer's signing key comes from.
+//!
+//! Keys never appear on the command line: a raw key or mnemonic is read from a
+//! named environment variable, and a keystore password from an environment
+//! variable or an interactive prompt.
+
+use std::path::PathBuf;
+
+use alloy::signers::local::{coins_bip39::English, MnemonicBuilder, PrivateKeySigner};
+use anyhow::{anyhow, bail, Result};
+use clap::{Arg, ArgMatches};
+use serde::Deserialize;
+
+/// Default BIP-44 path of the first Ethereum account
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Source of the deployment key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerSource {
+    /// Hex private key in the environment variable
+    PrivateKeyEnv(String),
+    /// Encrypted JSON keystore (Web3 Secret Storage)
+    Keystore {
+        path: PathBuf,
+        /// Environment variable holding the password; prompt when `None`
+        password_env: Option<String>,
+    },
+    /// BIP-39 phrase in the environment variable, derived at `derivation_path`
+    Mnemonic {
+        phrase_env: String,
+        derivation_path: String,
+    },
+}
+
+impl SignerSource {
+    /// Command line options selecting a signer, shared by the binaries
+    pub fn args() -> [Arg; 5] {
+        [
+            Arg::new("private-key-env")
+                .long("private-key-env")
+                .help("Environment variable holding the hex private key")
+                .value_name("VAR"),
+            Arg::new("keystore")
+                .long("keystore")
+                .help("Encrypted JSON keystore to sign with")
+                .value_name("PATH"),
+            Arg::new("password-env")
+                .long("password-env")
+                .help("Environment variable holding the keystore password (default: prompt)")
+                .value_name("VAR")
+                .requires("keystore"),
+            Arg::new("mnemonic-env")
+                .long("mnemonic-env")
+                .help("Environment variable holding a BIP-39 mnemonic")
+                .value_name("VAR"),
+            Arg::new("derivation-path")
+                .long("derivation-path")
+                .help("Derivation path for --mnemonic-env")
+                .value_name("PATH")
+                .default_value(DEFAULT_DERIVATION_PATH),
+        ]
+    }
+
+    /// Signer selected by [`SignerSource::args`], `None` when no option is given
+    pub fn from_matches(matches: &ArgMatches) -> Result<Option<Self>> {
+        SignerConfig::from_matches(matches).source()
+    }
+
+    /// Resolve the source into a signer
+    pub fn load(&self) -> Result<PrivateKeySigner> {
+        match self {
+            SignerSource::PrivateKeyEnv(var) => {
+                let key = read_env(var)?;
+                key.trim()
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid private key in {}: {}", var, e))
+            }
+            SignerSource::Keystore { path, password_env } => {
+                let password = match password_env {
+                    Some(var) => read_env(var)?,
+                    None => {
+                        rpassword::prompt_password(format!("🔑 Password for {}: ", path.display()))?
+                    }
+                };
+                PrivateKeySigner::decrypt_keystore(path, password)
+                    .map_err(|e| anyhow!("Cannot decrypt keystore {}: {}", path.display(), e))
+            }
+            SignerSource::Mnemonic {
+                phrase_env,
+                derivation_path,
+            } => {
+                let phrase = read_env(phrase_env)?;
+                mnemonic_signer(phrase.trim(), derivation_path)
+                    .map_err(|e| anyhow!("Invalid mnemonic in {}: {}", phrase_env, e))
+            }
+        }
+    }
+}
+
+/// Account of the BIP-39 `phrase` at `derivation_path`
+pub fn mnemonic_signer(phrase: &str, derivation_path: &str) -> Result<PrivateKeySigner> {
+    Ok(MnemonicBuilder::<English>::default()
+        .phrase(phrase)
+        .derivation_path(derivation_path)?
+        .build()?)
+}
+
+/// Signer fields of a network profile or of the command line; at most one of
+/// `private_key_env`, `keystore` and `mnemonic_env` may be set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignerConfig {
+    pub private_key_env: Option<String>,
+    pub keystore: Option<PathBuf>,
+    pub password_env: Option<String>,
+    pub mnemonic_env: Option<String>,
+    pub derivation_path: Option<String>,
+}
+
+impl SignerConfig {
+    /// Fields given with [`SignerSource::args`]
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        let option = |name: &str| matches.get_one::<String>(name).cloned();
+        Self {
+            private_key_env: option("private-key-env"),
+            keystore: option("keystore").map(PathBuf::from),
+            password_env: option("password-env"),
+            mnemonic_env: option("mnemonic-env"),
+            derivation_path: option("derivation-path"),
+        }
+    }
+
+    /// The selected source, `None` when no key field is set
+    pub fn source(&self) -> Result<Option<SignerSource>> {
+        let sources = [
+            self.private_key_env.is_some(),
+            self.keystore.is_some(),
+            self.mnemonic_env.is_some(),
+        ];
+        if sources.into_iter().filter(|&set| set).count() > 1 {
+            bail!("Use only one of private-key-env, keystore and mnemonic-env");
+        }
+
+        Ok(if let Some(var) = &self.private_key_env {
+            Some(SignerSource::PrivateKeyEnv(var.clone()))
+        } else if let Some(path) = &self.keystore {
+            Some(SignerSource::Keystore {
+                path: path.clone(),
+                password_env: self.password_env.clone(),
+            })
+        } else {
+            self.mnemonic_env
+                .as_ref()
+                .map(|var| SignerSource::Mnemonic {
+                    phrase_env: var.clone(),
+                    derivation_path: self
+                        .derivation_path
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string()),
+                })
+        })
+    }
+}
+
+fn read_env(var: &str) -> Result<String> {
+    std::env::var(var).map_err(|_| anyhow!("Environment variable {} is not set", var))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Anvil's default mnemonic and its first two accounts
+    const ANVIL_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_signer_sources() {
+        std::env::set_var("STWO_TEST_MNEMONIC", ANVIL_MNEMONIC);
+        let first = SignerSource::Mnemonic {
+            phrase_env: "STWO_TEST_MNEMONIC".to_string(),
+            derivation_path: DEFAULT_DERIVATION_PATH.to_string(),
+        }
+        .load()
+        .unwrap();
+        assert_eq!(
+            first.address().to_string(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        let second = SignerSource::Mnemonic {
+            phrase_env: "STWO_TEST_MNEMONIC".to_string(),
+            derivation_path: "m/44'/60'/0'/0/1".to_string(),
+        }
+        .load()
+        .unwrap();
+        assert_eq!(
+            second.address().to_string(),
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+        );
+
+        std::env::set_var(
+            "STWO_TEST_PRIVATE_KEY",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        );
+        let key = SignerSource::PrivateKeyEnv("STWO_TEST_PRIVATE_KEY".to_string())
+            .load()
+            .unwrap();
+        assert_eq!(key.address(), first.address());
+
+        assert!(SignerSource::PrivateKeyEnv("STWO_TEST_UNSET".to_string())
+            .load()
+            .is_err());
+    }
+}
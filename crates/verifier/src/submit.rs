@@ -0,0 +1,246 @@
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Address, TxHash},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    signers::local::PrivateKeySigner,
+    sol_types::SolCall,
+};
+use anyhow::Result;
+use contracts::{
+    packed::{calldata_gas, encode_packed_proof},
+    STWOVerifier, VerifierInput,
+};
+use serde::Serialize;
+use tracing::{error, info, instrument, warn};
+
+use crate::deploy::STWOVerifierDeployer;
+use crate::tx::{send_transaction, TxOptions};
+
+/// Submit `verifier_input` to a deployed verifier and report the transaction cost.
+/// With `packed` the proof goes through `verifyPacked`, otherwise through `verify`.
+#[instrument(name = "submit", skip_all, fields(verifier = %verifier_address, packed = packed))]
+pub async fn submit_verification(
+    verifier_address: Address,
+    verifier_input: VerifierInput,
+    deployer: &STWOVerifierDeployer,
+    packed: bool,
+) -> Result<VerificationReport> {
+    // Get deployment info to reuse the same Anvil instance
+    let deployment_info = deployer.get_info();
+    let rpc_url = deployment_info.rpc_url.parse()?;
+
+    // Use the same private key as deployer (Anvil's default account #0)
+    let signer: PrivateKeySigner = deployer.get_signer();
+
+    let wallet = EthereumWallet::from(signer);
+    let provider = ProviderBuilder::new().wallet(wallet).connect_http(rpc_url);
+
+    // Create contract instance
+    let contract = STWOVerifier::new(verifier_address, &provider);
+
+    // Compare calldata cost of both encodings
+    let abi_calldata = STWOVerifier::verifyCall {
+        proof: verifier_input.proof.clone(),
+        params: verifier_input.verificationParams.clone(),
+        treeRoots: verifier_input.treeRoots.clone(),
+        treeColumnLogSizes: verifier_input.treeColumnLogSizes.clone(),
+        digest: verifier_input.digest,
+        nDraws: verifier_input.nDraws,
+    }
+    .abi_encode();
+    let packed_proof = encode_packed_proof(&verifier_input.proof).map_err(anyhow::Error::msg)?;
+    let packed_calldata = STWOVerifier::verifyPackedCall {
+        packedProof: packed_proof.clone(),
+        params: verifier_input.verificationParams.clone(),
+        treeRoots: verifier_input.treeRoots.clone(),
+        treeColumnLogSizes: verifier_input.treeColumnLogSizes.clone(),
+        digest: verifier_input.digest,
+        nDraws: verifier_input.nDraws,
+    }
+    .abi_encode();
+
+    info!(
+        abi_bytes = abi_calldata.len(),
+        abi_gas = calldata_gas(&abi_calldata),
+        packed_bytes = packed_calldata.len(),
+        packed_gas = calldata_gas(&packed_calldata),
+        "Calldata encodings"
+    );
+
+    let (receipt, passed) = if packed {
+        let verification_call = contract.verifyPacked(
+            packed_proof,
+            verifier_input.verificationParams.clone(),
+            verifier_input.treeRoots.clone(),
+            verifier_input.treeColumnLogSizes.clone(),
+            verifier_input.digest,
+            verifier_input.nDraws,
+        );
+        let receipt = send_verification(
+            &provider,
+            verification_call.clone().into_transaction_request(),
+            &deployer.tx_options(),
+        )
+        .await?;
+        // To get the actual return value, we need to call the view function
+        let passed = receipt.status() && verification_call.call().await?;
+        (receipt, passed)
+    } else {
+        let verification_call = contract.verify(
+            verifier_input.proof.clone(),
+            verifier_input.verificationParams.clone(),
+            verifier_input.treeRoots.clone(),
+            verifier_input.treeColumnLogSizes.clone(),
+            verifier_input.digest,
+            verifier_input.nDraws,
+        );
+        let receipt = send_verification(
+            &provider,
+            verification_call.clone().into_transaction_request(),
+            &deployer.tx_options(),
+        )
+        .await?;
+        let passed = receipt.status() && verification_call.call().await?;
+        (receipt, passed)
+    };
+
+    let gas_price = receipt.effective_gas_price;
+    let gas_cost_wei = receipt.gas_used as u128 * gas_price;
+    if !receipt.status() {
+        warn!(tx_hash = %receipt.transaction_hash, "Verification transaction reverted");
+    }
+    info!(
+        gas_used = receipt.gas_used,
+        gas_price,
+        cost_wei = gas_cost_wei,
+        passed,
+        "Verification submitted"
+    );
+    let calldata = if packed {
+        packed_calldata
+    } else {
+        abi_calldata
+    };
+    Ok(VerificationReport {
+        calldata_size: calldata.len(),
+        calldata_gas: calldata_gas(&calldata),
+        gas_used: receipt.gas_used,
+        gas_price,
+        tx_hash: receipt.transaction_hash,
+        passed,
+    })
+}
+
+/// Estimate the gas of verifying one proof through both `verify` and
+/// `verifyPacked`, against the same chain state and without sending a transaction
+#[instrument(name = "compare", skip_all, fields(verifier = %verifier_address))]
+pub async fn compare_encodings(
+    verifier_address: Address,
+    verifier_input: &VerifierInput,
+    deployer: &STWOVerifierDeployer,
+) -> Result<EncodingComparison> {
+    let rpc_url = deployer.get_info().rpc_url.parse()?;
+    let from = deployer.get_signer().address();
+    let provider = ProviderBuilder::new().connect_http(rpc_url);
+    let contract = STWOVerifier::new(verifier_address, &provider);
+
+    let abi_call = contract
+        .verify(
+            verifier_input.proof.clone(),
+            verifier_input.verificationParams.clone(),
+            verifier_input.treeRoots.clone(),
+            verifier_input.treeColumnLogSizes.clone(),
+            verifier_input.digest,
+            verifier_input.nDraws,
+        )
+        .from(from);
+    let abi = EncodingCost {
+        calldata_size: abi_call.calldata().len(),
+        calldata_gas: calldata_gas(abi_call.calldata()),
+        gas_estimate: abi_call.estimate_gas().await?,
+        passed: abi_call.call().await?,
+    };
+
+    let packed_proof = encode_packed_proof(&verifier_input.proof).map_err(anyhow::Error::msg)?;
+    let packed_call = contract
+        .verifyPacked(
+            packed_proof,
+            verifier_input.verificationParams.clone(),
+            verifier_input.treeRoots.clone(),
+            verifier_input.treeColumnLogSizes.clone(),
+            verifier_input.digest,
+            verifier_input.nDraws,
+        )
+        .from(from);
+    let packed = EncodingCost {
+        calldata_size: packed_call.calldata().len(),
+        calldata_gas: calldata_gas(packed_call.calldata()),
+        gas_estimate: packed_call.estimate_gas().await?,
+        passed: packed_call.call().await?,
+    };
+
+    info!(
+        abi_gas = abi.gas_estimate,
+        packed_gas = packed.gas_estimate,
+        "Encoding gas estimates"
+    );
+    Ok(EncodingComparison { abi, packed })
+}
+
+/// Cost of one proof under one calldata encoding
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingCost {
+    pub calldata_size: usize,
+    pub calldata_gas: u64,
+    /// Estimated gas of the whole verification transaction
+    pub gas_estimate: u64,
+    pub passed: bool,
+}
+
+/// The same proof estimated through `verify` and `verifyPacked`
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingComparison {
+    pub abi: EncodingCost,
+    pub packed: EncodingCost,
+}
+
+/// Cost and outcome of a submitted verification transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationReport {
+    pub calldata_size: usize,
+    pub calldata_gas: u64,
+    pub gas_used: u64,
+    /// Effective price per gas in wei
+    pub gas_price: u128,
+    pub tx_hash: TxHash,
+    pub passed: bool,
+}
+
+impl VerificationReport {
+    /// Fail unless the verifier accepted the proof, so callers exit non-zero
+    /// after reporting a rejected proof
+    pub fn ensure_passed(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.passed,
+            "Proof rejected by the verifier (transaction {})",
+            self.tx_hash
+        );
+        Ok(())
+    }
+}
+
+/// Send a verification transaction and wait for its confirmations
+async fn send_verification(
+    provider: &impl Provider,
+    tx: TransactionRequest,
+    options: &TxOptions,
+) -> Result<TransactionReceipt> {
+    info!(
+        confirmations = options.confirmations,
+        "Sending verification transaction"
+    );
+    send_transaction(provider, tx, options)
+        .await
+        .inspect_err(|e| error!(error = %e, "Contract call failed"))
+}
@@ -0,0 +1,157 @@
+//! Sending transactions for deployment and proof submission.
+
+use std::time::{Duration, Instant};
+
+use alloy::{
+    consensus::Transaction,
+    primitives::TxHash,
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use anyhow::{anyhow, bail, Result};
+use tracing::{info, info_span, warn, Instrument};
+
+use crate::gas::{Fees, GasOptions};
+
+/// Interval between receipt polls while a replaceable transaction is pending
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How transactions are sent and when they count as mined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxOptions {
+    /// Blocks to wait for after inclusion, the including block counting as one
+    pub confirmations: u64,
+    pub gas: GasOptions,
+}
+
+impl Default for TxOptions {
+    fn default() -> Self {
+        Self {
+            confirmations: 1,
+            gas: GasOptions::default(),
+        }
+    }
+}
+
+/// Send `tx` priced by `options.gas` and wait for its receipt after
+/// `options.confirmations` blocks.
+///
+/// With `replace_after` set, a transaction still pending after the timeout is
+/// sent again with the same nonce and bumped fees, until one of the sent
+/// transactions is mined or the replacements or the fee cap run out.
+pub async fn send_transaction(
+    provider: &impl Provider,
+    mut tx: TransactionRequest,
+    options: &TxOptions,
+) -> Result<TransactionReceipt> {
+    let fees = options.gas.fees(provider).await?;
+    fees.apply(&mut tx);
+    let pending = provider.send_transaction(tx.clone()).await?;
+    let span = info_span!("receipt", tx_hash = %pending.tx_hash());
+    info!(parent: &span, fees = %fees, "Transaction sent");
+
+    let Some(timeout) = options.gas.replace_after else {
+        let receipt = pending
+            .with_required_confirmations(options.confirmations)
+            .get_receipt()
+            .instrument(span.clone())
+            .await?;
+        log_receipt(&span, &receipt);
+        return Ok(receipt);
+    };
+    wait_replacing(provider, tx, options, fees, *pending.tx_hash(), timeout)
+        .instrument(span.clone())
+        .await
+        .inspect(|receipt| log_receipt(&span, receipt))
+}
+
+/// Poll for a receipt of `first` or one of its replacements, replacing the
+/// latest after every `timeout`
+async fn wait_replacing(
+    provider: &impl Provider,
+    mut tx: TransactionRequest,
+    options: &TxOptions,
+    mut fees: Fees,
+    first: TxHash,
+    timeout: Duration,
+) -> Result<TransactionReceipt> {
+    // Replacements reuse the nonce and gas limit the provider filled in
+    let mut hashes: Vec<TxHash> = vec![first];
+    let sent = provider
+        .get_transaction_by_hash(hashes[0])
+        .await?
+        .ok_or_else(|| anyhow!("Node dropped transaction {:?}", hashes[0]))?;
+    tx.nonce = Some(sent.nonce());
+    tx.gas = Some(sent.gas_limit());
+
+    let mut replacements = 0;
+    let mut deadline = Instant::now() + timeout;
+    loop {
+        for hash in &hashes {
+            if let Some(receipt) = provider.get_transaction_receipt(*hash).await? {
+                wait_for_confirmations(provider, &receipt, options.confirmations).await?;
+                return Ok(receipt);
+            }
+        }
+
+        if Instant::now() >= deadline {
+            let last = hashes[hashes.len() - 1];
+            if replacements == options.gas.max_replacements {
+                bail!(
+                    "Transaction {:?} still pending after {} replacements",
+                    last,
+                    options.gas.max_replacements
+                );
+            }
+            let Some(bumped) = options.gas.replacement(&fees) else {
+                bail!(
+                    "Transaction {:?} still pending and the max fee cap leaves no room to replace it",
+                    last
+                );
+            };
+            fees = bumped;
+            fees.apply(&mut tx);
+            replacements += 1;
+            info!(pending = %last, fees = %fees, "Replacing pending transaction");
+            // The previous transaction may have been mined meanwhile; the
+            // receipts are checked again either way
+            match provider.send_transaction(tx.clone()).await {
+                Ok(pending) => {
+                    info!(tx_hash = %pending.tx_hash(), "Replacement sent");
+                    hashes.push(*pending.tx_hash());
+                }
+                Err(e) => warn!(error = %e, "Replacement not accepted"),
+            }
+            deadline = Instant::now() + timeout;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Log the outcome of a mined transaction in its `receipt` span
+fn log_receipt(span: &tracing::Span, receipt: &TransactionReceipt) {
+    info!(
+        parent: span,
+        mined = %receipt.transaction_hash,
+        block = receipt.block_number,
+        gas_used = receipt.gas_used,
+        success = receipt.status(),
+        "Transaction mined"
+    );
+}
+
+/// Wait until `confirmations` blocks, the including one counted, are on top of `receipt`
+async fn wait_for_confirmations(
+    provider: &impl Provider,
+    receipt: &TransactionReceipt,
+    confirmations: u64,
+) -> Result<()> {
+    let Some(included) = receipt.block_number else {
+        return Ok(());
+    };
+    while provider.get_block_number().await? + 1 < included + confirmations {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(())
+}